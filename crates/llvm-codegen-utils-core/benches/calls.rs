@@ -0,0 +1,165 @@
+//! Heap allocations and time per emitted call for `Builder::call` next to
+//! the fixed-arity `call0`–`call3` and to collecting the arguments into a
+//! `Vec`, as `call` did before it switched to a `SmallVec`. The one allocation
+//! left per call is the returned handle.
+//!
+//! Run with `cargo bench -p px-llvm-codegen-utils-core --bench calls --features llvm-sys-210`.
+#![allow(non_snake_case)]
+
+#[cfg(feature = "llvm-sys-210")]
+use llvm_sys_210 as llvm_sys;
+#[cfg(all(feature = "llvm-sys-200", not(feature = "llvm-sys-210")))]
+use llvm_sys_200 as llvm_sys;
+#[cfg(all(feature = "llvm-sys-190", not(any(feature = "llvm-sys-200", feature = "llvm-sys-210"))))]
+use llvm_sys_190 as llvm_sys;
+#[cfg(all(
+    feature = "llvm-sys-180",
+    not(any(feature = "llvm-sys-190", feature = "llvm-sys-200", feature = "llvm-sys-210"))
+))]
+use llvm_sys_180 as llvm_sys;
+#[cfg(all(
+    feature = "llvm-sys-170",
+    not(any(
+        feature = "llvm-sys-180",
+        feature = "llvm-sys-190",
+        feature = "llvm-sys-200",
+        feature = "llvm-sys-210"
+    ))
+))]
+use llvm_sys_170 as llvm_sys;
+#[cfg(all(
+    feature = "llvm-sys-160",
+    not(any(
+        feature = "llvm-sys-170",
+        feature = "llvm-sys-180",
+        feature = "llvm-sys-190",
+        feature = "llvm-sys-200",
+        feature = "llvm-sys-210"
+    ))
+))]
+use llvm_sys_160 as llvm_sys;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts every allocation and reallocation made through Rust's allocator.
+/// LLVM allocates through its own, so only the crate's overhead is counted.
+struct Counting;
+
+static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+#[cfg(any(
+    feature = "llvm-sys-160",
+    feature = "llvm-sys-170",
+    feature = "llvm-sys-180",
+    feature = "llvm-sys-190",
+    feature = "llvm-sys-200",
+    feature = "llvm-sys-210",
+))]
+fn main() {
+    use px_llvm_codegen_utils_core::*;
+    use std::time::{Duration, Instant};
+
+    type Context<'a> = LLHandle<'a, Normal, llvm_sys::LLVMContext>;
+    type Module<'a> = LLHandle<'a, Normal, llvm_sys::LLVMModule>;
+    type IrBuilder<'a> = LLHandle<'a, Normal, llvm_sys::LLVMBuilder>;
+    type Type<'a> = LLHandle<'a, Normal, llvm_sys::LLVMType>;
+    type Val<'a> = LLHandle<'a, Normal, llvm_sys::LLVMValue>;
+    type Kind = llvm_sys::LLVMValue;
+
+    const CALLS: u32 = 200_000;
+
+    /// Runs `emit` `CALLS` times, returning the allocations and time per call.
+    fn measure(mut emit: impl FnMut()) -> (f64, Duration) {
+        let allocs = ALLOCS.load(Ordering::Relaxed);
+        let start = Instant::now();
+        for _ in 0..CALLS {
+            emit();
+        }
+        let elapsed = start.elapsed() / CALLS;
+        let allocs = ALLOCS.load(Ordering::Relaxed) - allocs;
+        (allocs as f64 / CALLS as f64, elapsed)
+    }
+
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"calls", &ctx);
+    let i32 = Type::int_ty(ctx.clone(), 32);
+    let void = unsafe { Type::from_raw_leaked(llvm_sys::core::LLVMVoidTypeInContext(ctx.ptr())) };
+    let f = FunctionBuilder::new(
+        IrBuilder::new_in_ctx(ctx.clone()),
+        module.clone(),
+        c"caller",
+        void.fun_ty([].into_iter()),
+    );
+    let arg = Kind::const_int(i32.clone(), 1, false);
+
+    for arity in 0..=4 {
+        let ty = i32.clone().fun_ty(std::iter::repeat_n(i32.clone(), arity));
+        let name = std::ffi::CString::new(format!("callee{arity}")).unwrap();
+        let callee = Kind::declare(module.clone(), &name, ty.clone());
+        let callee = unsafe { Val::from_raw_leaked(callee.ptr()) };
+        let (ty, callee, a) = (&ty, &callee, &arg);
+
+        let vec = measure(|| {
+            let mut args = std::iter::repeat_n(a.clone(), arity).map(|a| a.ptr()).collect::<Vec<_>>();
+            let res = unsafe {
+                llvm_sys::core::LLVMBuildCall2(
+                    f.builder().ptr(),
+                    ty.ptr(),
+                    callee.ptr(),
+                    args.as_mut_ptr(),
+                    args.len() as u32,
+                    c"".as_ptr(),
+                )
+            };
+            drop(unsafe { Val::from_raw_leaked(res) });
+        });
+        let general = measure(|| {
+            f.call(ty.clone(), callee.clone(), std::iter::repeat_n(a.clone(), arity), c"");
+        });
+        let fixed = measure(|| match arity {
+            0 => drop(f.call0(ty.clone(), callee.clone(), c"")),
+            1 => drop(f.call1(ty.clone(), callee.clone(), a.clone(), c"")),
+            2 => drop(f.call2(ty.clone(), callee.clone(), a.clone(), a.clone(), c"")),
+            3 => drop(f.call3(ty.clone(), callee.clone(), a.clone(), a.clone(), a.clone(), c"")),
+            _ => {}
+        });
+        let fixed_name = format!("call{arity}");
+        let mut rows = vec![("Vec", vec), ("call", general)];
+        if arity <= 3 {
+            rows.push((&fixed_name, fixed));
+        }
+        for (method, (allocs, time)) in rows {
+            println!("{arity} args, {method:>5}: {allocs:.2} allocations, {time:>8.1?} per call");
+        }
+    }
+}
+
+#[cfg(not(any(
+    feature = "llvm-sys-160",
+    feature = "llvm-sys-170",
+    feature = "llvm-sys-180",
+    feature = "llvm-sys-190",
+    feature = "llvm-sys-200",
+    feature = "llvm-sys-210",
+)))]
+fn main() {
+    eprintln!("enable an `llvm-sys-*` feature to run this benchmark");
+}
@@ -0,0 +1,57 @@
+//! Clone/drop throughput of `LLHandle` on one and on several threads, next to
+//! the global `Mutex<BTreeMap>` refcount it replaced.
+//!
+//! Run with `cargo bench -p px-llvm-codegen-utils-core --bench handle`.
+
+use px_llvm_codegen_utils_core::{LLHandle, Normal};
+use std::collections::BTreeMap;
+use std::hint::black_box;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const ITERS: u32 = 2_000_000;
+
+/// The previous scheme: one global map from address to count.
+static COUNTS: Mutex<BTreeMap<usize, usize>> = Mutex::new(BTreeMap::new());
+
+fn global_map(addr: usize) {
+    for _ in 0..ITERS {
+        *COUNTS.lock().unwrap().entry(addr).or_default() += 1;
+        let mut counts = COUNTS.lock().unwrap();
+        let n = counts.get_mut(&addr).unwrap();
+        *n -= 1;
+        if *n == 0 {
+            counts.remove(&addr);
+        }
+    }
+}
+
+fn per_handle(_: usize) {
+    let mut obj = 0u8;
+    let handle = unsafe { LLHandle::<'_, Normal, u8>::from_raw_parts(&mut obj, |_, _| {}, Normal) };
+    for _ in 0..ITERS {
+        drop(black_box(handle.clone()));
+    }
+}
+
+/// Runs `f` on `threads` threads at once and returns the time per clone/drop pair.
+fn time(threads: usize, f: fn(usize)) -> Duration {
+    let start = Instant::now();
+    std::thread::scope(|s| {
+        for t in 0..threads {
+            s.spawn(move || f(t + 1));
+        }
+    });
+    start.elapsed() / ITERS
+}
+
+fn main() {
+    let threads = std::thread::available_parallelism().map_or(4, |n| n.get());
+    for (name, f) in [("global map", global_map as fn(usize)), ("per-handle Rc", per_handle)] {
+        println!(
+            "{name:>14}: {:>10.1?} per clone/drop on 1 thread, {:>10.1?} on {threads} threads",
+            time(1, f),
+            time(threads, f),
+        );
+    }
+}
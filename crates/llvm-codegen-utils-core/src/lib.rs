@@ -8,44 +8,164 @@
 //!
 //! - [`Ctx`] - LLVM context wrapper
 //! - [`Mod`] - LLVM module wrapper for organizing functions and global values
+//! - [`Comdat`] - Linker deduplication groups for globals
 //! - [`Value`] / [`ValueKind`] - Type-safe representations of LLVM values
 //! - [`Func`] - Function value wrapper
 //! - [`BB`] - Basic block wrapper for control flow
 //! - [`Ty`] - LLVM type wrapper with constructors for common types
 //! - [`Builder`] - IR builder providing methods for instruction generation
+//! - [`FunctionBuilder`] - A builder bundled with the function it emits into
+//! - [`eh`] - Typed exception-handling pads such as landing pads
+//! - [`Signature`] - A function's declared types, for checked calls
+//! - [`NameCache`] - Reusable `CStr` copies of names built at runtime
+//! - [`Metadata`] / [`DIBuilder`] - Metadata nodes and debug-info emission
+//! - [`Target`] / [`TargetMachine`] - Code generation to object files and assembly
+//! - [`TargetData`] - Type sizes and layouts under a data layout
+//! - [`Jit`] - In-process compilation and execution on ORC's LLJIT
+//! - [`FunctionPassManager`] - Legacy per-function optimization (LLVM 16 only)
 //! - [`LLHandle`] - Smart handle for LLVM resources with automatic cleanup
+//! - [`LLWeak`] - Non-owning companion to [`LLHandle`]
 //!
 //! ## LLVM Version Support
 //!
 //! This crate supports multiple LLVM versions through feature flags:
 //!
 //! <!-- GEN FEATURE_FLAGS -->
-//! - `llvm-sys-190` - LLVM 19
+//! - `llvm-sys-160` - LLVM 16
+//! - `llvm-sys-170` - LLVM 17
 //! - `llvm-sys-180` - LLVM 18
+//! - `llvm-sys-190` - LLVM 19
 //! - `llvm-sys-200` - LLVM 20
 //! - `llvm-sys-210` - LLVM 21
 //! <!-- RESUME -->
 //!
 //! Enable exactly one feature flag corresponding to your installed LLVM version.
+//!
+//! The selected version is exposed as `LLVM_VERSION`, and the version of the
+//! library actually linked as `runtime_version()`.
+//!
+//! The API is the same on every version, with a few exceptions:
+//!
+//! - Before LLVM 18, [`Value::set_tail_call_kind`] only supports
+//!   [`TailCallKind::None`] and [`TailCallKind::Tail`], and
+//!   [`Builder::zext_nneg`], [`Builder::or_disjoint`] and
+//!   [`Builder::gc_statepoint`] panic.
+//! - [`FunctionPassManager`] is only implemented on LLVM 16.
+//!
+//! ## inkwell Interop
+//!
+//! The `inkwell-interop` feature adds conversions between this crate's handles
+//! and the corresponding [inkwell](https://crates.io/crates/inkwell) wrappers.
+//! Each `llvm-sys-*` feature enables the matching `llvmXX-Y` feature of
+//! inkwell, so inkwell is always built for the same LLVM; with this feature,
+//! enable exactly one LLVM version.
+//!
+//! Handles converted from inkwell objects are leaked, since inkwell keeps
+//! ownership. In the other direction, converting an [`LLHandle`] to an inkwell
+//! `Module` hands the module over through [`LLHandle::try_into_raw`] and fails
+//! unless the handle owns it.
+//!
+//! ## Safe Checks
+//!
+//! Emitting an instruction after a block's terminator produces invalid IR that
+//! LLVM only reports much later, if it does not abort outright. The
+//! `safe-checks` feature makes [`Builder`] methods panic at the offending call
+//! instead, naming the block. The check costs a lookup per instruction, so it
+//! is meant for debug builds and tests. Builders positioned with
+//! [`Builder::position_before`] are exempt, as they insert ahead of the
+//! terminator. Emitting from a builder that is not positioned, e.g. after
+//! [`Builder::finish`], panics as well.
 
-use std::collections::BTreeMap;
 use std::ffi::CStr;
 use std::marker::PhantomData;
-use std::mem::{replace, take, transmute, MaybeUninit};
-use std::sync::{Arc, LazyLock, Mutex};
-use std::thread::LocalKey;
-use std::thread_local;
+use std::mem::ManuallyDrop;
+use std::rc::{Rc, Weak};
 
-use nonempty::NonEmpty;
-use typenum::Same;
 mod private {
     pub trait Sealed {}
+    /// Unchecked operations backing the typed handles in [`crate::eh`], which
+    /// only implement them for the matching instructions.
+    pub trait Eh<'a>: crate::Value<'a> {
+        fn add_clause<'b>(&self, clause: <Self::Kind as crate::ValueKind>::Val<'b, crate::Normal>);
+        fn add_filter<'b>(&self, type_infos: impl Iterator<Item = <Self::Kind as crate::ValueKind>::Val<'b, crate::Normal>>);
+        fn set_cleanup(&self, cleanup: bool);
+        fn add_handler<'b>(&self, dest: <<Self::Kind as crate::ValueKind>::Func<'b> as crate::Func<'b>>::BB);
+    }
 }
 
 /// Trait for LLVM context wrappers.
 ///
 /// The context is the top-level container for LLVM's internal state.
-pub trait Ctx<'a>: Clone + private::Sealed + 'a {}
+pub trait Ctx<'a>: Clone + private::Sealed + 'a {
+    /// Creates a fresh context, disposed of when the last handle to it is dropped.
+    ///
+    /// Modules and builders created from the handle hold on to it, so the
+    /// context outlives them. Types, values and blocks do not, and must not be
+    /// used once the context is gone.
+    fn create_context() -> Self;
+    /// This context type at an arbitrary lifetime, as passed to the closure of
+    /// [`Ctx::with_context`].
+    type Branded<'c>: Ctx<'c>;
+    /// Runs `f` with a fresh context, for one-shot compilations.
+    ///
+    /// `f` must accept the context at any lifetime `'c`, and every handle
+    /// derived from it carries `'c`, so neither the context nor its modules,
+    /// types or values can escape through `R`. The context is therefore
+    /// disposed of when `f` returns or unwinds. Return owned results instead,
+    /// such as emitted object code or the printed IR.
+    fn with_context<R>(f: impl for<'c> FnOnce(Self::Branded<'c>) -> R) -> R {
+        f(<Self::Branded<'_> as Ctx<'_>>::create_context())
+    }
+    /// Returns the ID of the metadata kind `name` (e.g. `range` or `tbaa`),
+    /// registering it if it is not a built-in kind.
+    fn metadata_kind_id(&self, name: &CStr) -> u32;
+    /// Routes diagnostics emitted in this context (e.g. by inline-asm parsing or
+    /// the backend) to `f` instead of LLVM's default printer, replacing any
+    /// previously installed handler.
+    ///
+    /// The handler lives until it is replaced or the context is disposed. It
+    /// must not panic, since it is called from LLVM.
+    fn set_diagnostic_handler(&self, f: impl FnMut(DiagnosticInfo) + 'static);
+    /// The module type of this context.
+    type Mod<'b>: Mod<'b>
+    where
+        Self: 'b;
+    /// Returns the live modules of this context, in creation order.
+    ///
+    /// LLVM keeps no list of a context's modules, so only modules created by
+    /// [`Mod::create_mod`], [`Mod::clone_module`] or `from_raw_owned` on this
+    /// thread are tracked, so leaked handles such as those converted from
+    /// inkwell never appear. Modules that have been dropped, linked into
+    /// another module or handed to a [`Jit`] are no longer listed.
+    fn modules(&self) -> Vec<Self::Mod<'a>>;
+    /// Returns the first module listed by [`Ctx::modules`] whose identifier is
+    /// `name`.
+    fn module(&self, name: &CStr) -> Option<Self::Mod<'a>>;
+}
+
+/// Severity of a [`DiagnosticInfo`].
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[non_exhaustive]
+pub enum DiagnosticSeverity {
+    /// An error.
+    Error,
+    /// A warning.
+    Warning,
+    /// An optimization remark.
+    Remark,
+    /// A note attached to a preceding diagnostic.
+    Note,
+}
+
+/// A diagnostic passed to the handler installed by [`Ctx::set_diagnostic_handler`].
+#[derive(Clone, Hash, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct DiagnosticInfo {
+    /// How severe the diagnostic is.
+    pub severity: DiagnosticSeverity,
+    /// The rendered diagnostic message.
+    pub message: String,
+}
 
 /// Trait for LLVM module wrappers.
 ///
@@ -61,6 +181,363 @@ pub trait Mod<'a>: Clone + private::Sealed + 'a {
     fn create_mod<'b, 'c, 'd>(a: &'b CStr, ctx: &'c Self::Ctx<'d>) -> Self
     where
         'a: 'b + 'c + 'd;
+    /// The type wrapper used for intrinsic overloads.
+    type Ty<'b>: Ty<'b>
+    where
+        Self: 'b;
+    /// The function type of declared intrinsics.
+    type Func<'b>: Func<'b>
+    where
+        Self: 'b;
+    /// Declares the intrinsic `name` (e.g. `c"llvm.ctpop"`) in this module,
+    /// instantiated at the given overload types, and returns the declaration.
+    ///
+    /// `overloads` lists the types of the overloaded (`any`) parameters in order;
+    /// `llvm.memcpy` for instance is overloaded on its two pointer types and its
+    /// length type. Returns `None` if `name` is not a known intrinsic.
+    fn intrinsic(
+        &self,
+        name: &CStr,
+        overloads: impl Iterator<Item = Self::Ty<'a>>,
+    ) -> Option<Self::Func<'a>>;
+    /// The target machine type passes can be specialized for.
+    type TargetMachine<'b>: TargetMachine<'b>
+    where
+        Self: 'b;
+    /// Runs a new-pass-manager pipeline over this module.
+    ///
+    /// `passes` uses the textual pipeline syntax accepted by `opt -passes=`, e.g.
+    /// `c"default<O2>"` or `c"mem2reg,instcombine"`. Returns LLVM's error message if
+    /// the pipeline cannot be parsed.
+    fn run_passes(
+        &self,
+        passes: &CStr,
+        target_machine: Option<&Self::TargetMachine<'a>>,
+    ) -> Result<(), String>;
+    /// Links `other` into this module, consuming it, and returns this module.
+    ///
+    /// A failed link can leave this module partially linked, so it is consumed
+    /// too; keep a [`Mod::clone_module`] copy to retry. On failure the linker's
+    /// error diagnostics are returned; other diagnostics still reach the
+    /// context's handler.
+    ///
+    /// Fails without linking if the modules live in different contexts, or if
+    /// `other` does not own its module or other handles to it are still alive.
+    fn link(self, other: Self) -> Result<Self, String>;
+    /// Creates a deep copy of this module in the same context, disposed of when
+    /// its last handle is dropped.
+    ///
+    /// Unlike [`Clone::clone`], which yields another handle to the same module,
+    /// changes to the copy do not affect this module.
+    fn clone_module(&self) -> Self;
+    /// The value type of aliases and ifuncs added to this module.
+    type Val<'b>: Value<'b, Tag = Normal>
+    where
+        Self: 'b;
+    /// Adds an alias `name` for `aliasee`, a global value or constant
+    /// expression whose value type is `ty`.
+    fn add_alias(
+        &self,
+        ty: Self::Ty<'a>,
+        addr_space: u32,
+        aliasee: Self::Val<'a>,
+        name: &CStr,
+    ) -> Self::Val<'a>;
+    /// Adds an indirect function `name` of function type `ty`.
+    ///
+    /// The dynamic loader calls `resolver` once to pick the implementation, so it
+    /// must be a defined function taking no parameters and returning a `ptr` to
+    /// a function of type `ty`.
+    fn add_ifunc(
+        &self,
+        ty: Self::Ty<'a>,
+        addr_space: u32,
+        resolver: Self::Func<'a>,
+        name: &CStr,
+    ) -> Self::Val<'a>;
+    /// The comdat type of this module.
+    type Comdat<'b>: Comdat<'b>
+    where
+        Self: 'b;
+    /// Returns the comdat `name` of this module, creating it (with
+    /// [`ComdatSelectionKind::Any`]) if it does not exist yet.
+    fn comdat(&self, name: &CStr) -> Self::Comdat<'a>;
+    /// Appends `asm` to this module's top-level inline assembly, e.g.
+    /// `c".globl trampoline"`. Successive calls are separated by newlines.
+    fn append_inline_asm(&self, asm: &CStr);
+    /// Returns this module's top-level inline assembly, or an empty string if
+    /// it has none.
+    #[doc(alias = "get_inline_asm")]
+    fn inline_asm(&self) -> String;
+}
+
+/// Trait for LLVM comdat wrappers.
+///
+/// A comdat groups sections that the linker keeps or discards together, which
+/// lets inline functions and vtables be emitted into several objects without
+/// duplicate-symbol errors. Globals are added with [`Value::set_comdat`].
+pub trait Comdat<'a>: Clone + private::Sealed + 'a {
+    /// Returns how the linker picks among duplicate copies of this comdat.
+    fn selection_kind(&self) -> ComdatSelectionKind;
+    /// Sets how the linker picks among duplicate copies of this comdat.
+    fn set_selection_kind(&self, kind: ComdatSelectionKind);
+}
+
+/// Comdat selection kinds for use with [`Comdat::set_selection_kind`].
+///
+/// Only COFF supports kinds other than `Any`; ELF and Wasm treat them all as
+/// `Any`.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+#[non_exhaustive]
+pub enum ComdatSelectionKind {
+    /// The linker may pick any copy.
+    #[default]
+    Any,
+    /// All copies must have identical contents.
+    ExactMatch,
+    /// The linker picks the largest copy.
+    Largest,
+    /// Duplicates are an error.
+    NoDeduplicate,
+    /// All copies must have the same size.
+    SameSize,
+}
+
+/// Trait for LLVM target wrappers.
+///
+/// A target is a backend such as x86 or AArch64. Targets must be initialized
+/// before they can be looked up; `init_native_target()` does so for the host.
+pub trait Target<'a>: Clone + private::Sealed + 'a {
+    /// The target machine type created from this target.
+    type TargetMachine<'b>: TargetMachine<'b>
+    where
+        Self: 'b;
+    /// Looks up the target for `triple` (e.g. `c"x86_64-unknown-linux-gnu"`).
+    ///
+    /// Returns LLVM's error message if no registered target matches.
+    fn from_triple(triple: &CStr) -> Result<Self, String>;
+    /// Creates a target machine generating code for `triple` on `cpu`.
+    ///
+    /// `cpu` and `features` may be empty to use the target's defaults; `features`
+    /// is a comma-separated list such as `c"+avx2,-sse4a"`.
+    ///
+    /// Fails if the target has no code generator, e.g. because only its target
+    /// info was initialized.
+    fn create_target_machine(
+        &self,
+        triple: &CStr,
+        cpu: &CStr,
+        features: &CStr,
+        opt_level: CodeGenOptLevel,
+        reloc: RelocMode,
+        code_model: CodeModel,
+    ) -> Result<Self::TargetMachine<'a>, String>;
+}
+
+/// Trait for LLVM target machine wrappers.
+///
+/// A target machine lowers modules to machine code for one target configuration.
+pub trait TargetMachine<'a>: Clone + private::Sealed + 'a {
+    /// The module type this machine emits code for.
+    type Mod<'b>: Mod<'b>
+    where
+        Self: 'b;
+    /// Compiles `module` and writes the result to the file at `path`.
+    ///
+    /// Returns LLVM's error message if the file cannot be written or the target
+    /// cannot emit `file_type`.
+    fn emit_to_file(
+        &self,
+        module: &Self::Mod<'a>,
+        file_type: FileType,
+        path: &CStr,
+    ) -> Result<(), String>;
+    /// Compiles `module` and returns the resulting bytes.
+    fn emit_to_memory(
+        &self,
+        module: &Self::Mod<'a>,
+        file_type: FileType,
+    ) -> Result<Vec<u8>, String>;
+}
+
+/// Code generation optimization levels for [`Target::create_target_machine`].
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+#[non_exhaustive]
+pub enum CodeGenOptLevel {
+    /// `-O0`.
+    None,
+    /// `-O1`.
+    Less,
+    /// `-O2`.
+    #[default]
+    Default,
+    /// `-O3`.
+    Aggressive,
+}
+
+/// Relocation models for [`Target::create_target_machine`].
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+#[non_exhaustive]
+pub enum RelocMode {
+    /// The target's default model.
+    #[default]
+    Default,
+    /// Non-relocatable code.
+    Static,
+    /// Position-independent code.
+    Pic,
+    /// Relocatable, but with absolute references to external symbols.
+    DynamicNoPic,
+}
+
+/// Code models for [`Target::create_target_machine`].
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+#[non_exhaustive]
+pub enum CodeModel {
+    /// The target's default model.
+    #[default]
+    Default,
+    /// The target's default model for JIT compilation.
+    JitDefault,
+    /// Tiny code model.
+    Tiny,
+    /// Small code model.
+    Small,
+    /// Kernel code model.
+    Kernel,
+    /// Medium code model.
+    Medium,
+    /// Large code model.
+    Large,
+}
+
+/// Output kinds for [`TargetMachine::emit_to_file`] and
+/// [`TargetMachine::emit_to_memory`].
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[non_exhaustive]
+pub enum FileType {
+    /// Textual assembly.
+    Assembly,
+    /// A relocatable object file.
+    Object,
+}
+
+/// Trait for LLVM target data wrappers.
+///
+/// Target data answers size, alignment and struct layout queries for types
+/// under a data layout string such as `c"e-m:e-i64:64-n8:16:32:64-S128"`.
+pub trait TargetData<'a>: Clone + private::Sealed + 'a {
+    /// The type wrapper queries are made about.
+    type Ty<'b>: Ty<'b>
+    where
+        Self: 'b;
+    /// Parses `layout` into target data, e.g. `c"e-i32:32-i64:64"`.
+    ///
+    /// Fails with LLVM's parse error if `layout` is malformed.
+    fn from_layout(layout: &CStr) -> Result<Self, String>;
+    /// Returns the ABI size of `ty` in bytes, including tail padding.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ty` is unsized.
+    fn size_of(&self, ty: Self::Ty<'a>) -> u64;
+    /// Returns the ABI alignment of `ty` in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ty` is unsized.
+    fn abi_align_of(&self, ty: Self::Ty<'a>) -> u32;
+    /// Returns the byte offset of field `index` within the struct type `struct_ty`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `struct_ty` is not a sized struct or has no field `index`.
+    fn offset_of_element(&self, struct_ty: Self::Ty<'a>, index: u32) -> u64;
+    /// Returns the index of the field of the struct type `struct_ty` containing
+    /// byte `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `struct_ty` is not a sized struct.
+    fn element_at_offset(&self, struct_ty: Self::Ty<'a>, offset: u64) -> u32;
+}
+
+/// Trait for LLVM JIT wrappers, built on ORC's `LLJIT`.
+///
+/// Modules added to the JIT are compiled lazily, on the first lookup of a
+/// symbol they define. The JIT keeps the contexts of the modules added to it
+/// alive until it is dropped.
+pub trait Jit<'a>: Clone + private::Sealed + 'a {
+    /// The module type the JIT compiles.
+    type Mod<'b>: Mod<'b>
+    where
+        Self: 'b;
+    /// Creates a JIT for the host.
+    ///
+    /// The native target must have been initialized with `init_native_target()`.
+    fn create() -> Result<Self, String>;
+    /// Transfers ownership of `module` to the JIT.
+    ///
+    /// Fails if `module` does not own its module (i.e. was not created by
+    /// [`Mod::create_mod`] or [`Mod::clone_module`]) or if other handles to it
+    /// are still alive. If the module's context was not created by
+    /// [`Ctx::create_context`] either, it must outlive the JIT.
+    fn add_module(&self, module: Self::Mod<'a>) -> Result<(), String>;
+    /// Looks up the address of the symbol `name`, compiling it if necessary.
+    fn lookup_address(&self, name: &CStr) -> Result<u64, String>;
+    /// Looks up the function `name` and returns it as the function pointer type `F`.
+    ///
+    /// # Safety
+    ///
+    /// `F` must be an `extern "C"` function pointer type matching the signature
+    /// of `name`, and must not be called after the JIT has been dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `F` is not pointer-sized.
+    unsafe fn lookup_fn<F: Copy>(&self, name: &CStr) -> Result<F, String> {
+        assert_eq!(
+            std::mem::size_of::<F>(),
+            std::mem::size_of::<usize>(),
+            "F must be a function pointer"
+        );
+        let addr = self.lookup_address(name)? as usize;
+        Ok(unsafe { std::mem::transmute_copy(&addr) })
+    }
+}
+
+/// Trait for legacy function pass managers, for quick per-function cleanups
+/// such as `instcombine` or `simplifycfg`.
+///
+/// Only implemented on LLVM 16: later versions removed the legacy pass
+/// constructors from the C API. Prefer [`Mod::run_passes`] with a pipeline
+/// like `c"instcombine,simplifycfg"`, which works on every version.
+///
+/// The pass manager keeps its module alive until it is dropped.
+pub trait FunctionPassManager<'a>: Clone + private::Sealed + 'a {
+    /// The module type the pass manager runs over.
+    type Mod<'b>: Mod<'b>
+    where
+        Self: 'b;
+    /// The function type the pass manager runs on.
+    type Func<'b>: Func<'b>
+    where
+        Self: 'b;
+    /// Creates an empty pass manager for the functions of `module`.
+    fn for_module(module: &Self::Mod<'a>) -> Self;
+    /// Adds the `instcombine` pass.
+    fn add_instruction_combining_pass(&self);
+    /// Adds the `simplifycfg` pass.
+    fn add_cfg_simplification_pass(&self);
+    /// Adds the `mem2reg` pass, promoting `alloca`s to SSA registers.
+    fn add_promote_memory_to_register_pass(&self);
+    /// Adds the `reassociate` pass.
+    fn add_reassociate_pass(&self);
+    /// Adds the `gvn` pass.
+    fn add_gvn_pass(&self);
+    /// Runs the added passes over `func`, which must belong to the pass
+    /// manager's module. Returns whether any pass changed it.
+    fn run(&self, func: Self::Func<'a>) -> bool;
 }
 
 /// Trait for LLVM value wrappers.
@@ -75,6 +552,352 @@ pub trait Value<'a>: Clone + private::Sealed + 'a {
     type Mod<'b>: Mod<'b>;
     /// Returns the module this value belongs to.
     fn r#mod<'b: 'a>(&'b self) -> Self::Mod<'b>;
+    /// Returns the type allocated by this value, if it is an `alloca` instruction.
+    ///
+    /// Under opaque pointers the result type of an `alloca` is always `ptr`, so this
+    /// is the only way to recover the allocated type (e.g. for a matching `Load2`).
+    fn allocated_type(&self) -> Option<<Self::Kind as ValueKind>::Ty<'a>>;
+    /// Returns the category of this value.
+    fn value_kind(&self) -> ValueClass;
+    /// Returns the opcode of this instruction, or `None` if it is not one.
+    fn opcode(&self) -> Option<Opcode>;
+    /// Recovers a typed function handle if this value is a function.
+    fn as_func(&self) -> Option<<Self::Kind as ValueKind>::Func<'a>>;
+    /// Recovers the basic block if this value is one (see [`BB::as_value`]).
+    fn as_basic_block(&self) -> Option<<<Self::Kind as ValueKind>::Func<'a> as Func<'a>>::BB>;
+    /// Attaches `!nontemporal !{i32 1}` to this load or store instruction.
+    fn set_nontemporal(&self);
+    /// Marks this call instruction as a (must-)tail call.
+    ///
+    /// # Panics
+    ///
+    /// Before LLVM 18 the C API can only toggle the plain `tail` marker, so
+    /// [`TailCallKind::MustTail`] and [`TailCallKind::NoTail`] panic there.
+    fn set_tail_call_kind(&self, kind: TailCallKind);
+    /// Attaches `node` as the `kind_id` metadata of this instruction or global
+    /// object (see [`Ctx::metadata_kind_id`]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if this value is neither an instruction nor a global object.
+    fn set_metadata<'b>(&self, kind_id: u32, node: <Self::Kind as ValueKind>::Metadata<'b>);
+    /// Returns the `kind_id` metadata attached to this instruction or global
+    /// object, if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this value is neither an instruction nor a global object.
+    fn metadata(&self, kind_id: u32) -> Option<<Self::Kind as ValueKind>::Metadata<'a>>;
+    /// Renames this value. LLVM appends a numeric suffix if the name is
+    /// already taken in the enclosing function or module, and an empty name
+    /// makes the value anonymous.
+    fn set_name(&self, name: &CStr);
+    /// Returns the name of this value, or `None` if it is anonymous.
+    fn name(&self) -> Option<String>;
+    /// Returns the type of this value.
+    ///
+    /// For functions and globals this is `ptr`; their value type is the type
+    /// they were declared with.
+    fn type_of(&self) -> <Self::Kind as ValueKind>::Ty<'a>;
+    /// Rewrites every use of this value to use `new` instead, e.g. after folding
+    /// an instruction to a constant. This value itself is left in place.
+    ///
+    /// `new` must have the same type as this value; this is checked in debug
+    /// builds.
+    fn replace_all_uses_with<'b>(&self, new: <Self::Kind as ValueKind>::Val<'b, Normal>);
+    /// Iterates over the operands of this instruction or constant expression.
+    ///
+    /// Block operands (e.g. of `br`) are yielded as values; see
+    /// [`Value::as_basic_block`].
+    fn operands(&self) -> impl Iterator<Item = <Self::Kind as ValueKind>::Val<'a, Normal>>;
+    /// Iterates over the users of this value, once per use, so an instruction
+    /// using it twice (e.g. `add %x, %x`) is yielded twice.
+    fn uses(&self) -> impl Iterator<Item = <Self::Kind as ValueKind>::Val<'a, Normal>>;
+    /// Removes this instruction from its block and deletes it.
+    ///
+    /// Uses of the instruction must be rewritten first (see
+    /// [`Value::replace_all_uses_with`]).
+    ///
+    /// # Safety
+    ///
+    /// The instruction is freed, so no other handle to it (e.g. a clone, or one
+    /// obtained from [`BB::instructions`]) may be used afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not an instruction or still has uses.
+    unsafe fn erase_from_parent(self);
+    /// Unlinks this instruction from its block without deleting it, returning
+    /// the detached instruction.
+    ///
+    /// The instruction is leaked unless it is inserted again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not an instruction.
+    fn remove_from_parent(self) -> Self;
+    /// Creates an unnamed copy of this instruction with the same operands, not
+    /// yet in any block. Place it with [`Builder::insert`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not an instruction.
+    fn clone_instruction(&self) -> <Self::Kind as ValueKind>::Val<'a, Normal>;
+    /// Makes this global variable thread-local with the given model, or a
+    /// regular global with [`ThreadLocalMode::NotThreadLocal`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not a global variable.
+    fn set_thread_local_mode(&self, mode: ThreadLocalMode);
+    /// Returns the thread-local model of this global variable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not a global variable.
+    fn thread_local_mode(&self) -> ThreadLocalMode;
+    /// Sets whether the address of this function or global variable is
+    /// significant.
+    fn set_unnamed_addr(&self, unnamed_addr: UnnamedAddr);
+    /// Returns whether the address of this function or global variable is
+    /// significant.
+    fn unnamed_addr(&self) -> UnnamedAddr;
+    /// Places this function or global variable in `comdat`.
+    fn set_comdat(&self, comdat: <Self::Mod<'a> as Mod<'a>>::Comdat<'a>);
+    /// Returns the comdat of this function or global variable, if any.
+    fn comdat(&self) -> Option<<Self::Mod<'a> as Mod<'a>>::Comdat<'a>>;
+    /// Places this function or global variable in the object file section `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not a global value.
+    fn set_section(&self, name: &CStr);
+    /// Returns the explicit section of this function or global variable, if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not a global value.
+    fn section(&self) -> Option<String>;
+    /// Sets the alignment in bytes of this function or global variable, or of
+    /// this `alloca`, `load` or `store` instruction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two or this is none of the above.
+    fn set_alignment(&self, align: u32);
+    /// Returns the zero-extended value of this constant integer.
+    ///
+    /// Returns `None` if this is not a constant integer or is wider than 64 bits.
+    fn const_int_value(&self) -> Option<u64>;
+    /// Returns the sign-extended value of this constant integer.
+    ///
+    /// Returns `None` if this is not a constant integer or is wider than 64 bits.
+    fn const_int_value_signed(&self) -> Option<i64>;
+}
+
+/// Value categories reported by [`Value::value_kind`], mirroring `LLVMValueKind`.
+///
+/// Categories not modelled here (e.g. ones added by newer LLVM versions) are
+/// reported as [`ValueClass::Other`].
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[non_exhaustive]
+pub enum ValueClass {
+    /// A function argument.
+    Argument,
+    /// A basic block used as a value.
+    BasicBlock,
+    /// A `MemorySSA` use.
+    MemoryUse,
+    /// A `MemorySSA` definition.
+    MemoryDef,
+    /// A `MemorySSA` phi.
+    MemoryPhi,
+    /// A function.
+    Function,
+    /// A global alias.
+    GlobalAlias,
+    /// A global indirect function.
+    GlobalIFunc,
+    /// A global variable.
+    GlobalVariable,
+    /// A `blockaddress` constant.
+    BlockAddress,
+    /// A constant expression.
+    ConstantExpr,
+    /// A constant array.
+    ConstantArray,
+    /// A constant struct.
+    ConstantStruct,
+    /// A constant vector.
+    ConstantVector,
+    /// An `undef` value.
+    UndefValue,
+    /// A `zeroinitializer` aggregate.
+    ConstantAggregateZero,
+    /// A constant data array.
+    ConstantDataArray,
+    /// A constant data vector.
+    ConstantDataVector,
+    /// A constant integer.
+    ConstantInt,
+    /// A constant floating-point value.
+    ConstantFP,
+    /// A null pointer constant.
+    ConstantPointerNull,
+    /// A `none` token constant.
+    ConstantTokenNone,
+    /// Metadata wrapped as a value.
+    MetadataAsValue,
+    /// An inline assembly value.
+    InlineAsm,
+    /// An instruction.
+    Instruction,
+    /// A `poison` value.
+    PoisonValue,
+    /// A target extension type `zeroinitializer`.
+    ConstantTargetNone,
+    /// Any other kind of value.
+    Other,
+}
+
+/// Instruction opcodes reported by [`Value::opcode`], mirroring `LLVMOpcode`.
+///
+/// Opcodes not modelled here are reported as [`Opcode::Other`].
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[non_exhaustive]
+pub enum Opcode {
+    /// `ret`
+    Ret,
+    /// `br`
+    Br,
+    /// `switch`
+    Switch,
+    /// `indirectbr`
+    IndirectBr,
+    /// `invoke`
+    Invoke,
+    /// `unreachable`
+    Unreachable,
+    /// `callbr`
+    CallBr,
+    /// `fneg`
+    FNeg,
+    /// `add`
+    Add,
+    /// `fadd`
+    FAdd,
+    /// `sub`
+    Sub,
+    /// `fsub`
+    FSub,
+    /// `mul`
+    Mul,
+    /// `fmul`
+    FMul,
+    /// `udiv`
+    UDiv,
+    /// `sdiv`
+    SDiv,
+    /// `fdiv`
+    FDiv,
+    /// `urem`
+    URem,
+    /// `srem`
+    SRem,
+    /// `frem`
+    FRem,
+    /// `shl`
+    Shl,
+    /// `lshr`
+    LShr,
+    /// `ashr`
+    AShr,
+    /// `and`
+    And,
+    /// `or`
+    Or,
+    /// `xor`
+    Xor,
+    /// `alloca`
+    Alloca,
+    /// `load`
+    Load,
+    /// `store`
+    Store,
+    /// `getelementptr`
+    GetElementPtr,
+    /// `trunc`
+    Trunc,
+    /// `zext`
+    ZExt,
+    /// `sext`
+    SExt,
+    /// `fptoui`
+    FPToUI,
+    /// `fptosi`
+    FPToSI,
+    /// `uitofp`
+    UIToFP,
+    /// `sitofp`
+    SIToFP,
+    /// `fptrunc`
+    FPTrunc,
+    /// `fpext`
+    FPExt,
+    /// `ptrtoint`
+    PtrToInt,
+    /// `inttoptr`
+    IntToPtr,
+    /// `bitcast`
+    BitCast,
+    /// `addrspacecast`
+    AddrSpaceCast,
+    /// `icmp`
+    ICmp,
+    /// `fcmp`
+    FCmp,
+    /// `phi`
+    Phi,
+    /// `call`
+    Call,
+    /// `select`
+    Select,
+    /// `va_arg`
+    VAArg,
+    /// `extractelement`
+    ExtractElement,
+    /// `insertelement`
+    InsertElement,
+    /// `shufflevector`
+    ShuffleVector,
+    /// `extractvalue`
+    ExtractValue,
+    /// `insertvalue`
+    InsertValue,
+    /// `freeze`
+    Freeze,
+    /// `fence`
+    Fence,
+    /// `cmpxchg`
+    AtomicCmpXchg,
+    /// `atomicrmw`
+    AtomicRMW,
+    /// `resume`
+    Resume,
+    /// `landingpad`
+    LandingPad,
+    /// `cleanupret`
+    CleanupRet,
+    /// `catchret`
+    CatchRet,
+    /// `catchpad`
+    CatchPad,
+    /// `cleanuppad`
+    CleanupPad,
+    /// `catchswitch`
+    CatchSwitch,
+    /// Any other opcode.
+    Other,
 }
 
 /// Trait for classifying LLVM value kinds.
@@ -91,18 +914,242 @@ pub trait ValueKind: private::Sealed {
     type Func<'a>: for<'b> Func<'a, Kind = Self, Mod<'b> = Self::Mod<'b>>;
     /// The LLVM type wrapper.
     type Ty<'a>: Ty<'a>;
+    /// The metadata wrapper.
+    type Metadata<'a>: Metadata<'a>;
+    /// The context wrapper.
+    type Ctx<'a>: Ctx<'a>;
+    /// Wraps a value (usually a constant) as metadata, e.g. for use in a node.
+    fn value_as_metadata<'a>(val: Self::Val<'a, Normal>) -> Self::Metadata<'a>;
     /// Creates a constant integer value.
+    ///
+    /// `n` only has 64 bits: for wider types such as `i128` the high bits are
+    /// zero, or copies of bit 63 if `sext` is set. Use [`ValueKind::const_u128`]
+    /// or [`ValueKind::const_int_of_words`] for full-width values.
     fn const_int<'a>(ty: Self::Ty<'a>, n: u64, sext: bool) -> Self::Val<'a, Normal>;
+    /// Creates a constant of integer type `ty` from all 128 bits of `n`,
+    /// truncated if `ty` is narrower.
+    fn const_u128<'a>(ty: Self::Ty<'a>, n: u128) -> Self::Val<'a, Normal> {
+        Self::const_int_of_words(ty, &[n as u64, (n >> 64) as u64])
+    }
+    /// Creates an `i1` constant, `true` or `false`.
+    fn const_bool<'a>(ctx: Self::Ctx<'a>, b: bool) -> Self::Val<'a, Normal>;
+    /// Creates an `i32` constant.
+    fn const_i32<'a>(ctx: Self::Ctx<'a>, n: i32) -> Self::Val<'a, Normal>;
+    /// Creates an `i64` constant.
+    fn const_i64<'a>(ctx: Self::Ctx<'a>, n: i64) -> Self::Val<'a, Normal>;
+    /// Creates a constant integer of the same type as `val`, e.g. the `1` in
+    /// `add(x, const_like(x, 1))`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `val` is not an integer.
+    fn const_like<'a>(val: Self::Val<'a, Normal>, n: u64) -> Self::Val<'a, Normal>;
+    /// Parses `s` in base `radix` into a constant of integer type `ty`, which may
+    /// be wider than 64 bits. A leading `-` negates the value in two's complement.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not 2, 8, 10 or 16, if `ty` is not an integer type,
+    /// or if `s` has no digits, a digit outside `radix`, or a magnitude that
+    /// does not fit in `ty`.
+    fn const_int_of_string<'a>(ty: Self::Ty<'a>, s: &CStr, radix: u8) -> Self::Val<'a, Normal>;
+    /// Creates a constant of integer type `ty` from little-endian 64-bit `words`,
+    /// for integers wider than 64 bits.
+    fn const_int_of_words<'a>(ty: Self::Ty<'a>, words: &[u64]) -> Self::Val<'a, Normal>;
+    /// Creates the all-zero constant of `ty`.
+    ///
+    /// For arrays, structs and vectors this is a single `zeroinitializer`, so
+    /// zeroing a large global doesn't require building a nested constant. For
+    /// scalars it is `0`, `0.0` or `null`.
+    fn const_zero<'a>(ty: Self::Ty<'a>) -> Self::Val<'a, Normal>;
+    /// Creates a constant array of `elem_ty` elements.
+    ///
+    /// The element type is given explicitly so that zero-length arrays can be built.
+    fn const_array<'a>(
+        elem_ty: Self::Ty<'a>,
+        vals: impl Iterator<Item = Self::Val<'a, Normal>>,
+    ) -> Self::Val<'a, Normal>;
+    /// Creates a constant array of the integer type `elem_ty` from `data`, each
+    /// element truncated to the type's width, e.g. for lookup tables.
+    ///
+    /// LLVM stores such arrays as flat data rather than one constant per element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `elem_ty` is not an integer type.
+    fn const_data_array<'a>(elem_ty: Self::Ty<'a>, data: &[u64]) -> Self::Val<'a, Normal>;
+    /// Creates a constant `[N x i8]` array holding `data`, without a NUL
+    /// terminator, e.g. for embedded binary resources.
+    fn const_bytes<'a>(ctx: Self::Ctx<'a>, data: &[u8]) -> Self::Val<'a, Normal>;
+    /// Creates a constant of an anonymous struct type (`{ ... }` or `<{ ... }>`
+    /// when `packed`) whose fields are `vals`.
+    fn const_struct<'a>(
+        ctx: Self::Ctx<'a>,
+        vals: impl Iterator<Item = Self::Val<'a, Normal>>,
+        packed: bool,
+    ) -> Self::Val<'a, Normal>;
+    /// Creates a constant of the named struct type `struct_ty`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `struct_ty` is not a struct type or `vals` does not yield
+    /// exactly one value per field.
+    fn const_named_struct<'a>(
+        struct_ty: Self::Ty<'a>,
+        vals: impl Iterator<Item = Self::Val<'a, Normal>>,
+    ) -> Self::Val<'a, Normal>;
+    /// Creates a constant `getelementptr` expression indexing into `ptr`, which
+    /// points to a value of type `ty`.
+    ///
+    /// Unlike [`Builder::gep2`] this emits no instruction, so the result can be
+    /// used in global initializers. `ptr` and `indices` must be constants.
+    fn const_gep<'a>(
+        ty: Self::Ty<'a>,
+        ptr: Self::Val<'a, Normal>,
+        indices: impl Iterator<Item = Self::Val<'a, Normal>>,
+    ) -> Self::Val<'a, Normal>;
+    /// Creates a constant `bitcast` expression converting the constant `val` to `ty`.
+    fn const_bitcast<'a>(val: Self::Val<'a, Normal>, ty: Self::Ty<'a>) -> Self::Val<'a, Normal>;
+    /// Truncates the integer constant `val` to the narrower integer type `ty`.
+    fn const_trunc<'a>(val: Self::Val<'a, Normal>, ty: Self::Ty<'a>) -> Self::Val<'a, Normal>;
+    /// Zero-extends the integer constant `val` to the wider integer type `ty`.
+    ///
+    /// # Panics
+    ///
+    /// LLVM 18 removed `zext` constant expressions, so from then on `val` must
+    /// fold to a plain constant (e.g. be a constant integer); otherwise this
+    /// panics.
+    fn const_zext<'a>(val: Self::Val<'a, Normal>, ty: Self::Ty<'a>) -> Self::Val<'a, Normal>;
+    /// Sign-extends the integer constant `val` to the wider integer type `ty`.
+    ///
+    /// # Panics
+    ///
+    /// As with [`ValueKind::const_zext`], from LLVM 18 on `val` must fold to a
+    /// plain constant.
+    fn const_sext<'a>(val: Self::Val<'a, Normal>, ty: Self::Ty<'a>) -> Self::Val<'a, Normal>;
+    /// Creates a `blockaddress` constant: a `ptr` to `bb`, which can be stored in
+    /// globals and branched to with [`Builder::indirect_br`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bb` does not belong to `func`.
+    fn block_address<'a>(
+        func: Self::Func<'a>,
+        bb: <Self::Func<'a> as Func<'a>>::BB,
+    ) -> Self::Val<'a, Normal>;
+    /// Negates the integer constant `val` without emitting an instruction.
+    ///
+    /// Like the other constant arithmetic helpers, the result is folded to a
+    /// plain constant when the operands are plain constants, and is a constant
+    /// expression otherwise (e.g. when an operand is a global's address).
+    fn const_neg<'a>(val: Self::Val<'a, Normal>) -> Self::Val<'a, Normal>;
+    /// Adds two integer constants of the same type.
+    fn const_add<'a>(
+        lhs: Self::Val<'a, Normal>,
+        rhs: Self::Val<'a, Normal>,
+    ) -> Self::Val<'a, Normal>;
+    /// Subtracts two integer constants of the same type.
+    fn const_sub<'a>(
+        lhs: Self::Val<'a, Normal>,
+        rhs: Self::Val<'a, Normal>,
+    ) -> Self::Val<'a, Normal>;
+    /// Multiplies two integer constants of the same type.
+    ///
+    /// # Panics
+    ///
+    /// Panics on LLVM 21 and newer, which removed the `mul` constant
+    /// expression.
+    fn const_mul<'a>(
+        lhs: Self::Val<'a, Normal>,
+        rhs: Self::Val<'a, Normal>,
+    ) -> Self::Val<'a, Normal>;
+    /// Computes the bitwise XOR of two integer constants of the same type.
+    fn const_xor<'a>(
+        lhs: Self::Val<'a, Normal>,
+        rhs: Self::Val<'a, Normal>,
+    ) -> Self::Val<'a, Normal>;
+    /// Creates an inline assembly value of function type `ty`.
+    ///
+    /// The result is callable: pass it as the `fn` argument of [`Builder::call`]
+    /// together with the same `ty`.
+    fn inline_asm<'a>(
+        ty: Self::Ty<'a>,
+        asm: &CStr,
+        constraints: &CStr,
+        has_side_effects: bool,
+        is_align_stack: bool,
+        dialect: AsmDialect,
+    ) -> Self::Val<'a, Normal>;
     /// Adds a function to the module.
+    ///
+    /// The function starts out as a declaration and becomes a definition once a
+    /// basic block is appended to it (see [`Func::is_declaration`]).
     fn function<'a, 'b, 'c, 'd: 'a + 'b + 'c>(
         r#mod: Self::Mod<'a>,
         name: &'b CStr,
         ty: Self::Ty<'c>,
     ) -> Self::Func<'d>;
+    /// Declares an external function, reusing an existing declaration of the
+    /// same name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the module already defines a function called `name`.
+    fn declare<'a, 'b, 'c, 'd: 'a + 'b + 'c>(
+        r#mod: Self::Mod<'a>,
+        name: &'b CStr,
+        ty: Self::Ty<'c>,
+    ) -> Self::Func<'d>;
+    /// Adds a constant global `[N + 1 x ptr]` array to the module holding `ptrs`
+    /// followed by a terminating null pointer, as used for `argv`-style tables.
+    fn build_ptr_array_global<'a, 'b, 'c, 'd: 'a + 'b + 'c>(
+        r#mod: Self::Mod<'a>,
+        name: &'b CStr,
+        ptrs: impl Iterator<Item = Self::Val<'c, Normal>>,
+    ) -> Self::Val<'d, Normal>;
+    /// Adds a private `unnamed_addr` constant global initialized with `init`,
+    /// such as a table from [`ValueKind::const_data_array`], and returns a
+    /// `ptr` to it. An empty `name` makes the global anonymous.
+    fn add_constant_global<'a, 'b, 'c, 'd: 'a + 'b + 'c>(
+        r#mod: Self::Mod<'a>,
+        name: &'b CStr,
+        init: Self::Val<'c, Normal>,
+    ) -> Self::Val<'d, Normal>;
 }
 
 /// Trait for LLVM function value wrappers.
-pub trait Func<'a>: Clone + private::Sealed + Value<'a, Tag = FuncTag> + 'a {}
+pub trait Func<'a>: Clone + private::Sealed + Value<'a, Tag = FuncTag> + 'a {
+    /// The basic block type of this function.
+    type BB: BB<'a>;
+    /// Iterates over the basic blocks of this function in layout order.
+    fn basic_blocks(&self) -> impl Iterator<Item = Self::BB>;
+    /// Iterates over the parameters of this function.
+    fn params(&self) -> impl Iterator<Item = <Self::Kind as ValueKind>::Val<'a, Normal>>;
+    /// Returns whether this function is only declared, i.e. has no basic blocks.
+    fn is_declaration(&self) -> bool;
+    /// Sets the personality function used to unwind through this function.
+    ///
+    /// Required before the function may contain landing pads, e.g. with
+    /// `__gxx_personality_v0` declared as an external function for C++-style
+    /// exceptions.
+    fn set_personality<'b>(&self, personality: <Self::Kind as ValueKind>::Func<'b>);
+    /// Sets the garbage-collector strategy used for this function, e.g.
+    /// `c"statepoint-example"` or `c"shadow-stack"`.
+    fn set_gc(&self, name: &CStr);
+    /// Returns the garbage-collector strategy of this function, if any.
+    fn gc(&self) -> Option<String>;
+    /// Checks this function for malformed IR, which is much cheaper than
+    /// verifying the whole module while it is still being built.
+    ///
+    /// LLVM does not say what is wrong; use [`Func::verify_with_message`] for
+    /// that.
+    #[allow(clippy::result_unit_err)]
+    fn verify(&self) -> Result<(), ()>;
+    /// Like [`Func::verify`], but also prints a description of the problems
+    /// to stderr.
+    #[allow(clippy::result_unit_err)]
+    fn verify_with_message(&self) -> Result<(), ()>;
+}
 
 /// Trait for LLVM basic block wrappers.
 ///
@@ -117,7 +1164,85 @@ pub trait BB<'a>: Clone + private::Sealed + 'a {
     fn new<'b, 'c>(f: Self::Func<'b>, name: &'c CStr) -> Self
     where
         'a: 'b + 'c;
+    /// Creates a new basic block immediately before `before`, in the same function.
+    ///
+    /// There is no function parameter: the block always lands in the function
+    /// of `before`, so passing one would only allow the two to disagree.
+    fn insert_before<'c>(before: &Self, name: &'c CStr) -> Self
+    where
+        'a: 'c;
+    /// Creates a basic block that belongs to no function yet, e.g. to build a
+    /// CFG bottom-up. Attach it with [`BB::append_existing`].
+    ///
+    /// The block is leaked unless it is appended to a function.
+    fn create_in_context(
+        ctx: <<Self::Func<'a> as Value<'a>>::Kind as ValueKind>::Ctx<'a>,
+        name: &CStr,
+    ) -> Self;
+    /// Appends the detached block `bb` (see [`BB::create_in_context`]) to the
+    /// end of `func`.
+    fn append_existing<'b>(func: Self::Func<'b>, bb: &Self)
+    where
+        'a: 'b;
+    /// Removes this block from its function and deletes it, along with its instructions.
+    ///
+    /// Branches to the block (and other uses, such as `blockaddress`
+    /// constants) must be removed first.
+    ///
+    /// # Safety
+    ///
+    /// The block and its instructions are freed, so no other handle to any of
+    /// them may be used afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block still has predecessors or other uses.
+    unsafe fn delete(self);
+    /// Moves this block so it directly precedes `other` in the function's block list.
+    fn move_before(&self, other: &Self);
+    /// Moves this block so it directly follows `other` in the function's block list.
+    fn move_after(&self, other: &Self);
+    /// Returns the terminator instruction of this block, if it has one.
+    #[allow(clippy::type_complexity)]
+    fn terminator(
+        &self,
+    ) -> Option<<<Self::Func<'a> as Value<'a>>::Kind as ValueKind>::Val<'a, Normal>>;
+    /// Iterates over the instructions of this block, ending with its terminator.
+    fn instructions(
+        &self,
+    ) -> impl Iterator<Item = <<Self::Func<'a> as Value<'a>>::Kind as ValueKind>::Val<'a, Normal>>;
+    /// Returns this block as a value of `label` type, e.g. for `blockaddress`.
+    ///
+    /// [`Value::as_basic_block`] converts back.
+    fn as_value(&self) -> <<Self::Func<'a> as Value<'a>>::Kind as ValueKind>::Val<'a, Normal>;
 }
+/// Picks between two token streams depending on the LLVM version `impls!` is
+/// being expanded for.
+///
+/// `since!(17 $l { .. } else { .. })` expands to the first block when `$l` is
+/// LLVM 17 or newer and to the `else` block otherwise. Only the thresholds the
+/// crate currently needs are listed:
+///
+/// - 17: `LLVMArrayType2` / `LLVMConstArray2` (64-bit lengths), removal of
+///   typed pointers
+/// - 18: `LLVMSetTailCallKind` (`musttail` / `notail`)
+/// - 19: `LLVMConstStringInContext2` (64-bit lengths)
+/// - 21: removal of the `mul` constant expression (`LLVMConstMul`)
+#[allow(unused_macros)]
+macro_rules! since {
+    (17 llvm_sys_160 { $($new:tt)* } else { $($old:tt)* }) => { $($old)* };
+    (17 $l:ident { $($new:tt)* } else { $($old:tt)* }) => { $($new)* };
+    (18 llvm_sys_160 { $($new:tt)* } else { $($old:tt)* }) => { $($old)* };
+    (18 llvm_sys_170 { $($new:tt)* } else { $($old:tt)* }) => { $($old)* };
+    (18 $l:ident { $($new:tt)* } else { $($old:tt)* }) => { $($new)* };
+    (19 llvm_sys_160 { $($new:tt)* } else { $($old:tt)* }) => { $($old)* };
+    (19 llvm_sys_170 { $($new:tt)* } else { $($old:tt)* }) => { $($old)* };
+    (19 llvm_sys_180 { $($new:tt)* } else { $($old:tt)* }) => { $($old)* };
+    (19 $l:ident { $($new:tt)* } else { $($old:tt)* }) => { $($new)* };
+    (21 llvm_sys_210 { $($new:tt)* } else { $($old:tt)* }) => { $($new)* };
+    (21 $l:ident { $($new:tt)* } else { $($old:tt)* }) => { $($old)* };
+}
+#[allow(unused_macros)]
 macro_rules! rest {
     ($llvm:ident as [$i:ident ($(($l:lifetime) @ $e:ident : $t:ty as |$v:ident|$b:expr),*)]) => {
         $(let $e= match $e{$v => $b});*;
@@ -133,7 +1258,7 @@ macro_rules! inst {
     ($(#[$($doc:tt)*])*  ($l2:lifetime)@  $i:ident ($(($($l:lifetime),*) @ $e:ident : $t:ty as |$v:ident|$b:expr),*) =>  $($llvm:ident )? => $stuff:tt) => {
         paste::paste!{
             $(#[$($doc)*])*
-            #[allow(unreachable_code,unused_variables)]
+            #[allow(unreachable_code,unused_variables,unused_unsafe,non_snake_case)]
             fn $i<'b,$($($l),*),*,'res:  $($($l +)* )* 'b>(&'b self, $($e: $t),*) -> <Self::ValKind<'a,'a> as ValueKind>::Val<'res,Normal> where $($($l2 : $l),*),*{
 
                 let builder = |(),$($e : $t),*| -> std::convert::Infallible{
@@ -151,11 +1276,12 @@ macro_rules! inst {
                     rest!($llvm as $stuff);
                     let builder = $llvm::core::[<LLVMBuild $i >];
                     let ptr = self.ptr();
+                    unsafe { check_insertion_point(ptr) };
                     let leaked = |a,b|unsafe{crate::LLHandle::leaked(a,b)};
                     let mark: Result<(),std::convert::Infallible> = Ok(());
 
                     // shim!()
-                )?;
+                )?
 
                 let res = unsafe{
                     builder(ptr,$($e),*)
@@ -185,51 +1311,257 @@ pub enum ICmp {
     /// Signed less-than comparison.
     Lts,
 }
-macro_rules! default_insts {
-    ($l2:lifetime @ $($llvm:ident)?) => {
-        insts!(($l2) @ {
-            [
-                /// Allocates memory on the stack for a value of the given type.
-                ///
-                /// Returns a pointer to the allocated memory.
-                ///
-                /// # Parameters
-                /// - `ty`: The type to allocate space for
-                /// - `name`: Name for the resulting instruction
-                Alloca (('ty) @ ty: Self::Ty<'ty> as |x|x.ptr(), ('name) @ name : &'name CStr as |x|x.as_ptr())
-            ],
-            [
-                /// Loads a value from memory.
-                ///
-                /// # Parameters
-                /// - `ty`: The type of the value to load
-                /// - `pointer`: Pointer to the memory location to load from
-                /// - `name`: Name for the resulting instruction
-                Load2 (('ty) @ ty: Self::Ty<'ty> as |x|x.ptr(), ('ptr) @ pointer: <Self::ValKind<'a,'a> as ValueKind>::Val<'ptr,Normal> as |x|x.ptr(), ('name) @ name : &'name CStr as |x|x.as_ptr())
-            ],
-            [
-                /// Gets a pointer to a struct field.
-                ///
-                /// # Parameters
-                /// - `ty`: The type of the struct
-                /// - `pointer`: Pointer to the struct
-                /// - `idx`: Index of the field to get a pointer to
-                /// - `name`: Name for the resulting instruction
-                StructGEP2 (('ty) @ ty: Self::Ty<'ty> as |x|x.ptr(), ('ptr) @ pointer: <Self::ValKind<'a,'a> as ValueKind>::Val<'ptr,Normal> as |x|x.ptr(), ('idx) @ idx: &'idx u32 as |x|*x, ('name) @ name : &'name CStr as |x|x.as_ptr())
-            ],
-            [
-                /// Stores a value to memory.
-                ///
-                /// # Parameters
-                /// - `value`: The value to store
-                /// - `pointer`: Pointer to the memory location to store to
-                Store (('val) @ value: <Self::ValKind<'a,'a> as ValueKind>::Val<'val,Normal> as |x|x.ptr(), ('ptr) @ pointer: <Self::ValKind<'a,'a> as ValueKind>::Val<'ptr,Normal> as |x|x.ptr())
-            ],
-            [
-                /// Adds two integer values.
-                ///
-                /// # Parameters
-                /// - `lhs`: Left-hand side operand
+
+/// Tail-call markers for use with [`Value::set_tail_call_kind`].
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[non_exhaustive]
+pub enum TailCallKind {
+    /// No marker.
+    None,
+    /// `tail`: the call may be optimized into a tail call.
+    Tail,
+    /// `musttail`: the call must be lowered as a tail call.
+    ///
+    /// LLVM's verifier requires the call to immediately precede a `ret` of its
+    /// result and the caller and callee to have ABI-compatible signatures and
+    /// the same calling convention.
+    MustTail,
+    /// `notail`: the call must not be lowered as a tail call.
+    NoTail,
+}
+
+/// Inline assembly syntax for use with [`ValueKind::inline_asm`].
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+#[non_exhaustive]
+pub enum AsmDialect {
+    /// AT&T syntax.
+    #[default]
+    Att,
+    /// Intel syntax.
+    Intel,
+}
+
+/// Thread-local storage models for use with [`Value::set_thread_local_mode`].
+///
+/// The models trade generality for speed: `GeneralDynamic` works everywhere,
+/// while `LocalExec` only works for variables defined in the main executable.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+#[non_exhaustive]
+pub enum ThreadLocalMode {
+    /// Not thread-local.
+    #[default]
+    NotThreadLocal,
+    /// `thread_local`: the variable may live in any loaded module.
+    GeneralDynamic,
+    /// `thread_local(localdynamic)`: the variable is defined in the current module.
+    LocalDynamic,
+    /// `thread_local(initialexec)`: the variable lives in a module loaded at startup.
+    InitialExec,
+    /// `thread_local(localexec)`: the variable is defined in the executable itself.
+    LocalExec,
+}
+
+/// Whether a global's address is significant, for use with
+/// [`Value::set_unnamed_addr`].
+///
+/// Globals whose address is not significant may be merged with identical
+/// constants.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+#[non_exhaustive]
+pub enum UnnamedAddr {
+    /// The address is significant.
+    #[default]
+    None,
+    /// `local_unnamed_addr`: the address is not significant within the module.
+    Local,
+    /// `unnamed_addr`: the address is not significant anywhere.
+    Global,
+}
+
+/// Binary integer operations for use with [`Builder::try_binop`].
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[non_exhaustive]
+pub enum BinOp {
+    /// Integer addition.
+    Add,
+    /// Integer subtraction.
+    Sub,
+    /// Integer multiplication.
+    Mul,
+    /// Bitwise AND.
+    And,
+    /// Bitwise OR.
+    Or,
+    /// Bitwise XOR.
+    Xor,
+}
+
+/// Error returned by the fallible `try_*` methods of [`Builder`].
+#[derive(Clone, Copy, Hash, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum BuildError {
+    /// An operand or argument did not have the type the instruction requires.
+    TypeMismatch,
+    /// A call was given the wrong number of arguments.
+    ArityMismatch {
+        /// Number of parameters declared by the function type.
+        expected: usize,
+        /// Number of arguments supplied.
+        found: usize,
+    },
+    /// LLVM returned a null value instead of an instruction.
+    Null,
+}
+impl std::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::TypeMismatch => write!(f, "operand type mismatch"),
+            BuildError::ArityMismatch { expected, found } => {
+                write!(f, "expected {expected} arguments, found {found}")
+            }
+            BuildError::Null => write!(f, "LLVM failed to build the instruction"),
+        }
+    }
+}
+impl std::error::Error for BuildError {}
+
+/// The return and parameter types of a function, captured when it is declared
+/// so that calls to it can be checked with [`Builder::call_with_signature`].
+///
+/// ```
+/// # #[cfg(feature = "llvm-sys-210")] {
+/// # use llvm_sys_210 as llvm_sys;
+/// use px_llvm_codegen_utils_core::*;
+/// # type Context<'a> = LLHandle<'a, Normal, llvm_sys::LLVMContext>;
+/// # type Module<'a> = LLHandle<'a, Normal, llvm_sys::LLVMModule>;
+/// # type IrBuilder<'a> = LLHandle<'a, Normal, llvm_sys::LLVMBuilder>;
+/// # type Type<'a> = LLHandle<'a, Normal, llvm_sys::LLVMType>;
+/// # type Val<'a> = LLHandle<'a, Normal, llvm_sys::LLVMValue>;
+///
+/// let ctx = Context::create_context();
+/// let r#mod = Module::create_mod(c"m", &ctx);
+/// let i64 = Type::int_ty(ctx.clone(), 64);
+/// let sig = Signature::new(i64.clone(), [i64.clone(), i64]);
+/// let add = FunctionBuilder::new(IrBuilder::new_in_ctx(ctx.clone()), r#mod.clone(), c"add", sig.fn_ty());
+/// let caller = FunctionBuilder::new(IrBuilder::new_in_ctx(ctx.clone()), r#mod, c"caller", sig.fn_ty());
+/// # let add = unsafe { Val::from_raw_leaked(add.func().ptr()) };
+/// let sum = caller.call_with_signature(&sig, add, [caller.param(0)].into_iter(), c"sum");
+/// assert_eq!(sum.unwrap_err(), BuildError::ArityMismatch { expected: 2, found: 1 });
+/// # }
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Signature<T> {
+    ret: T,
+    params: Vec<T>,
+    var_arg: bool,
+}
+
+impl<'a, T: Ty<'a>> Signature<T> {
+    /// Creates the signature of a function returning `ret` and taking `params`.
+    pub fn new(ret: T, params: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            ret,
+            params: params.into_iter().collect(),
+            var_arg: false,
+        }
+    }
+    /// Creates the signature of a variadic function returning `ret` and taking
+    /// `params` followed by `...`, such as `printf`.
+    pub fn var_arg(ret: T, params: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            var_arg: true,
+            ..Self::new(ret, params)
+        }
+    }
+    /// Recovers the signature of the function type `fn_ty`, or `None` if it is
+    /// not a function type.
+    pub fn of(fn_ty: &T) -> Option<Self> {
+        Some(Self {
+            ret: fn_ty.return_type()?,
+            params: fn_ty.param_types()?,
+            var_arg: fn_ty.is_var_arg()?,
+        })
+    }
+    /// Returns the return type.
+    pub fn ret(&self) -> &T {
+        &self.ret
+    }
+    /// Returns the fixed parameter types, in order.
+    pub fn params(&self) -> &[T] {
+        &self.params
+    }
+    /// Returns whether the function takes variadic arguments after
+    /// [`Signature::params`].
+    pub fn is_var_arg(&self) -> bool {
+        self.var_arg
+    }
+    /// Builds the corresponding function type.
+    pub fn fn_ty(&self) -> T {
+        let params = self.params.iter().cloned();
+        if self.var_arg {
+            self.ret.clone().var_arg_fun_ty(params)
+        } else {
+            self.ret.clone().fun_ty(params)
+        }
+    }
+}
+macro_rules! default_insts {
+    ($l2:lifetime @ $($llvm:ident)?) => {
+        insts!(($l2) @ {
+            [
+                /// Allocates memory on the stack for a value of the given type.
+                ///
+                /// Returns a pointer to the allocated memory.
+                ///
+                /// # Parameters
+                /// - `ty`: The type to allocate space for
+                /// - `name`: Name for the resulting instruction
+                Alloca (('ty) @ ty: Self::Ty<'ty> as |x|x.ptr(), ('name) @ name : &'name CStr as |x|x.as_ptr())
+            ],
+            [
+                /// Allocates stack memory for a runtime-sized array, e.g. a
+                /// variable-length array.
+                ///
+                /// Returns a pointer to the first element.
+                ///
+                /// # Parameters
+                /// - `ty`: The element type
+                /// - `count`: Integer value giving the number of elements
+                /// - `name`: Name for the resulting instruction
+                ArrayAlloca (('ty) @ ty: Self::Ty<'ty> as |x|x.ptr(), ('count) @ count: <Self::ValKind<'a,'a> as ValueKind>::Val<'count,Normal> as |x|x.ptr(), ('name) @ name : &'name CStr as |x|x.as_ptr())
+            ],
+            [
+                /// Loads a value from memory.
+                ///
+                /// # Parameters
+                /// - `ty`: The type of the value to load
+                /// - `pointer`: Pointer to the memory location to load from
+                /// - `name`: Name for the resulting instruction
+                Load2 (('ty) @ ty: Self::Ty<'ty> as |x|x.ptr(), ('ptr) @ pointer: <Self::ValKind<'a,'a> as ValueKind>::Val<'ptr,Normal> as |x|x.ptr(), ('name) @ name : &'name CStr as |x|x.as_ptr())
+            ],
+            [
+                /// Gets a pointer to a struct field.
+                ///
+                /// # Parameters
+                /// - `ty`: The type of the struct
+                /// - `pointer`: Pointer to the struct
+                /// - `idx`: Index of the field to get a pointer to
+                /// - `name`: Name for the resulting instruction
+                StructGEP2 (('ty) @ ty: Self::Ty<'ty> as |x|x.ptr(), ('ptr) @ pointer: <Self::ValKind<'a,'a> as ValueKind>::Val<'ptr,Normal> as |x|x.ptr(), ('idx) @ idx: &'idx u32 as |x|*x, ('name) @ name : &'name CStr as |x|x.as_ptr())
+            ],
+            [
+                /// Stores a value to memory.
+                ///
+                /// # Parameters
+                /// - `value`: The value to store
+                /// - `pointer`: Pointer to the memory location to store to
+                Store (('val) @ value: <Self::ValKind<'a,'a> as ValueKind>::Val<'val,Normal> as |x|x.ptr(), ('ptr) @ pointer: <Self::ValKind<'a,'a> as ValueKind>::Val<'ptr,Normal> as |x|x.ptr())
+            ],
+            [
+                /// Adds two integer values.
+                ///
+                /// # Parameters
+                /// - `lhs`: Left-hand side operand
                 /// - `rhs`: Right-hand side operand
                 /// - `name`: Name for the resulting instruction
                 Add (('lhs) @ lhs: <Self::ValKind<'a,'a> as ValueKind>::Val<'lhs,Normal> as |x|x.ptr(), ('rhs) @ rhs: <Self::ValKind<'a,'a> as ValueKind>::Val<'rhs,Normal> as |x|x.ptr(), ('name) @ name : &'name CStr as |x|x.as_ptr())
@@ -330,6 +1662,55 @@ macro_rules! default_insts {
                 /// - `else`: Basic block to branch to if condition is false
                 CondBr (('cond) @ r#if: <Self::ValKind<'a,'a> as ValueKind>::Val<'cond,Normal> as |x|x.ptr(), ('then) @ then: Self::BB<'then,'a,'a> as |x|x.ptr(),('e) @ r#else: Self::BB<'e,'a,'a> as |x|x.ptr())
             ],
+            [
+                /// Returns a value from the current function.
+                ///
+                /// # Parameters
+                /// - `value`: The value to return
+                Ret (('val) @ value: <Self::ValKind<'a,'a> as ValueKind>::Val<'val,Normal> as |x|x.ptr())
+            ],
+            [
+                /// Freezes a possibly `undef`/`poison` value into an arbitrary but fixed value.
+                ///
+                /// # Parameters
+                /// - `value`: The value to freeze
+                /// - `name`: Name for the resulting instruction
+                Freeze (('val) @ value: <Self::ValKind<'a,'a> as ValueKind>::Val<'val,Normal> as |x|x.ptr(), ('name) @ name : &'name CStr as |x|x.as_ptr())
+            ],
+            [
+                /// Reads the next variadic argument of type `ty` from a `va_list`.
+                ///
+                /// See [`Builder::va_start`] for setting up the list.
+                ///
+                /// # Parameters
+                /// - `list`: Pointer to the `va_list`
+                /// - `ty`: The type of the argument to read
+                /// - `name`: Name for the resulting instruction
+                VAArg (('list) @ list: <Self::ValKind<'a,'a> as ValueKind>::Val<'list,Normal> as |x|x.ptr(), ('ty) @ ty: Self::Ty<'ty> as |x|x.ptr(), ('name) @ name : &'name CStr as |x|x.as_ptr())
+            ],
+            [
+                /// Resumes propagation of an in-flight exception.
+                ///
+                /// # Parameters
+                /// - `exn`: The exception value, usually the landing pad result (see [`eh::LandingPad::as_value`])
+                Resume (('exn) @ exn: <Self::ValKind<'a,'a> as ValueKind>::Val<'exn,Normal> as |x|x.ptr())
+            ],
+            [
+                /// Leaves a catch handler, continuing normal execution at `dest`.
+                ///
+                /// # Parameters
+                /// - `pad`: The `catchpad` being exited (see [`eh::CatchPad::as_value`])
+                /// - `dest`: The block to continue at
+                CatchRet (('pad) @ pad: <Self::ValKind<'a,'a> as ValueKind>::Val<'pad,Normal> as |x|x.ptr(), ('dest) @ dest: Self::BB<'dest,'a,'a> as |x|x.ptr())
+            ],
+            [
+                /// Leaves a cleanup funclet, continuing to unwind.
+                ///
+                /// # Parameters
+                /// - `pad`: The `cleanuppad` being exited (see [`eh::CleanupPad::as_value`])
+                /// - `unwind`: Block to unwind to next, or `None` to unwind to the caller
+                CleanupRet (('pad) @ pad: <Self::ValKind<'a,'a> as ValueKind>::Val<'pad,Normal> as |x|x.ptr(), ('unwind) @ unwind: Option<Self::BB<'unwind,'a,'a>> as |x|x.map_or(std::ptr::null_mut(), |x|x.ptr()))
+            ],
         } => $(<$llvm>)?);
     };
 }
@@ -343,12 +1724,97 @@ pub trait Ty<'a>: Clone + private::Sealed + 'a {
         Self: 'b;
     /// Creates an integer type with the specified bit width.
     fn int_ty(ctx: Self::Ctx<'a>, size: u32) -> Self;
+    /// Creates the `i1` type used for booleans.
+    fn i1_ty(ctx: Self::Ctx<'a>) -> Self {
+        Self::int_ty(ctx, 1)
+    }
     /// Creates a pointer type in the specified address space.
     fn ptr_ty(ctx: Self::Ctx<'a>, address_space: u32) -> Self;
     /// Creates a struct type with the specified field types.
     fn struct_ty(ctx: Self::Ctx<'a>, fields: impl Iterator<Item = Self>, packed: bool) -> Self;
     /// Creates a function type with this type as the return type.
     fn fun_ty(self, params: impl Iterator<Item = Self>) -> Self;
+    /// Like [`Ty::fun_ty`], but the function also takes variadic arguments
+    /// after `params` (`...`).
+    fn var_arg_fun_ty(self, params: impl Iterator<Item = Self>) -> Self;
+    /// Returns the category of this type.
+    fn kind(&self) -> TypeKind;
+    /// Returns the bit width of this type, if it is an integer type.
+    fn int_width(&self) -> Option<u32>;
+    /// Returns the element type of this array or vector type.
+    ///
+    /// Pointers are opaque on every supported LLVM version, so they carry no
+    /// element type and `None` is returned for them as for any other type.
+    fn element_type(&self) -> Option<Self>;
+    /// Returns the field types of this struct type, in order.
+    fn struct_field_types(&self) -> Option<Vec<Self>>;
+    /// Returns the element type of this pointer type.
+    ///
+    /// Only LLVM 16 still supports typed pointers; on newer versions, and for
+    /// opaque `ptr` types, this is always `None`.
+    fn pointee_type(&self) -> Option<Self>;
+    /// Returns the return type of this function type.
+    fn return_type(&self) -> Option<Self>;
+    /// Returns the number of fixed parameters of this function type.
+    fn count_param_types(&self) -> Option<u32>;
+    /// Returns the fixed parameter types of this function type, in order.
+    fn param_types(&self) -> Option<Vec<Self>>;
+    /// Returns whether this function type takes variadic arguments.
+    fn is_var_arg(&self) -> Option<bool>;
+    /// Returns whether values of this type have a size, i.e. whether it is
+    /// neither `void`, a function or label type, nor an opaque struct.
+    fn is_sized(&self) -> bool;
+}
+
+/// Type categories reported by [`Ty::kind`], mirroring `LLVMTypeKind`.
+///
+/// Categories not modelled here (e.g. ones added by newer LLVM versions) are
+/// reported as [`TypeKind::Other`].
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[non_exhaustive]
+pub enum TypeKind {
+    /// `void`.
+    Void,
+    /// 16-bit IEEE floating point (`half`).
+    Half,
+    /// 16-bit brain floating point (`bfloat`).
+    BFloat,
+    /// 32-bit floating point (`float`).
+    Float,
+    /// 64-bit floating point (`double`).
+    Double,
+    /// 80-bit x87 floating point (`x86_fp80`).
+    X86Fp80,
+    /// 128-bit IEEE floating point (`fp128`).
+    Fp128,
+    /// 128-bit PowerPC double-double (`ppc_fp128`).
+    PpcFp128,
+    /// A basic block label.
+    Label,
+    /// An integer of any width.
+    Integer,
+    /// A function type.
+    Function,
+    /// A struct type.
+    Struct,
+    /// An array type.
+    Array,
+    /// A pointer type.
+    Pointer,
+    /// A fixed-length vector type.
+    Vector,
+    /// A scalable vector type.
+    ScalableVector,
+    /// `metadata`.
+    Metadata,
+    /// The x86 AMX tile type.
+    X86Amx,
+    /// `token`.
+    Token,
+    /// A target extension type.
+    TargetExt,
+    /// Any other kind of type.
+    Other,
 }
 
 /// Trait for LLVM IR builder wrappers.
@@ -363,13 +1829,20 @@ pub trait Ty<'a>: Clone + private::Sealed + 'a {
 /// naming convention (PascalCase) for macro-generated methods, while manually
 /// defined methods use snake_case:
 ///
-/// - **Memory**: `Alloca`, `Load2`, `Store`, `StructGEP2`, `gep2`
+/// - **Memory**: `Alloca`, `alloca_aligned`, `ArrayAlloca`, `Load2`, `Store`, `StructGEP2`, `struct_gep_typed`, `field_ptr`, `gep2`, `gep2_in_bounds`, `gep_indices`, `global_string_ptr`
 /// - **Arithmetic**: `Add`, `Sub`, `Mul`, `Neg`
-/// - **Bitwise**: `And`, `Or`, `Xor`, `Not`
+/// - **Bitwise**: `And`, `Or`, `or_disjoint`, `Xor`, `Not`
 /// - **Comparison**: `ICmp`
-/// - **Conversion**: `TruncOrBitCast`
-/// - **Control Flow**: `Br`, `CondBr`
-/// - **Calls**: `call`
+/// - **Conversion**: `TruncOrBitCast`, `zext_nneg`, `Freeze`
+/// - **Control Flow**: `Br`, `CondBr`, `indirect_br`, `Ret`, `ret_void`
+/// - **Calls**: `call`, `call0`–`call3`, `call_struct`
+/// - **Variadics**: `VAArg`, `va_start`, `va_end`
+/// - **Exceptions**: `invoke`, `landing_pad`, `Resume`, `catch_switch`, `catch_pad`, `CatchRet`, `cleanup_pad`, `CleanupRet` (see [`eh`])
+/// - **Fallible**: `try_call`, `call_with_signature`, `try_gep2`, `try_binop`
+/// - **Safety checks**: `build_bounds_check`
+/// - **Dispatch**: `build_lookup`
+/// - **Intrinsics**: `mem_set`, `mem_cpy`, `mem_move` (see also [`Mod::intrinsic`])
+/// - **Garbage collection**: `gc_statepoint`, `gc_result`, `gc_relocate`
 pub trait Builder<'a>: Clone + private::Sealed + 'a {
     /// The basic block type for this builder.
     type BB<'b, 'e, 'd>: BB<'b, Func<'b>: Value<'b, Kind = Self::ValKind<'e, 'd>>>
@@ -405,13 +1878,194 @@ pub trait Builder<'a>: Clone + private::Sealed + 'a {
     fn r#continue<'b, 'c>(&'b self, bb: Self::BB<'c, '_, '_>)
     where
         'a: 'b + 'c;
+    /// Appends a new block to `func` and positions the builder at its end.
+    ///
+    /// This is `BB::new` followed by `continue`, for the common case
+    /// of emitting into a block right after creating it. The block is returned
+    /// so it can still be branched to.
+    fn append_and_enter<'b, 'c>(
+        &'c self,
+        func: <Self::BB<'b, 'a, 'a> as BB<'b>>::Func<'b>,
+        name: &CStr,
+    ) -> Self::BB<'b, 'a, 'a>
+    where
+        'a: 'b,
+    {
+        let bb = Self::BB::new(func, name);
+        self.r#continue(bb.clone());
+        bb
+    }
+    /// Positions the builder directly before `instr`, so subsequently built
+    /// instructions are inserted ahead of it.
+    fn position_before<'d>(&self, instr: <Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>);
+    /// Clears the insertion position without producing a [`Finished`] token.
+    fn clear_insertion_position(&self);
+    /// Attaches `line:col` in `scope` (e.g. a subprogram from
+    /// [`DIBuilder::create_function`]) as the `!dbg` location of instructions
+    /// subsequently built by this builder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the builder is not positioned.
+    fn set_debug_location<'d>(
+        &self,
+        line: u32,
+        col: u32,
+        scope: <Self::ValKind<'_, '_> as ValueKind>::Metadata<'d>,
+    );
+    /// Stops attaching a debug location to subsequently built instructions.
+    fn clear_debug_location(&self);
+    /// Inserts the detached instruction `instr` (e.g. from
+    /// [`Value::clone_instruction`] or [`Value::remove_from_parent`]) at the
+    /// builder's position and names it `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the builder is not positioned.
+    fn insert<'d>(&self, instr: <Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>, name: &CStr);
+    /// Returns from the current `void` function.
+    fn ret_void<'b, 'res: 'b>(&'b self) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>;
+    /// Returns the block the builder is currently positioned in, if any.
+    fn insert_block<'b>(&'b self) -> Option<Self::BB<'b, 'a, 'a>>
+    where
+        'a: 'b;
+    /// Clears the insertion position so no further instructions can be emitted
+    /// until the builder is positioned again.
+    ///
+    /// Afterwards [`Builder::insert_block`] returns `None`, and with the
+    /// `safe-checks` feature emitting an instruction panics.
+    fn finish(&self) -> Finished;
+    /// Emits an `invoke`: a call that continues at `then` on normal return and
+    /// unwinds to `catch` (whose first instruction must be a landing pad).
+    #[allow(clippy::too_many_arguments)]
+    fn invoke<'b, 'c, 'd, 'e, 'f, 'h, 'i, 'j, 'k, 'g: 'a + 'b + 'c + 'd + 'e + 'f + 'h + 'i>(
+        &'b self,
+        resty: Self::Ty<'c>,
+        r#fn: <Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>,
+        args: impl Iterator<Item = <Self::ValKind<'h, 'i> as ValueKind>::Val<'e, Normal>>,
+        then: Self::BB<'j, 'a, 'a>,
+        catch: Self::BB<'k, 'a, 'a>,
+        name: &'f CStr,
+    ) -> <Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>
+    where
+        Self: 'h + 'i,
+        'a: 'j + 'k;
+    /// Emits a `landingpad` of type `ty` (e.g. `{ ptr, i32 }`), catching
+    /// exceptions that unwind out of an `invoke`.
+    ///
+    /// Add clauses on the result. The enclosing function needs a personality
+    /// (see [`Func::set_personality`]).
+    fn landing_pad<'b, 'c, 'f, 'g: 'a + 'b + 'c + 'f>(
+        &'b self,
+        ty: Self::Ty<'c>,
+        name: &'f CStr,
+    ) -> eh::LandingPad<<Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>>;
+    /// Emits a `catchswitch` for funclet-based (e.g. MSVC C++) exception
+    /// handling, inside `parent` (or at the top level if `None`).
+    ///
+    /// If no handler matches, unwinding continues at `unwind`, or in the caller
+    /// if `None`. Register the handlers on the result; each must begin with a
+    /// [`Builder::catch_pad`].
+    fn catch_switch<'b, 'd, 'k, 'f, 'g: 'a + 'b + 'd + 'f>(
+        &'b self,
+        parent: Option<<Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>>,
+        unwind: Option<Self::BB<'k, 'a, 'a>>,
+        name: &'f CStr,
+    ) -> eh::CatchSwitch<<Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>>
+    where
+        'a: 'k;
+    /// Emits a `catchpad` as the first instruction of a handler registered on
+    /// `catch_switch`.
+    ///
+    /// `args` are passed to the personality function to decide whether this
+    /// handler matches; leave the pad with [`Builder::CatchRet`].
+    fn catch_pad<'b, 'd, 'e, 'f, 'h, 'i, 'g: 'a + 'b + 'd + 'e + 'f + 'h + 'i>(
+        &'b self,
+        catch_switch: &eh::CatchSwitch<<Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>>,
+        args: impl Iterator<Item = <Self::ValKind<'h, 'i> as ValueKind>::Val<'e, Normal>>,
+        name: &'f CStr,
+    ) -> eh::CatchPad<<Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>>
+    where
+        Self: 'h + 'i;
+    /// Emits a `cleanuppad`, starting a cleanup funclet inside `parent` (or at
+    /// the top level if `None`). Leave it with [`Builder::CleanupRet`].
+    fn cleanup_pad<'b, 'd, 'e, 'f, 'h, 'i, 'g: 'a + 'b + 'd + 'e + 'f + 'h + 'i>(
+        &'b self,
+        parent: Option<<Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>>,
+        args: impl Iterator<Item = <Self::ValKind<'h, 'i> as ValueKind>::Val<'e, Normal>>,
+        name: &'f CStr,
+    ) -> eh::CleanupPad<<Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>>
+    where
+        Self: 'h + 'i;
+    /// Emits an `indirectbr` to the block whose address is `addr`, e.g. for
+    /// computed goto. `addr` is usually a [`ValueKind::block_address`] constant
+    /// and must point to one of `dests`.
+    fn indirect_br<'b, 'd, 'j, 'g: 'a + 'b + 'd>(
+        &'b self,
+        addr: <Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>,
+        dests: impl Iterator<Item = Self::BB<'j, 'a, 'a>>,
+    ) -> <Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>
+    where
+        'a: 'j;
     fn call<'b, 'c, 'd, 'e, 'f, 'h, 'i, 'g: 'a + 'b + 'c + 'd + 'e + 'f + 'h + 'i>(
         &'b self,
         resty: Self::Ty<'c>,
         r#fn: <Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>,
         args: impl Iterator<Item = <Self::ValKind<'h, 'i> as ValueKind>::Val<'e, Normal>>,
         name: &'f CStr,
-    ) -> <Self::ValKind<'_, '_> as ValueKind>::Val<'g, Normal>
+    ) -> <Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>
+    where
+        Self: 'h + 'i;
+    /// Like [`Builder::call`] for a call without arguments, without allocating.
+    fn call0<'b, 'c, 'd, 'f, 'g: 'a + 'b + 'c + 'd + 'f>(
+        &'b self,
+        resty: Self::Ty<'c>,
+        r#fn: <Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>,
+        name: &'f CStr,
+    ) -> <Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>;
+    /// Like [`Builder::call`] for a call with one argument, without allocating.
+    fn call1<'b, 'c, 'd, 'e, 'f, 'g: 'a + 'b + 'c + 'd + 'e + 'f>(
+        &'b self,
+        resty: Self::Ty<'c>,
+        r#fn: <Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>,
+        a0: <Self::ValKind<'_, '_> as ValueKind>::Val<'e, Normal>,
+        name: &'f CStr,
+    ) -> <Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>;
+    /// Like [`Builder::call`] for a call with two arguments, without allocating.
+    fn call2<'b, 'c, 'd, 'e, 'h, 'f, 'g: 'a + 'b + 'c + 'd + 'e + 'h + 'f>(
+        &'b self,
+        resty: Self::Ty<'c>,
+        r#fn: <Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>,
+        a0: <Self::ValKind<'_, '_> as ValueKind>::Val<'e, Normal>,
+        a1: <Self::ValKind<'_, '_> as ValueKind>::Val<'h, Normal>,
+        name: &'f CStr,
+    ) -> <Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>;
+    /// Like [`Builder::call`] for a call with three arguments, without allocating.
+    #[allow(clippy::too_many_arguments)]
+    fn call3<'b, 'c, 'd, 'e, 'h, 'i, 'f, 'g: 'a + 'b + 'c + 'd + 'e + 'h + 'i + 'f>(
+        &'b self,
+        resty: Self::Ty<'c>,
+        r#fn: <Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>,
+        a0: <Self::ValKind<'_, '_> as ValueKind>::Val<'e, Normal>,
+        a1: <Self::ValKind<'_, '_> as ValueKind>::Val<'h, Normal>,
+        a2: <Self::ValKind<'_, '_> as ValueKind>::Val<'i, Normal>,
+        name: &'f CStr,
+    ) -> <Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>;
+    /// Like [`Builder::call`], for a function returning a struct by value: each
+    /// field of the result is extracted with `extractvalue` and returned in
+    /// order, as tuples are lowered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `resty` does not return a struct, or if the builder is not
+    /// positioned.
+    fn call_struct<'b, 'c, 'd, 'e, 'f, 'h, 'i, 'g: 'a + 'b + 'c + 'd + 'e + 'f + 'h + 'i>(
+        &'b self,
+        resty: Self::Ty<'c>,
+        r#fn: <Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>,
+        args: impl Iterator<Item = <Self::ValKind<'h, 'i> as ValueKind>::Val<'e, Normal>>,
+        name: &'f CStr,
+    ) -> Vec<<Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>>
     where
         Self: 'h + 'i;
     fn gep2<'b, 'c, 'd, 'e, 'f, 'h, 'i, 'g: 'a + 'b + 'c + 'd + 'e + 'f + 'h + 'i>(
@@ -420,322 +2074,3983 @@ pub trait Builder<'a>: Clone + private::Sealed + 'a {
         ptr: <Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>,
         args: impl Iterator<Item = <Self::ValKind<'h, 'i> as ValueKind>::Val<'e, Normal>>,
         name: &'f CStr,
-    ) -> <Self::ValKind<'_, '_> as ValueKind>::Val<'g, Normal>
+    ) -> <Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>
     where
         Self: 'h + 'i;
-    default_insts!('a @ );
-}
-static M: LazyLock<Mutex<BTreeMap<usize, (usize, Box<dyn FnOnce(*mut (), *mut ()) + Send>)>>> =
-    LazyLock::new(|| Default::default());
-
-/// A smart handle for LLVM resources.
-///
-/// This type provides reference counting and automatic cleanup for LLVM resources.
-/// When the last clone of a handle is dropped, the associated LLVM resource is
-/// disposed of via the dropper function provided during construction.
-///
-/// # Type Parameters
-///
-/// - `'a` - Lifetime of the handle
-/// - `K` - Key/tag type used to categorize the resource
-/// - `T` - The underlying LLVM type being wrapped
-///
-/// # Safety
-///
-/// This type uses unsafe internally to manage raw pointers to LLVM resources.
-/// Callers must ensure the underlying LLVM resources remain valid for the
-/// lifetime of the handle.
-pub struct LLHandle<'a, K, T> {
-    val: *mut T,
-    key: *mut K,
-    phantom: PhantomData<fn(K, &'a T) -> (K, &'a T)>,
-}
-impl<'a, K, T> Clone for LLHandle<'a, K, T> {
-    fn clone(&self) -> Self {
-        if let Some((n, _)) = M.lock().unwrap().get_mut(&(self.val as usize)) {
-            *n += 1;
-        }
-        Self {
-            val: self.val.clone(),
-            key: self.key.clone(),
-            phantom: self.phantom.clone(),
-        }
-    }
-}
-impl<'a, K, T> Drop for LLHandle<'a, K, T> {
-    fn drop(&mut self) {
-        let mut lock = M.lock().unwrap();
-        let Some((a, b)) = lock.remove(&(self.val as usize)) else {
-            return;
-        };
-        if a == 0 {
-            drop(lock);
-            b(self.val.cast(), self.key.cast());
-        } else {
-            lock.insert(self.val as usize, (a - 1, b));
-        }
-    }
-}
-impl<'a, K, T> LLHandle<'a, K, T> {
-    /// Creates a new handle from raw parts with automatic cleanup.
-    ///
-    /// # Safety
+    /// Like [`Builder::gep2`], but emits an `inbounds` GEP.
     ///
-    /// - `ptr` must be a valid pointer to an LLVM resource
-    /// - `dropper` must properly dispose of the resource when called
-    /// - The resource must not be disposed of by any other means
-    pub unsafe fn from_raw_parts(ptr: *mut T, dropper: fn(*mut T, K), key: K) -> Self {
-        let key = Box::into_raw(Box::new(key));
-        M.lock().unwrap().insert(
-            ptr as usize,
-            (
-                0,
-                match Box::new(move |b: *mut (), a: *mut ()| {
-                    let key = a as *mut K;
-                    let ptr = b as *mut T;
-                    dropper(ptr.cast(), *unsafe { Box::from_raw(key) });
-                }) {
-                    val => {
-                        let val: Box<dyn FnOnce(*mut (), *mut ()) + Send + '_> = val;
-                        unsafe { transmute(val) }
-                    }
-                },
-            ),
-        );
-        Self {
-            val: ptr,
-            key,
-            phantom: PhantomData,
-        }
-    }
-
-    /// Creates a handle for a "leaked" resource that won't be automatically cleaned up.
+    /// The `inbounds` flag lets alias analysis assume the result stays within the
+    /// allocated object; computing an out-of-bounds address with it yields poison,
+    /// and accessing memory through it is undefined behavior.
+    fn gep2_in_bounds<'b, 'c, 'd, 'e, 'f, 'h, 'i, 'g: 'a + 'b + 'c + 'd + 'e + 'f + 'h + 'i>(
+        &'b self,
+        resty: Self::Ty<'c>,
+        ptr: <Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>,
+        args: impl Iterator<Item = <Self::ValKind<'h, 'i> as ValueKind>::Val<'e, Normal>>,
+        name: &'f CStr,
+    ) -> <Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>
+    where
+        Self: 'h + 'i;
+    /// Like [`Builder::gep2`], but with constant `indices` that are materialized
+    /// as `i32` for struct fields and `i64` otherwise.
     ///
-    /// Use this for LLVM resources that are owned by another resource
-    /// (e.g., values owned by their parent module).
+    /// As with any GEP, the first index steps over whole `ty` values behind
+    /// `ptr` and only the following ones descend into `ty`: field 1 of element 3
+    /// of a `[10 x {i32, i32}]` is `&[0, 3, 1]`, not `&[3, 1]`.
     ///
-    /// # Safety
+    /// # Panics
     ///
-    /// - `ptr` must be a valid pointer to an LLVM resource
-    /// - The resource must outlive the handle
-    pub unsafe fn leaked(ptr: *mut T, key: K) -> Self {
+    /// Panics if an index descends into a type that is not a struct, array or
+    /// vector, or names a struct field that does not exist.
+    fn gep_indices<'b, 'ptr, 'name, 'res: 'ptr + 'name + 'b>(
+        &'b self,
+        ty: Self::Ty<'a>,
+        ptr: <Self::ValKind<'a, 'a> as ValueKind>::Val<'ptr, Normal>,
+        indices: &[u64],
+        name: &'name CStr,
+    ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
+    where
+        'a: 'ptr + 'name;
+    /// Fallible variant of [`Builder::call`].
+    ///
+    /// Checks the argument count and types against the function type `resty`
+    /// before emitting, instead of letting LLVM assert on malformed IR.
+    fn try_call<'b, 'c, 'd, 'e, 'f, 'h, 'i, 'g: 'a + 'b + 'c + 'd + 'e + 'f + 'h + 'i>(
+        &'b self,
+        resty: Self::Ty<'c>,
+        r#fn: <Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>,
+        args: impl Iterator<Item = <Self::ValKind<'h, 'i> as ValueKind>::Val<'e, Normal>>,
+        name: &'f CStr,
+    ) -> Result<<Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>, BuildError>
+    where
+        Self: 'h + 'i;
+    /// Calls `r#fn`, a function declared with `sig`, after checking `args`
+    /// against it.
+    ///
+    /// The argument count is always checked, allowing extra arguments only for
+    /// a variadic `sig`; types of the fixed arguments are compared only in
+    /// debug builds, so release builds pay nothing beyond the length check.
+    fn call_with_signature<'b, 'd, 'e, 'f, 'h, 'i, 'g: 'a + 'b + 'd + 'e + 'f + 'h + 'i>(
+        &'b self,
+        sig: &Signature<Self::Ty<'a>>,
+        r#fn: <Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>,
+        args: impl Iterator<Item = <Self::ValKind<'h, 'i> as ValueKind>::Val<'e, Normal>>,
+        name: &'f CStr,
+    ) -> Result<<Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>, BuildError>
+    where
+        Self: 'h + 'i;
+    /// Fallible variant of [`Builder::gep2`].
+    ///
+    /// Returns [`BuildError::TypeMismatch`] if `ptr` is not a pointer, `resty`
+    /// is unsized, or an index does not fit the type it steps into: every index
+    /// must be an integer, and one into a struct a constant `i32` naming one of
+    /// its fields.
+    fn try_gep2<'b, 'c, 'd, 'e, 'f, 'h, 'i, 'g: 'a + 'b + 'c + 'd + 'e + 'f + 'h + 'i>(
+        &'b self,
+        resty: Self::Ty<'c>,
+        ptr: <Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>,
+        args: impl Iterator<Item = <Self::ValKind<'h, 'i> as ValueKind>::Val<'e, Normal>>,
+        name: &'f CStr,
+    ) -> Result<<Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>, BuildError>
+    where
+        Self: 'h + 'i;
+    /// Fallible binary integer arithmetic (see [`BinOp`]).
+    ///
+    /// Returns [`BuildError::TypeMismatch`] if `lhs` and `rhs` differ in type or
+    /// are not integers (or vectors of them).
+    fn try_binop<'b, 'lhs, 'rhs, 'name, 'res: 'lhs + 'rhs + 'name + 'b>(
+        &'b self,
+        op: BinOp,
+        lhs: <Self::ValKind<'a, 'a> as ValueKind>::Val<'lhs, Normal>,
+        rhs: <Self::ValKind<'a, 'a> as ValueKind>::Val<'rhs, Normal>,
+        name: &'name CStr,
+    ) -> Result<<Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>, BuildError>
+    where
+        'a: 'lhs + 'rhs + 'name;
+    /// Emits an `icmp uge index, len` bounds check.
+    ///
+    /// On failure control transfers to a fresh block in which `on_fail` is run
+    /// (typically to emit a trap or a panic call); if `on_fail` leaves that block
+    /// unterminated, an `unreachable` is appended. The builder is then positioned
+    /// in the in-bounds block.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the builder is not positioned.
+    fn build_bounds_check<'b, 'idx, 'len>(
+        &'b self,
+        index: <Self::ValKind<'a, 'a> as ValueKind>::Val<'idx, Normal>,
+        len: <Self::ValKind<'a, 'a> as ValueKind>::Val<'len, Normal>,
+        on_fail: impl FnOnce(&Self),
+    ) where
+        'a: 'idx + 'len;
+    /// Emits a perfect-hash lookup of `key` against the constant set `keys`.
+    ///
+    /// A modulus under which all `keys` are distinct is chosen at build time, and
+    /// two private constant tables indexed by `key % modulus` are added to the
+    /// current module. Returns `(hit, index)`: `hit` is an `i1` that is true when
+    /// `key` is one of `keys`, and `index` is the `i32` position of the matching
+    /// key in `keys` (unspecified on a miss). Branch on `hit` to reach the
+    /// default destination.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not an integer, `keys` is empty, contains duplicates
+    /// or values that do not fit `key`'s type, if no modulus that fits the type
+    /// and needs at most [`MAX_LOOKUP_SLOTS`] slots separates the keys, or if
+    /// the builder is not positioned.
+    fn build_lookup<'b, 'key, 'name, 'res: 'key + 'name + 'b>(
+        &'b self,
+        key: <Self::ValKind<'a, 'a> as ValueKind>::Val<'key, Normal>,
+        keys: &[u64],
+        name: &'name CStr,
+    ) -> (
+        <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>,
+        <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>,
+    )
+    where
+        'a: 'key + 'name;
+    /// Emits a call to `llvm.memset` filling `len` bytes at `dst` with the `i8`
+    /// value `val`, where `dst` is known to be aligned to `align` bytes (`0` if
+    /// unknown).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the builder is not positioned.
+    fn mem_set<'b, 'dst, 'val, 'len, 'res: 'dst + 'val + 'len + 'b>(
+        &'b self,
+        dst: <Self::ValKind<'a, 'a> as ValueKind>::Val<'dst, Normal>,
+        val: <Self::ValKind<'a, 'a> as ValueKind>::Val<'val, Normal>,
+        len: <Self::ValKind<'a, 'a> as ValueKind>::Val<'len, Normal>,
+        align: u32,
+    ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
+    where
+        'a: 'dst + 'val + 'len;
+    /// Emits a call to `llvm.memcpy` copying `len` bytes from `src` to `dst`,
+    /// given the known alignment of each pointer (`0` if unknown).
+    ///
+    /// The regions must not overlap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the builder is not positioned.
+    fn mem_cpy<'b, 'dst, 'src, 'len, 'res: 'dst + 'src + 'len + 'b>(
+        &'b self,
+        dst: <Self::ValKind<'a, 'a> as ValueKind>::Val<'dst, Normal>,
+        dst_align: u32,
+        src: <Self::ValKind<'a, 'a> as ValueKind>::Val<'src, Normal>,
+        src_align: u32,
+        len: <Self::ValKind<'a, 'a> as ValueKind>::Val<'len, Normal>,
+    ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
+    where
+        'a: 'dst + 'src + 'len;
+    /// Like [`Builder::mem_cpy`], but the regions may overlap.
+    fn mem_move<'b, 'dst, 'src, 'len, 'res: 'dst + 'src + 'len + 'b>(
+        &'b self,
+        dst: <Self::ValKind<'a, 'a> as ValueKind>::Val<'dst, Normal>,
+        dst_align: u32,
+        src: <Self::ValKind<'a, 'a> as ValueKind>::Val<'src, Normal>,
+        src_align: u32,
+        len: <Self::ValKind<'a, 'a> as ValueKind>::Val<'len, Normal>,
+    ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
+    where
+        'a: 'dst + 'src + 'len;
+    /// Emits a call to `llvm.va_start`, initializing the `va_list` at `list` to
+    /// the variadic arguments of the current function.
+    ///
+    /// The layout of `va_list` is target-specific (a single `ptr` on some
+    /// targets, a struct such as `{ i32, i32, ptr, ptr }` on x86-64 System V),
+    /// so `list` must point to storage of the target's `va_list` type. Each
+    /// `va_start` must be paired with a [`Builder::va_end`] before returning.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the builder is not positioned.
+    fn va_start<'b, 'list, 'res: 'list + 'b>(
+        &'b self,
+        list: <Self::ValKind<'a, 'a> as ValueKind>::Val<'list, Normal>,
+    ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
+    where
+        'a: 'list;
+    /// Emits a call to `llvm.va_end`, releasing the `va_list` at `list`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the builder is not positioned.
+    fn va_end<'b, 'list, 'res: 'list + 'b>(
+        &'b self,
+        list: <Self::ValKind<'a, 'a> as ValueKind>::Val<'list, Normal>,
+    ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
+    where
+        'a: 'list;
+    /// Like `StructGEP2`, but also returns the type of the addressed field so it
+    /// can be passed straight to `Load2` or `Store`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ty` is not a struct type or has no field `idx`.
+    fn struct_gep_typed<'b, 'ptr, 'name, 'res: 'ptr + 'name + 'b>(
+        &'b self,
+        ty: Self::Ty<'a>,
+        ptr: <Self::ValKind<'a, 'a> as ValueKind>::Val<'ptr, Normal>,
+        idx: u32,
+        name: &'name CStr,
+    ) -> (
+        <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>,
+        Self::Ty<'a>,
+    )
+    where
+        'a: 'ptr + 'name;
+    /// Returns a pointer to field `field_index` of the `struct_ty` at
+    /// `base_ptr`, together with the field's type.
+    ///
+    /// This is [`Builder::struct_gep_typed`] under the name used for
+    /// high-level struct codegen.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `struct_ty` is not a struct type or has no field `field_index`.
+    fn field_ptr<'b, 'ptr, 'name, 'res: 'ptr + 'name + 'b>(
+        &'b self,
+        struct_ty: Self::Ty<'a>,
+        base_ptr: <Self::ValKind<'a, 'a> as ValueKind>::Val<'ptr, Normal>,
+        field_index: u32,
+        name: &'name CStr,
+    ) -> (
+        <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>,
+        Self::Ty<'a>,
+    )
+    where
+        'a: 'ptr + 'name,
+    {
+        self.struct_gep_typed(struct_ty, base_ptr, field_index, name)
+    }
+    /// Adds a private constant global holding the NUL-terminated string `s` to
+    /// the current module and returns a `ptr` to its first character.
+    ///
+    /// Every call creates a new global; identical strings are not deduplicated
+    /// here, but they are `unnamed_addr` so LLVM's constant merging can fold
+    /// them later.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the builder is not positioned.
+    fn global_string_ptr<'b, 's, 'name, 'res: 's + 'name + 'b>(
+        &'b self,
+        s: &'s CStr,
+        name: &'name CStr,
+    ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>;
+    /// Like `Alloca`, but with an explicit alignment of `align` bytes, e.g. for
+    /// SIMD or over-aligned types.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two.
+    fn alloca_aligned<'b, 'name, 'res: 'name + 'b>(
+        &'b self,
+        ty: Self::Ty<'a>,
+        align: u32,
+        name: &'name CStr,
+    ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>;
+    /// Emits `zext nneg`: zero-extends the integer `val` to `ty`, promising that
+    /// `val` is non-negative so the extension can also be treated as a `sext`.
+    ///
+    /// If `val` is in fact negative the result is `poison`, and using it is
+    /// undefined behaviour.
+    ///
+    /// # Panics
+    ///
+    /// Panics before LLVM 18, which has no `nneg` flag, or if the builder is
+    /// not positioned.
+    fn zext_nneg<'b, 'val, 'name, 'res: 'val + 'name + 'b>(
+        &'b self,
+        val: <Self::ValKind<'a, 'a> as ValueKind>::Val<'val, Normal>,
+        ty: Self::Ty<'a>,
+        name: &'name CStr,
+    ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
+    where
+        'a: 'val + 'name;
+    /// Emits `or disjoint`: a bitwise OR of `lhs` and `rhs`, promising that they
+    /// have no set bits in common so the OR can also be treated as an `add`.
+    ///
+    /// If a bit is in fact set in both the result is `poison`, and using it is
+    /// undefined behaviour.
+    ///
+    /// # Panics
+    ///
+    /// Panics before LLVM 18, which has no `disjoint` flag, or if the builder
+    /// is not positioned.
+    fn or_disjoint<'b, 'lhs, 'rhs, 'name, 'res: 'lhs + 'rhs + 'name + 'b>(
+        &'b self,
+        lhs: <Self::ValKind<'a, 'a> as ValueKind>::Val<'lhs, Normal>,
+        rhs: <Self::ValKind<'a, 'a> as ValueKind>::Val<'rhs, Normal>,
+        name: &'name CStr,
+    ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
+    where
+        'a: 'lhs + 'rhs + 'name;
+    /// Emits a `gc.statepoint` calling `r#fn`, of function type `fn_ty`, with
+    /// `args`, and records the GC pointers in `gc_live` as live across the call.
+    ///
+    /// The result is the statepoint token: get the call's return value with
+    /// [`Builder::gc_result`], and after the call use only the pointers
+    /// returned by [`Builder::gc_relocate`], since a moving collector may have
+    /// changed the originals.
+    ///
+    /// # ABI
+    ///
+    /// The call is emitted as
+    /// `gc.statepoint(i64 id, i32 num_patch_bytes, ptr elementtype(fn_ty) fn, i32 <args.len()>, i32 0, args..., i32 0, i32 0)`
+    /// with `gc_live` in a `"gc-live"` operand bundle: no flags, and no
+    /// transition or deopt arguments. `id` and `num_patch_bytes` are copied to
+    /// the stack map; `0` for both is fine unless the runtime patches call
+    /// sites. The enclosing function needs a statepoint-based GC strategy (see
+    /// [`Func::set_gc`]), and GC pointers conventionally live in address space 1.
+    ///
+    /// # Panics
+    ///
+    /// Panics before LLVM 18, whose C API cannot attach operand bundles, or if
+    /// the builder is not positioned.
+    #[allow(clippy::too_many_arguments)]
+    fn gc_statepoint<'b, 'fun, 'arg, 'live, 'name, 'res: 'fun + 'arg + 'live + 'name + 'b>(
+        &'b self,
+        id: u64,
+        num_patch_bytes: u32,
+        fn_ty: Self::Ty<'a>,
+        r#fn: <Self::ValKind<'a, 'a> as ValueKind>::Val<'fun, Normal>,
+        args: impl Iterator<Item = <Self::ValKind<'a, 'a> as ValueKind>::Val<'arg, Normal>>,
+        gc_live: impl Iterator<Item = <Self::ValKind<'a, 'a> as ValueKind>::Val<'live, Normal>>,
+        name: &'name CStr,
+    ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
+    where
+        'a: 'fun + 'arg + 'live + 'name;
+    /// Emits a `gc.result`, extracting the return value, of type `ty`, of the
+    /// call wrapped by the statepoint `token`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the builder is not positioned.
+    fn gc_result<'b, 'tok, 'name, 'res: 'tok + 'name + 'b>(
+        &'b self,
+        token: <Self::ValKind<'a, 'a> as ValueKind>::Val<'tok, Normal>,
+        ty: Self::Ty<'a>,
+        name: &'name CStr,
+    ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
+    where
+        'a: 'tok + 'name;
+    /// Emits a `gc.relocate`, returning where the collector moved a pointer
+    /// that was live across the statepoint `token`.
+    ///
+    /// `base` and `derived` index the statepoint's `gc_live` list: `derived` is
+    /// the pointer to relocate and `base` the object it points into (the same
+    /// index for a pointer to the start of an object). `ty` is the type of the
+    /// derived pointer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the builder is not positioned.
+    fn gc_relocate<'b, 'tok, 'name, 'res: 'tok + 'name + 'b>(
+        &'b self,
+        token: <Self::ValKind<'a, 'a> as ValueKind>::Val<'tok, Normal>,
+        base: u32,
+        derived: u32,
+        ty: Self::Ty<'a>,
+        name: &'name CStr,
+    ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
+    where
+        'a: 'tok + 'name;
+    default_insts!('a @ );
+}
+
+/// The most slots a table emitted by [`Builder::build_lookup`] may have.
+pub const MAX_LOOKUP_SLOTS: u64 = 1 << 16;
+
+/// Typed handles for exception-handling pads.
+///
+/// LLVM's C API casts whatever value it is given to the pad it expects, so the
+/// operations specific to each pad live on these wrappers rather than on
+/// [`Value`]. They are returned by the matching [`Builder`] methods, or
+/// recovered from a plain value with `from_value`.
+///
+/// Itanium-style unwinding (Linux, macOS) uses [`LandingPad`](eh::LandingPad);
+/// the funclet model of MSVC targets uses [`CatchSwitch`](eh::CatchSwitch),
+/// [`CatchPad`](eh::CatchPad) and [`CleanupPad`](eh::CleanupPad).
+pub mod eh {
+    use crate::private::Eh;
+    use crate::{Func, Normal, Opcode, Value, ValueKind};
+
+    macro_rules! pads {
+        ($($(#[doc = $doc:expr])* $name:ident,)*) => {
+            $(
+                $(#[doc = $doc])*
+                #[derive(Clone, PartialEq, Eq, Hash, Debug)]
+                pub struct $name<V>(pub(crate) V);
+                impl<'a, V: Value<'a>> $name<V> {
+                    #[doc = concat!("Recovers the typed handle if `val` is a `", stringify!($name), "` instruction.")]
+                    pub fn from_value(val: V) -> Option<Self> {
+                        (val.opcode() == Some(Opcode::$name)).then_some(Self(val))
+                    }
+                    /// Returns the instruction as a plain value.
+                    pub fn as_value(&self) -> V {
+                        self.0.clone()
+                    }
+                }
+            )*
+        };
+    }
+    pads! {
+        /// A `landingpad`, from [`Builder::landing_pad`](crate::Builder::landing_pad).
+        LandingPad,
+        /// A `catchswitch`, from [`Builder::catch_switch`](crate::Builder::catch_switch).
+        CatchSwitch,
+        /// A `catchpad`, from [`Builder::catch_pad`](crate::Builder::catch_pad).
+        CatchPad,
+        /// A `cleanuppad`, from [`Builder::cleanup_pad`](crate::Builder::cleanup_pad).
+        CleanupPad,
+    }
+
+    impl<'a, V: Eh<'a>> LandingPad<V> {
+        /// Adds a clause catching exceptions whose type info is `type_info`, or
+        /// every exception if it is a null pointer.
+        pub fn add_catch<'b>(&self, type_info: <V::Kind as ValueKind>::Val<'b, Normal>) {
+            self.0.add_clause(type_info)
+        }
+        /// Adds a clause matching exceptions whose type is not among
+        /// `type_infos`, as for a C++ dynamic exception specification.
+        pub fn add_filter<'b>(&self, type_infos: impl IntoIterator<Item = <V::Kind as ValueKind>::Val<'b, Normal>>) {
+            self.0.add_filter(type_infos.into_iter())
+        }
+        /// Sets whether this landing pad also runs on exceptions none of its
+        /// clauses match, e.g. to run destructors before resuming.
+        pub fn set_cleanup(&self, cleanup: bool) {
+            self.0.set_cleanup(cleanup)
+        }
+    }
+
+    impl<'a, V: Eh<'a>> CatchSwitch<V> {
+        /// Adds `dest` as a handler, which must begin with a `catchpad` of this
+        /// `catchswitch`.
+        pub fn add_handler<'b>(&self, dest: <<V::Kind as ValueKind>::Func<'b> as Func<'b>>::BB) {
+            self.0.add_handler(dest)
+        }
+    }
+}
+
+/// A [`Builder`] bundled with the function it emits into.
+///
+/// [`FunctionBuilder::new`] adds the function to the module, appends an `entry`
+/// block and positions the builder at its end, so straight-line code can be
+/// emitted right away. The builder's own methods are available through `Deref`.
+///
+/// ```ignore
+/// let i64 = Ty::int_ty(ctx.clone(), 64);
+/// let add = FunctionBuilder::new(builder, r#mod, c"add", i64.clone().fun_ty([i64.clone(), i64].into_iter()));
+/// let sum = add.Add(add.param(0), add.param(1), c"sum");
+/// add.Ret(sum);
+/// ```
+pub struct FunctionBuilder<'a, B: Builder<'a>> {
+    builder: B,
+    func: <B::ValKind<'a, 'a> as ValueKind>::Func<'a>,
+    entry: B::BB<'a, 'a, 'a>,
+    names: NameCache,
+}
+
+impl<'a, B: Builder<'a>> FunctionBuilder<'a, B>
+where
+    B::BB<'a, 'a, 'a>: BB<'a, Func<'a> = <B::ValKind<'a, 'a> as ValueKind>::Func<'a>>,
+{
+    /// Adds the function `name` of function type `ty` to `r#mod` and positions
+    /// `builder` at the end of its new `entry` block.
+    pub fn new(builder: B, r#mod: B::Mod<'a>, name: &CStr, ty: B::Ty<'a>) -> Self {
+        let func = <B::ValKind<'a, 'a> as ValueKind>::function(r#mod, name, ty);
+        let entry = B::BB::new(func.clone(), c"entry");
+        builder.r#continue(entry.clone());
         Self {
-            val: ptr,
-            key: Box::into_raw(Box::new(key)),
-            phantom: PhantomData,
+            builder,
+            func,
+            entry,
+            names: NameCache::default(),
+        }
+    }
+    /// Returns the function being built.
+    pub fn func(&self) -> <B::ValKind<'a, 'a> as ValueKind>::Func<'a> {
+        self.func.clone()
+    }
+    /// Returns the underlying builder.
+    pub fn builder(&self) -> &B {
+        &self.builder
+    }
+    /// Returns parameter `index` of the function.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the function has no such parameter.
+    pub fn param(&self, index: usize) -> <B::ValKind<'a, 'a> as ValueKind>::Val<'a, Normal> {
+        self.func
+            .params()
+            .nth(index)
+            .expect("parameter index out of range")
+    }
+    /// Returns the function's entry block.
+    pub fn entry_block(&self) -> B::BB<'a, 'a, 'a> {
+        self.entry.clone()
+    }
+    /// Appends a new block to the function without moving the builder.
+    pub fn append_block(&self, name: &CStr) -> B::BB<'a, 'a, 'a> {
+        B::BB::new(self.func.clone(), name)
+    }
+    /// Positions the builder at the end of `bb`.
+    pub fn position_at_end(&self, bb: B::BB<'a, 'a, 'a>) {
+        self.builder.r#continue(bb)
+    }
+    /// Interns `name` in this builder's [`NameCache`], for use as an
+    /// instruction or block name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` contains a NUL byte.
+    pub fn name(&self, name: &str) -> &CStr {
+        self.names.intern(name)
+    }
+    /// Emits an `if`/`else` on the `i1` value `cond`.
+    ///
+    /// `then` and `else_` run with the builder at the end of fresh `if.then` and
+    /// `if.else` blocks. Each branch that falls through, i.e. leaves the block
+    /// it ends in unterminated, is wired to a new `if.end` block, where the
+    /// builder is left. If neither branch falls through, no `if.end` block is
+    /// created and the builder is left unpositioned.
+    ///
+    /// The branches' results are returned, so a branch can hand back a value
+    /// together with [`Builder::insert_block`] to feed a `phi` in `if.end`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the builder is not positioned.
+    pub fn build_if<T, E>(
+        &self,
+        cond: <B::ValKind<'a, 'a> as ValueKind>::Val<'a, Normal>,
+        then: impl FnOnce(&Self) -> T,
+        else_: impl FnOnce(&Self) -> E,
+    ) -> (T, E) {
+        let then_bb = self.append_block(c"if.then");
+        let else_bb = self.append_block(c"if.else");
+        self.builder.CondBr(cond, then_bb.clone(), else_bb.clone());
+        self.position_at_end(then_bb);
+        let t = then(self);
+        let then_end = self.open_block();
+        self.position_at_end(else_bb);
+        let e = else_(self);
+        let else_end = self.open_block();
+        let ends = then_end.into_iter().chain(else_end).collect::<Vec<_>>();
+        if ends.is_empty() {
+            self.builder.clear_insertion_position();
+        } else {
+            let merge = self.append_block(c"if.end");
+            for end in ends {
+                self.builder.r#continue(end);
+                self.builder.Br(merge.clone());
+            }
+            self.position_at_end(merge);
+        }
+        (t, e)
+    }
+    /// Emits a `while` loop.
+    ///
+    /// Branches to a fresh `while.cond` block, in which `cond` emits the `i1`
+    /// loop condition, and then to `while.body` or `while.end`. `body` runs at
+    /// the end of `while.body`; if it falls through, a back edge to `while.cond`
+    /// is added. The builder is left in `while.end`, and `body`'s result is
+    /// returned.
+    ///
+    /// Loop-carried values such as induction variables are simplest kept in an
+    /// `alloca` created before the loop and updated in `body`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the builder is not positioned.
+    pub fn build_while<T>(
+        &self,
+        cond: impl FnOnce(&Self) -> <B::ValKind<'a, 'a> as ValueKind>::Val<'a, Normal>,
+        body: impl FnOnce(&Self) -> T,
+    ) -> T {
+        let header = self.append_block(c"while.cond");
+        let body_bb = self.append_block(c"while.body");
+        let exit = self.append_block(c"while.end");
+        self.builder.Br(header.clone());
+        self.position_at_end(header.clone());
+        let c = cond(self);
+        self.builder.CondBr(c, body_bb.clone(), exit.clone());
+        self.position_at_end(body_bb);
+        let t = body(self);
+        if self.open_block().is_some() {
+            self.builder.Br(header);
         }
+        self.position_at_end(exit);
+        t
     }
+}
 
-    /// Returns the raw pointer to the underlying LLVM resource.
-    pub fn ptr(&self) -> *mut T {
-        return self.val;
+// Kept apart from the impl above: its `B::BB<'a, 'a, 'a>` bound would otherwise
+// be picked for every block, pinning borrowed blocks to `'a`.
+impl<'a, B: Builder<'a>> FunctionBuilder<'a, B> {
+    /// Returns the block the builder is in, unless it is already terminated.
+    fn open_block(&self) -> Option<B::BB<'_, 'a, 'a>> {
+        self.builder
+            .insert_block()
+            .filter(|bb| bb.terminator().is_none())
     }
+}
 
-    /// Returns a reference to the key/tag associated with this handle.
-    pub fn key(&self) -> &K {
-        return unsafe { &*self.key };
+impl<'a, B: Builder<'a>> std::ops::Deref for FunctionBuilder<'a, B> {
+    type Target = B;
+    fn deref(&self) -> &B {
+        &self.builder
     }
 }
 
-macro_rules! seal {
-    ($(<$($generics:lifetime),*> => $t:ty),* $(,)?) => {
-        $(impl<$($generics),*> private::Sealed for $t{})*
-    };
+/// Caches NUL-terminated copies of names, so that names built at runtime can
+/// be passed as `&CStr` without allocating a new `CString` every time.
+///
+/// ```
+/// use px_llvm_codegen_utils_core::NameCache;
+///
+/// let names = NameCache::default();
+/// let a = names.intern("tmp");
+/// let b = names.intern("tmp");
+/// assert!(std::ptr::eq(a, b));
+/// assert_eq!(a, c"tmp");
+/// ```
+#[derive(Default)]
+pub struct NameCache {
+    names: std::cell::RefCell<std::collections::HashMap<String, Box<CStr>>>,
 }
 
-/// Marker type for normal (non-function) LLVM values.
-pub struct Normal;
+impl NameCache {
+    /// Returns the cached copy of `name`, adding it on first use.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` contains a NUL byte.
+    pub fn intern(&self, name: &str) -> &CStr {
+        let mut names = self.names.borrow_mut();
+        let cached: *const CStr = match names.get(name) {
+            Some(cached) => &**cached,
+            None => {
+                let owned = std::ffi::CString::new(name).expect("name contains a NUL byte");
+                &**names
+                    .entry(name.to_owned())
+                    .or_insert(owned.into_boxed_c_str())
+            }
+        };
+        // Entries are never removed or replaced while `self` is borrowed, and
+        // rehashing the map moves the boxes but not the strings they own.
+        unsafe { &*cached }
+    }
+}
 
-/// Marker type for function LLVM values.
-pub struct FuncTag;
-macro_rules! impls {
-    ($l:ident {}) => {
-        const _: () = {
-            use $l as llvm_sys;
-            seal!(
-             <'a>  =>   crate::LLHandle<'a,Normal,llvm_sys::LLVMContext>,
-              <'a>  =>  crate::LLHandle<'a,Normal,llvm_sys::LLVMModule>,
-               <> =>  llvm_sys::LLVMValue,
-              <'a>  =>  crate::LLHandle<'a,Normal,llvm_sys::LLVMBasicBlock>,
-              <'a>  =>  crate::LLHandle<'a,Normal,llvm_sys::LLVMBuilder>,
-              <'a>  =>  crate::LLHandle<'a,Normal,llvm_sys::LLVMType>,
-            );
-            impl From<crate::ICmp> for llvm_sys::LLVMIntPredicate{
-                fn from(a: crate::ICmp) -> Self{
-                    match a{
-                        crate::ICmp::Eq => llvm_sys::LLVMIntPredicate::LLVMIntEQ,
-                        crate ::ICmp::Lt => llvm_sys::LLVMIntPredicate::LLVMIntULT,
-                        crate ::ICmp::Lts => llvm_sys::LLVMIntPredicate::LLVMIntSLT,
+/// Trait for LLVM metadata wrappers.
+///
+/// Metadata nodes are owned by their context, like types and constants.
+pub trait Metadata<'a>: Clone + private::Sealed + 'a {
+    /// The context type associated with this metadata.
+    type Ctx<'b>: Ctx<'b>
+    where
+        Self: 'b;
+    /// Creates a metadata string (`!"..."`).
+    fn md_string(ctx: Self::Ctx<'a>, s: &CStr) -> Self;
+    /// Creates a metadata tuple node (`!{...}`) from `elems`.
+    fn md_node(ctx: Self::Ctx<'a>, elems: impl Iterator<Item = Self>) -> Self;
+}
+
+/// Source languages accepted by [`DIBuilder::create_compile_unit`].
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[non_exhaustive]
+pub enum SourceLanguage {
+    /// C89.
+    C,
+    /// C99.
+    C99,
+    /// C11.
+    C11,
+    /// C++.
+    CPlusPlus,
+    /// Rust.
+    Rust,
+}
+
+/// Trait for LLVM debug-info builder wrappers.
+///
+/// A debug-info builder emits DWARF metadata into one module: create a file and
+/// a compile unit first, then subprograms for functions, and finally point an
+/// IR [`Builder`] at a source location with [`DIBuilder::set_location`] so that
+/// subsequently built instructions carry `!dbg` attachments.
+///
+/// Debug info must be finalized before the module is verified or emitted. This
+/// happens when the last handle is dropped, or earlier via [`DIBuilder::finalize`].
+pub trait DIBuilder<'a>: Clone + private::Sealed + 'a {
+    /// The module type debug info is emitted into.
+    type Mod<'b>: Mod<'b>
+    where
+        Self: 'b;
+    /// The metadata type produced by this builder.
+    type Metadata<'b>: Metadata<'b>
+    where
+        Self: 'b;
+    /// The IR builder type locations are attached to.
+    type Builder<'b>: Builder<'b>
+    where
+        Self: 'b;
+    /// The function type subprograms are attached to.
+    type Func<'b>: Func<'b>
+    where
+        Self: 'b;
+    /// Creates a debug-info builder for `r#mod`.
+    fn new_in_mod(r#mod: Self::Mod<'a>) -> Self;
+    /// Resolves any deferred debug-info descriptors.
+    fn finalize(&self);
+    /// Creates a file descriptor.
+    fn create_file(&self, filename: &CStr, directory: &CStr) -> Self::Metadata<'a>;
+    /// Creates the compile unit for this module, emitting full debug info.
+    ///
+    /// Each module should have exactly one compile unit.
+    fn create_compile_unit(
+        &self,
+        lang: SourceLanguage,
+        file: Self::Metadata<'a>,
+        producer: &CStr,
+        optimized: bool,
+    ) -> Self::Metadata<'a>;
+    /// Creates a basic type such as an integer or floating-point type.
+    ///
+    /// `encoding` is a DWARF `DW_ATE_*` constant (e.g. `0x05` for `DW_ATE_signed`).
+    fn create_basic_type(
+        &self,
+        name: &CStr,
+        size_in_bits: u64,
+        encoding: u32,
+    ) -> Self::Metadata<'a>;
+    /// Creates a subroutine type; the first element of `types` is the return
+    /// type, or `None` for `void`.
+    fn create_subroutine_type(
+        &self,
+        file: Self::Metadata<'a>,
+        types: impl Iterator<Item = Option<Self::Metadata<'a>>>,
+    ) -> Self::Metadata<'a>;
+    /// Creates a subprogram definition for `func` and attaches it to the function.
+    fn create_function(
+        &self,
+        func: Self::Func<'a>,
+        scope: Self::Metadata<'a>,
+        name: &CStr,
+        file: Self::Metadata<'a>,
+        line: u32,
+        ty: Self::Metadata<'a>,
+    ) -> Self::Metadata<'a>;
+    /// Sets the source location attached to instructions subsequently built by
+    /// `builder`; equivalent to [`Builder::set_debug_location`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `builder` is not positioned.
+    fn set_location(
+        &self,
+        builder: &Self::Builder<'a>,
+        line: u32,
+        col: u32,
+        scope: Self::Metadata<'a>,
+    );
+}
+
+/// Shared ownership record for an LLVM resource.
+///
+/// Every clone of an [`LLHandle`] holds an [`Rc`] to the same shim, so reference
+/// counting is per-resource and needs no global lock. For owned resources the
+/// dropper runs exactly once, when the last clone is dropped.
+///
+/// The count is not atomic: handles stay on the thread of the context they
+/// belong to (see [`LLHandle`]), so `Rc` is enough and keeps the shim, and
+/// with it every handle, `!Send` and `!Sync`.
+///
+/// A shim may also hold on to the shims of resources it depends on, such as a
+/// module's context, which are then released only after the dropper has run.
+struct LLShim<K, T> {
+    val: *mut T,
+    key: ManuallyDrop<K>,
+    dropper: Option<fn(*mut T, K)>,
+    owners: std::cell::RefCell<Vec<Rc<dyn std::any::Any>>>,
+}
+impl<K, T> Drop for LLShim<K, T> {
+    fn drop(&mut self) {
+        let key = unsafe { ManuallyDrop::take(&mut self.key) };
+        if let Some(dropper) = self.dropper.take() {
+            dropper(self.val, key);
+        }
+        // `owners` are dropped after this, so they outlive the resource.
+    }
+}
+
+/// A smart handle for LLVM resources.
+///
+/// This type provides reference counting and automatic cleanup for LLVM resources.
+/// When the last clone of a handle is dropped, the associated LLVM resource is
+/// disposed of via the dropper function provided during construction.
+///
+/// # Type Parameters
+///
+/// - `'a` - Lifetime of the handle
+/// - `K` - Key/tag type used to categorize the resource
+/// - `T` - The underlying LLVM type being wrapped
+///
+/// # Safety
+///
+/// This type uses unsafe internally to manage raw pointers to LLVM resources.
+/// Callers must ensure the underlying LLVM resources remain valid for the
+/// lifetime of the handle.
+///
+/// # Thread Safety
+///
+/// LLVM contexts are not thread-safe, and every handle ultimately refers to state
+/// owned by some context, so handles are neither `Send` nor `Sync`. Independent
+/// contexts may be used from different threads, but all handles derived from a
+/// context must stay on the thread that created it. The shared [`Rc`] count
+/// enforces this: moving a handle to another thread does not compile.
+#[allow(clippy::type_complexity)]
+pub struct LLHandle<'a, K, T> {
+    val: *mut T,
+    shim: Rc<LLShim<K, T>>,
+    phantom: PhantomData<fn(K, &'a T) -> (K, &'a T)>,
+}
+impl<'a, K, T> Clone for LLHandle<'a, K, T> {
+    fn clone(&self) -> Self {
+        Self {
+            val: self.val,
+            shim: self.shim.clone(),
+            phantom: self.phantom,
+        }
+    }
+}
+/// Handles compare by pointer identity: two handles are equal when they refer to
+/// the same LLVM object, whichever clone or [`LLHandle::leaked`] call they came
+/// from. Structurally identical but distinct objects (e.g. two functions with
+/// the same body) are not equal.
+///
+/// Only the pointer is hashed, so handles are fine as map keys even though
+/// Clippy's `mutable_key_type` lint flags them for the reference count's
+/// interior mutability.
+///
+/// ```
+/// use px_llvm_codegen_utils_core::{LLHandle, Normal};
+/// use std::collections::HashSet;
+///
+/// let mut obj = 0u8;
+/// let a = unsafe { LLHandle::<'_, Normal, u8>::leaked(&mut obj, Normal) };
+/// let b = unsafe { LLHandle::<'_, Normal, u8>::leaked(&mut obj, Normal) };
+/// let set = HashSet::from([a]);
+/// assert!(set.contains(&b));
+/// ```
+impl<'a, K, T> PartialEq for LLHandle<'a, K, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.val == other.val
+    }
+}
+impl<'a, K, T> Eq for LLHandle<'a, K, T> {}
+impl<'a, K, T> std::hash::Hash for LLHandle<'a, K, T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.val.hash(state)
+    }
+}
+impl<'a, K, T> LLHandle<'a, K, T> {
+    unsafe fn from_shim(val: *mut T, dropper: Option<fn(*mut T, K)>, key: K) -> Self {
+        Self {
+            val,
+            shim: Rc::new(LLShim {
+                val,
+                key: ManuallyDrop::new(key),
+                dropper,
+                owners: Default::default(),
+            }),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Keeps `owner`'s resource alive for as long as this handle's, e.g. a
+    /// context for as long as a module created in it.
+    #[allow(dead_code)]
+    fn owned_by<K2: 'static, T2: 'static>(self, owner: &LLHandle<'_, K2, T2>) -> Self {
+        self.shim.owners.borrow_mut().push(owner.shim.clone());
+        self
+    }
+
+    /// Returns the first handle passed to [`LLHandle::owned_by`] that has the
+    /// given type.
+    #[allow(dead_code)]
+    fn owner<K2: 'static, T2: 'static>(&self) -> Option<LLHandle<'a, K2, T2>> {
+        let owners = self.shim.owners.borrow();
+        let shim = owners.iter().find_map(|o| o.clone().downcast::<LLShim<K2, T2>>().ok())?;
+        Some(LLHandle {
+            val: shim.val,
+            shim,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Creates a new handle from raw parts with automatic cleanup.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must be a valid pointer to an LLVM resource
+    /// - `dropper` must properly dispose of the resource when called
+    /// - The resource must not be disposed of by any other means
+    pub unsafe fn from_raw_parts(ptr: *mut T, dropper: fn(*mut T, K), key: K) -> Self {
+        unsafe { Self::from_shim(ptr, Some(dropper), key) }
+    }
+
+    /// Creates a handle for a "leaked" resource that won't be automatically cleaned up.
+    ///
+    /// Use this for LLVM resources that are owned by another resource
+    /// (e.g., values owned by their parent module).
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must be a valid pointer to an LLVM resource
+    /// - The resource must outlive the handle
+    pub unsafe fn leaked(ptr: *mut T, key: K) -> Self {
+        unsafe { Self::from_shim(ptr, None, key) }
+    }
+
+    /// Returns the raw pointer to the underlying LLVM resource.
+    pub fn ptr(&self) -> *mut T {
+        self.val
+    }
+
+    /// Returns a reference to the key/tag associated with this handle.
+    pub fn key(&self) -> &K {
+        &self.shim.key
+    }
+
+    /// Takes ownership of the resource away from the handle, returning the raw
+    /// pointer without running the dropper.
+    ///
+    /// Fails, returning the handle unchanged, if the handle does not own the
+    /// resource (see [`LLHandle::leaked`]) or other clones of it are alive.
+    /// Outstanding [`LLWeak`] references can no longer be upgraded afterwards.
+    pub fn try_into_raw(self) -> Result<*mut T, Self> {
+        if self.shim.dropper.is_none() {
+            return Err(self);
+        }
+        let Self { val, shim, .. } = self;
+        match Rc::try_unwrap(shim) {
+            Ok(mut shim) => {
+                shim.dropper = None;
+                Ok(val)
+            }
+            Err(shim) => Err(Self {
+                val,
+                shim,
+                phantom: PhantomData,
+            }),
+        }
+    }
+
+    /// Creates a non-owning [`LLWeak`] reference to this handle's resource.
+    pub fn downgrade(&self) -> LLWeak<'a, K, T> {
+        LLWeak {
+            val: self.val,
+            shim: Rc::downgrade(&self.shim),
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// A non-owning reference to the resource behind an [`LLHandle`].
+///
+/// This mirrors [`std::rc::Weak`]: it does not keep the resource alive, and
+/// [`LLWeak::upgrade`] returns `None` once every clone of the originating handle
+/// has been dropped. Note that each call to [`LLHandle::leaked`] starts a new
+/// handle family, so a weak reference only tracks the clones of the handle it
+/// was downgraded from.
+///
+/// Like [`LLHandle`], weak references are neither `Send` nor `Sync`.
+#[allow(clippy::type_complexity)]
+pub struct LLWeak<'a, K, T> {
+    val: *mut T,
+    shim: Weak<LLShim<K, T>>,
+    phantom: PhantomData<fn(K, &'a T) -> (K, &'a T)>,
+}
+impl<'a, K, T> Clone for LLWeak<'a, K, T> {
+    fn clone(&self) -> Self {
+        Self {
+            val: self.val,
+            shim: self.shim.clone(),
+            phantom: self.phantom,
+        }
+    }
+}
+impl<'a, K, T> LLWeak<'a, K, T> {
+    /// Attempts to recover a strong [`LLHandle`], returning `None` if the
+    /// resource has already been released.
+    pub fn upgrade(&self) -> Option<LLHandle<'a, K, T>> {
+        Some(LLHandle {
+            val: self.val,
+            shim: self.shim.upgrade()?,
+            phantom: PhantomData,
+        })
+    }
+}
+
+#[allow(unused_macros)]
+macro_rules! seal {
+    ($(<$($generics:lifetime),*> => $t:ty),* $(,)?) => {
+        $(impl<$($generics),*> private::Sealed for $t{})*
+    };
+}
+
+/// Marker type for normal (non-function) LLVM values.
+pub struct Normal;
+
+/// Marker type for function LLVM values.
+pub struct FuncTag;
+
+/// Marker returned by [`Builder::finish`] once emission into a function is done.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Finished;
+#[allow(unused_macros)]
+macro_rules! impls {
+    ($l:ident {}) => {
+        const _: () = {
+            use $l as llvm_sys;
+            since!(17 $l {
+                unsafe fn array_type(ty: llvm_sys::prelude::LLVMTypeRef, n: u64) -> llvm_sys::prelude::LLVMTypeRef {
+                    llvm_sys::core::LLVMArrayType2(ty, n)
+                }
+                unsafe fn const_array(ty: llvm_sys::prelude::LLVMTypeRef, vals: &mut [llvm_sys::prelude::LLVMValueRef]) -> llvm_sys::prelude::LLVMValueRef {
+                    llvm_sys::core::LLVMConstArray2(ty, vals.as_mut_ptr(), vals.len().try_into().unwrap())
+                }
+            } else {
+                // LLVM 16 only has the 32-bit-length variants.
+                unsafe fn array_type(ty: llvm_sys::prelude::LLVMTypeRef, n: u64) -> llvm_sys::prelude::LLVMTypeRef {
+                    llvm_sys::core::LLVMArrayType(ty, n.try_into().unwrap())
+                }
+                unsafe fn const_array(ty: llvm_sys::prelude::LLVMTypeRef, vals: &mut [llvm_sys::prelude::LLVMValueRef]) -> llvm_sys::prelude::LLVMValueRef {
+                    llvm_sys::core::LLVMConstArray(ty, vals.as_mut_ptr(), vals.len().try_into().unwrap())
+                }
+            });
+            /// Declares `name` at the types of `overloads` in the module `b` is
+            /// positioned in and calls it with `args`.
+            unsafe fn call_intrinsic(
+                b: llvm_sys::prelude::LLVMBuilderRef,
+                name: &CStr,
+                overloads: &mut [llvm_sys::prelude::LLVMTypeRef],
+                args: &mut [llvm_sys::prelude::LLVMValueRef],
+            ) -> llvm_sys::prelude::LLVMValueRef {
+                use llvm_sys::core::*;
+                let block = LLVMGetInsertBlock(b);
+                assert!(!block.is_null(), "builder is not positioned");
+                check_insertion_point(b);
+                let r#mod = LLVMGetGlobalParent(LLVMGetBasicBlockParent(block));
+                let id = LLVMLookupIntrinsicID(name.as_ptr(), name.count_bytes());
+                let decl = LLVMGetIntrinsicDeclaration(r#mod, id, overloads.as_mut_ptr(), overloads.len());
+                LLVMBuildCall2(
+                    b,
+                    LLVMGlobalGetValueType(decl),
+                    decl,
+                    args.as_mut_ptr(),
+                    args.len().try_into().unwrap(),
+                    c"".as_ptr(),
+                )
+            }
+            /// Calls `llvm.va_start` or `llvm.va_end` on `list`. Newer LLVM versions
+            /// overload these on the pointer type, older ones do not.
+            unsafe fn call_va_intrinsic(
+                b: llvm_sys::prelude::LLVMBuilderRef,
+                name: &CStr,
+                list: llvm_sys::prelude::LLVMValueRef,
+            ) -> llvm_sys::prelude::LLVMValueRef {
+                use llvm_sys::core::*;
+                let id = LLVMLookupIntrinsicID(name.as_ptr(), name.count_bytes());
+                let mut overloads = [LLVMTypeOf(list)];
+                let overloads = if LLVMIntrinsicIsOverloaded(id) != 0 { &mut overloads[..] } else { &mut [] };
+                call_intrinsic(b, name, overloads, &mut [list])
+            }
+            /// Creates the function type returning `ret` and taking `params`,
+            /// followed by `...` if `var_arg` is set.
+            unsafe fn function_type(
+                ret: llvm_sys::prelude::LLVMTypeRef,
+                params: &mut [llvm_sys::prelude::LLVMTypeRef],
+                var_arg: bool,
+            ) -> llvm_sys::prelude::LLVMTypeRef {
+                llvm_sys::core::LLVMFunctionType(
+                    ret,
+                    params.as_mut_ptr(),
+                    params.len().try_into().unwrap(),
+                    var_arg as _,
+                )
+            }
+            /// Emits `LLVMBuildCall2` with `args` after the insertion-point check.
+            unsafe fn build_call(
+                b: llvm_sys::prelude::LLVMBuilderRef,
+                resty: llvm_sys::prelude::LLVMTypeRef,
+                r#fn: llvm_sys::prelude::LLVMValueRef,
+                args: &mut [llvm_sys::prelude::LLVMValueRef],
+                name: &CStr,
+            ) -> llvm_sys::prelude::LLVMValueRef {
+                check_insertion_point(b);
+                llvm_sys::core::LLVMBuildCall2(
+                    b,
+                    resty,
+                    r#fn,
+                    args.as_mut_ptr(),
+                    args.len().try_into().unwrap(),
+                    name.as_ptr(),
+                )
+            }
+            /// Finds the smallest modulus of at most `max` under which all `keys`
+            /// map to distinct slots.
+            fn perfect_modulus(keys: &[u64], max: u64) -> Option<u64> {
+                let mut seen = std::collections::BTreeSet::new();
+                assert!(
+                    keys.iter().all(|k| seen.insert(*k)),
+                    "lookup keys must be distinct"
+                );
+                (keys.len() as u64..=max).find(|m| {
+                    seen.clear();
+                    keys.iter().all(|k| seen.insert(k % m))
+                })
+            }
+            /// Checks that `s` is a valid integer literal in base `radix` whose
+            /// magnitude fits in `width` bits, and strips its leading zeros, which
+            /// LLVM would otherwise count against the width.
+            fn int_literal(s: &CStr, radix: u8, width: u32) -> std::ffi::CString {
+                let text = s.to_str().ok().unwrap_or_else(|| panic!("invalid integer literal {s:?}"));
+                let (sign, digits) = match text.strip_prefix(['-', '+']) {
+                    Some(digits) => (&text[..1], digits),
+                    None => ("", text),
+                };
+                assert!(!digits.is_empty(), "empty integer literal {text:?}");
+                // Little-endian 64-bit words of the magnitude.
+                let mut words: Vec<u64> = vec![];
+                for c in digits.chars() {
+                    let digit = c
+                        .to_digit(radix.into())
+                        .unwrap_or_else(|| panic!("invalid digit {c:?} in base-{radix} literal {text:?}"));
+                    let mut carry = u128::from(digit);
+                    for w in &mut words {
+                        let v = u128::from(*w) * u128::from(radix) + carry;
+                        *w = v as u64;
+                        carry = v >> 64;
+                    }
+                    if carry != 0 {
+                        words.push(carry as u64);
+                    }
+                }
+                let bits = words.last().map_or(0, |top| 64 * words.len() as u32 - top.leading_zeros());
+                assert!(bits <= width, "integer literal {text:?} does not fit in i{width}");
+                let digits = digits.trim_start_matches('0');
+                std::ffi::CString::new(format!("{sign}{}", if digits.is_empty() { "0" } else { digits })).unwrap()
+            }
+            since!(18 $l {
+                /// Folds the cast `op` of the constant `val` to `ty` with a throwaway
+                /// builder, for the casts LLVM 18 removed as constant expressions.
+                unsafe fn fold_cast(
+                    op: llvm_sys::LLVMOpcode,
+                    val: llvm_sys::prelude::LLVMValueRef,
+                    ty: llvm_sys::prelude::LLVMTypeRef,
+                ) -> llvm_sys::prelude::LLVMValueRef {
+                    use llvm_sys::core::*;
+                    let b = LLVMCreateBuilderInContext(LLVMGetTypeContext(ty));
+                    let res = LLVMBuildCast(b, op, val, ty, c"".as_ptr());
+                    LLVMDisposeBuilder(b);
+                    if LLVMIsAConstant(res).is_null() {
+                        // Unfoldable casts come back as detached instructions.
+                        LLVMDeleteInstruction(res);
+                        panic!("{op:?} of this constant does not fold to a constant");
+                    }
+                    res
+                }
+            } else {});
+            #[cfg(feature = "safe-checks")]
+            thread_local! {
+                /// Builders last positioned with `position_before`, which may emit
+                /// into a block that already has a terminator.
+                static MID_BLOCK: std::cell::RefCell<std::collections::HashSet<usize>> = Default::default();
+            }
+            /// Records whether `b` is positioned before an instruction rather than
+            /// at the end of a block, for [`check_insertion_point`].
+            #[allow(unused_variables)]
+            fn set_mid_block(b: llvm_sys::prelude::LLVMBuilderRef, mid: bool) {
+                #[cfg(feature = "safe-checks")]
+                MID_BLOCK.with_borrow_mut(|builders| {
+                    if mid {
+                        builders.insert(b as usize);
+                    } else {
+                        builders.remove(&(b as usize));
+                    }
+                });
+            }
+            /// With the `safe-checks` feature, panics if `b` is not positioned or
+            /// would append to a block that already ends in a terminator; LLVM
+            /// itself would only reject the function later, or abort.
+            #[allow(unused_variables)]
+            unsafe fn check_insertion_point(b: llvm_sys::prelude::LLVMBuilderRef) {
+                #[cfg(feature = "safe-checks")]
+                {
+                    use llvm_sys::core::*;
+                    let block = LLVMGetInsertBlock(b);
+                    assert!(!block.is_null(), "builder is not positioned");
+                    if LLVMGetBasicBlockTerminator(block).is_null()
+                        || MID_BLOCK.with_borrow(|builders| builders.contains(&(b as usize)))
+                    {
+                        return;
+                    }
+                    let name = CStr::from_ptr(LLVMGetBasicBlockName(block));
+                    panic!("instruction emitted after the terminator of block {name:?}");
+                }
+            }
+            thread_local! {
+                /// Owned modules by context, for [`crate::Ctx::modules`].
+                static MODULES: std::cell::RefCell<
+                    std::collections::HashMap<usize, Vec<Weak<crate::LLShim<Normal, llvm_sys::LLVMModule>>>>,
+                > = Default::default();
+            }
+            /// Adds an owned module to its context's entry in `MODULES`.
+            fn register_module(m: &crate::LLHandle<'_, Normal, llvm_sys::LLVMModule>) {
+                let ctx = unsafe { llvm_sys::core::LLVMGetModuleContext(m.ptr()) };
+                MODULES.with_borrow_mut(|mods| {
+                    let mods = mods.entry(ctx as usize).or_default();
+                    mods.retain(|s| s.strong_count() != 0);
+                    mods.push(Rc::downgrade(&m.shim));
+                });
+            }
+            /// Converts an `LLVMErrorRef` into a `Result`, consuming the error.
+            unsafe fn check_error(err: llvm_sys::error::LLVMErrorRef) -> Result<(), String> {
+                if err.is_null() {
+                    return Ok(());
+                }
+                let msg = llvm_sys::error::LLVMGetErrorMessage(err);
+                let s = CStr::from_ptr(msg).to_string_lossy().into_owned();
+                llvm_sys::error::LLVMDisposeErrorMessage(msg);
+                Err(s)
+            }
+            type DiagnosticHandler = Box<dyn FnMut(crate::DiagnosticInfo)>;
+            extern "C" fn diagnostic_trampoline(
+                info: llvm_sys::prelude::LLVMDiagnosticInfoRef,
+                handler: *mut std::ffi::c_void,
+            ) {
+                use llvm_sys::core::*;
+                let handler = unsafe { &mut *(handler as *mut DiagnosticHandler) };
+                handler(crate::DiagnosticInfo {
+                    severity: unsafe { LLVMGetDiagInfoSeverity(info) }.into(),
+                    message: unsafe { take_message(LLVMGetDiagInfoDescription(info)) },
+                });
+            }
+            /// Returns the handler installed on `ctx` by `set_diagnostic_handler`,
+            /// if any.
+            unsafe fn diagnostic_handler(ctx: llvm_sys::prelude::LLVMContextRef) -> Option<*mut DiagnosticHandler> {
+                use llvm_sys::core::*;
+                let trampoline = diagnostic_trampoline as extern "C" fn(_, _) as usize;
+                if LLVMContextGetDiagnosticHandler(ctx).map(|h| h as usize) != Some(trampoline) {
+                    return None;
+                }
+                Some(LLVMContextGetDiagnosticContext(ctx) as *mut DiagnosticHandler)
+            }
+            /// Disposes of an owned context along with its diagnostic handler and
+            /// its entry in `MODULES`.
+            fn dispose_context(ctx: llvm_sys::prelude::LLVMContextRef, _: Normal) {
+                unsafe {
+                    let handler = diagnostic_handler(ctx);
+                    let _ = MODULES.try_with(|m| m.borrow_mut().remove(&(ctx as usize)));
+                    llvm_sys::core::LLVMContextDispose(ctx);
+                    if let Some(handler) = handler {
+                        drop(Box::from_raw(handler));
+                    }
+                }
+            }
+            /// Checks that `layout` is a well-formed data layout string.
+            ///
+            /// `LLVMCreateTargetData` aborts the process on a malformed layout,
+            /// while the IR parser reports the same error, so parse an empty
+            /// module declaring it first.
+            fn check_layout(layout: &CStr) -> Result<(), String> {
+                use llvm_sys::core::*;
+                let mut ir = b"target datalayout = \"".to_vec();
+                for &c in layout.to_bytes() {
+                    match c {
+                        b'"' | b'\\' => ir.extend(format!("\\{c:02X}").bytes()),
+                        c => ir.push(c),
+                    }
+                }
+                ir.extend(b"\"\n");
+                unsafe {
+                    let ctx = LLVMContextCreate();
+                    let buf = LLVMCreateMemoryBufferWithMemoryRangeCopy(ir.as_ptr().cast(), ir.len(), c"layout".as_ptr());
+                    let (mut module, mut msg) = (std::ptr::null_mut(), std::ptr::null_mut());
+                    // Takes ownership of `buf`.
+                    let res = if llvm_sys::ir_reader::LLVMParseIRInContext(ctx, buf, &mut module, &mut msg) != 0 {
+                        Err(take_message(msg))
+                    } else {
+                        LLVMDisposeModule(module);
+                        Ok(())
+                    };
+                    LLVMContextDispose(ctx);
+                    res
+                }
+            }
+            /// Copies and disposes of a message allocated by LLVM.
+            unsafe fn take_message(msg: *mut std::ffi::c_char) -> String {
+                let s = CStr::from_ptr(msg).to_string_lossy().into_owned();
+                llvm_sys::core::LLVMDisposeMessage(msg);
+                s
+            }
+            seal!(
+             <'a>  =>   crate::LLHandle<'a,Normal,llvm_sys::LLVMContext>,
+              <'a>  =>  crate::LLHandle<'a,Normal,llvm_sys::LLVMModule>,
+               <> =>  llvm_sys::LLVMValue,
+              <'a>  =>  crate::LLHandle<'a,Normal,llvm_sys::LLVMBasicBlock>,
+              <'a>  =>  crate::LLHandle<'a,Normal,llvm_sys::LLVMBuilder>,
+              <'a>  =>  crate::LLHandle<'a,Normal,llvm_sys::LLVMType>,
+              <'a>  =>  crate::LLHandle<'a,Normal,llvm_sys::LLVMOpaqueMetadata>,
+              <'a>  =>  crate::LLHandle<'a,Normal,llvm_sys::LLVMOpaqueDIBuilder>,
+              <'a>  =>  crate::LLHandle<'a,Normal,llvm_sys::target_machine::LLVMOpaqueTargetMachine>,
+              <'a>  =>  crate::LLHandle<'a,Normal,llvm_sys::target_machine::LLVMTarget>,
+              <'a>  =>  crate::LLHandle<'a,Normal,llvm_sys::orc2::lljit::LLVMOrcOpaqueLLJIT>,
+              <'a>  =>  crate::LLHandle<'a,Normal,llvm_sys::target::LLVMOpaqueTargetData>,
+              <'a>  =>  crate::LLHandle<'a,Normal,llvm_sys::LLVMComdat>,
+              <'a>  =>  crate::LLHandle<'a,Normal,llvm_sys::LLVMPassManager>,
+            );
+            // Debug output is the textual IR, so `dbg!` and failed assertions show
+            // what was actually built.
+            impl<'a> std::fmt::Debug for crate::LLHandle<'a, Normal, llvm_sys::LLVMModule> {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str(&unsafe { take_message(llvm_sys::core::LLVMPrintModuleToString(self.ptr())) })
+                }
+            }
+            impl<'a, K> std::fmt::Debug for crate::LLHandle<'a, K, llvm_sys::LLVMValue> {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str(&unsafe { take_message(llvm_sys::core::LLVMPrintValueToString(self.ptr())) })
+                }
+            }
+            impl<'a> std::fmt::Debug for crate::LLHandle<'a, Normal, llvm_sys::LLVMType> {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str(&unsafe { take_message(llvm_sys::core::LLVMPrintTypeToString(self.ptr())) })
+                }
+            }
+            impl<'a> crate::TargetData<'a> for crate::LLHandle<'a, Normal, llvm_sys::target::LLVMOpaqueTargetData> {
+                type Ty<'b> = crate::LLHandle<'b, Normal, llvm_sys::LLVMType> where Self: 'b;
+                fn from_layout(layout: &CStr) -> Result<Self, String> {
+                    check_layout(layout)?;
+                    let ptr = unsafe { llvm_sys::target::LLVMCreateTargetData(layout.as_ptr()) };
+                    Ok(unsafe {
+                        crate::LLHandle::from_raw_parts(
+                            ptr,
+                            |a, _| llvm_sys::target::LLVMDisposeTargetData(a),
+                            Normal,
+                        )
+                    })
+                }
+                fn size_of(&self, ty: Self::Ty<'a>) -> u64 {
+                    assert!(ty.is_sized(), "type is unsized");
+                    unsafe { llvm_sys::target::LLVMABISizeOfType(self.ptr(), ty.ptr()) }
+                }
+                fn abi_align_of(&self, ty: Self::Ty<'a>) -> u32 {
+                    assert!(ty.is_sized(), "type is unsized");
+                    unsafe { llvm_sys::target::LLVMABIAlignmentOfType(self.ptr(), ty.ptr()) }
+                }
+                fn offset_of_element(&self, struct_ty: Self::Ty<'a>, index: u32) -> u64 {
+                    assert!(struct_ty.kind() == crate::TypeKind::Struct && struct_ty.is_sized(), "not a sized struct type");
+                    let count = unsafe { llvm_sys::core::LLVMCountStructElementTypes(struct_ty.ptr()) };
+                    assert!(index < count, "struct has no field {index}");
+                    unsafe { llvm_sys::target::LLVMOffsetOfElement(self.ptr(), struct_ty.ptr(), index) }
+                }
+                fn element_at_offset(&self, struct_ty: Self::Ty<'a>, offset: u64) -> u32 {
+                    assert!(struct_ty.kind() == crate::TypeKind::Struct && struct_ty.is_sized(), "not a sized struct type");
+                    unsafe { llvm_sys::target::LLVMElementAtOffset(self.ptr(), struct_ty.ptr(), offset) }
+                }
+            }
+            impl<'a> crate::Jit<'a> for crate::LLHandle<'a, Normal, llvm_sys::orc2::lljit::LLVMOrcOpaqueLLJIT> {
+                type Mod<'b> = crate::LLHandle<'b, Normal, llvm_sys::LLVMModule> where Self: 'b;
+                fn create() -> Result<Self, String> {
+                    let mut jit = std::ptr::null_mut();
+                    unsafe {
+                        check_error(llvm_sys::orc2::lljit::LLVMOrcCreateLLJIT(&mut jit, std::ptr::null_mut()))?;
+                        Ok(crate::LLHandle::from_raw_parts(
+                            jit,
+                            |a, _| {
+                                let _ = check_error(llvm_sys::orc2::lljit::LLVMOrcDisposeLLJIT(a));
+                            },
+                            Normal,
+                        ))
+                    }
+                }
+                fn add_module(&self, module: Self::Mod<'a>) -> Result<(), String> {
+                    use llvm_sys::orc2::{lljit::*, *};
+                    let ctx = module.owner::<Normal, llvm_sys::LLVMContext>();
+                    let Ok(module) = module.try_into_raw() else {
+                        return Err("the JIT must be given the only owning handle to a module".to_owned());
+                    };
+                    // The JIT frees the module, so its context has to live as
+                    // long as the JIT does.
+                    if let Some(ctx) = ctx {
+                        self.shim.owners.borrow_mut().push(ctx.shim);
+                    }
+                    unsafe {
+                        // The module stays in its own context; the thread-safe
+                        // context only supplies the lock ORC compiles it under,
+                        // and is kept alive by the module that references it.
+                        let tsc = LLVMOrcCreateNewThreadSafeContext();
+                        let tsm = LLVMOrcCreateNewThreadSafeModule(module, tsc);
+                        LLVMOrcDisposeThreadSafeContext(tsc);
+                        check_error(LLVMOrcLLJITAddLLVMIRModule(
+                            self.ptr(),
+                            LLVMOrcLLJITGetMainJITDylib(self.ptr()),
+                            tsm,
+                        ))
+                    }
+                }
+                fn lookup_address(&self, name: &CStr) -> Result<u64, String> {
+                    let mut addr = 0;
+                    unsafe {
+                        check_error(llvm_sys::orc2::lljit::LLVMOrcLLJITLookup(self.ptr(), &mut addr, name.as_ptr()))?;
+                    }
+                    Ok(addr)
+                }
+            }
+            since!(17 $l {} else {
+                impl<'a> crate::FunctionPassManager<'a> for crate::LLHandle<'a, Normal, llvm_sys::LLVMPassManager> {
+                    type Mod<'b> = crate::LLHandle<'b, Normal, llvm_sys::LLVMModule> where Self: 'b;
+                    type Func<'b> = crate::LLHandle<'b, FuncTag, llvm_sys::LLVMValue> where Self: 'b;
+                    fn for_module(module: &Self::Mod<'a>) -> Self {
+                        let ptr = unsafe { llvm_sys::core::LLVMCreateFunctionPassManagerForModule(module.ptr()) };
+                        unsafe {
+                            crate::LLHandle::from_raw_parts(ptr, |a, _| llvm_sys::core::LLVMDisposePassManager(a), Normal)
+                        }
+                        .owned_by(module)
+                    }
+                    fn add_instruction_combining_pass(&self) {
+                        unsafe { llvm_sys::transforms::instcombine::LLVMAddInstructionCombiningPass(self.ptr()) }
+                    }
+                    fn add_cfg_simplification_pass(&self) {
+                        unsafe { llvm_sys::transforms::scalar::LLVMAddCFGSimplificationPass(self.ptr()) }
+                    }
+                    fn add_promote_memory_to_register_pass(&self) {
+                        unsafe { llvm_sys::transforms::util::LLVMAddPromoteMemoryToRegisterPass(self.ptr()) }
+                    }
+                    fn add_reassociate_pass(&self) {
+                        unsafe { llvm_sys::transforms::scalar::LLVMAddReassociatePass(self.ptr()) }
+                    }
+                    fn add_gvn_pass(&self) {
+                        unsafe { llvm_sys::transforms::scalar::LLVMAddGVNPass(self.ptr()) }
+                    }
+                    fn run(&self, func: Self::Func<'a>) -> bool {
+                        use llvm_sys::core::*;
+                        unsafe {
+                            LLVMInitializeFunctionPassManager(self.ptr());
+                            let changed = LLVMRunFunctionPassManager(self.ptr(), func.ptr()) != 0;
+                            LLVMFinalizeFunctionPassManager(self.ptr());
+                            changed
+                        }
+                    }
+                }
+            });
+            impl From<crate::CodeGenOptLevel> for llvm_sys::target_machine::LLVMCodeGenOptLevel{
+                fn from(a: crate::CodeGenOptLevel) -> Self{
+                    use llvm_sys::target_machine::LLVMCodeGenOptLevel as L;
+                    match a{
+                        crate::CodeGenOptLevel::None => L::LLVMCodeGenLevelNone,
+                        crate::CodeGenOptLevel::Less => L::LLVMCodeGenLevelLess,
+                        crate::CodeGenOptLevel::Default => L::LLVMCodeGenLevelDefault,
+                        crate::CodeGenOptLevel::Aggressive => L::LLVMCodeGenLevelAggressive,
+                    }
+                }
+            }
+            impl From<crate::RelocMode> for llvm_sys::target_machine::LLVMRelocMode{
+                fn from(a: crate::RelocMode) -> Self{
+                    use llvm_sys::target_machine::LLVMRelocMode as L;
+                    match a{
+                        crate::RelocMode::Default => L::LLVMRelocDefault,
+                        crate::RelocMode::Static => L::LLVMRelocStatic,
+                        crate::RelocMode::Pic => L::LLVMRelocPIC,
+                        crate::RelocMode::DynamicNoPic => L::LLVMRelocDynamicNoPic,
+                    }
+                }
+            }
+            impl From<crate::CodeModel> for llvm_sys::target_machine::LLVMCodeModel{
+                fn from(a: crate::CodeModel) -> Self{
+                    use llvm_sys::target_machine::LLVMCodeModel as L;
+                    match a{
+                        crate::CodeModel::Default => L::LLVMCodeModelDefault,
+                        crate::CodeModel::JitDefault => L::LLVMCodeModelJITDefault,
+                        crate::CodeModel::Tiny => L::LLVMCodeModelTiny,
+                        crate::CodeModel::Small => L::LLVMCodeModelSmall,
+                        crate::CodeModel::Kernel => L::LLVMCodeModelKernel,
+                        crate::CodeModel::Medium => L::LLVMCodeModelMedium,
+                        crate::CodeModel::Large => L::LLVMCodeModelLarge,
+                    }
+                }
+            }
+            impl From<crate::FileType> for llvm_sys::target_machine::LLVMCodeGenFileType{
+                fn from(a: crate::FileType) -> Self{
+                    use llvm_sys::target_machine::LLVMCodeGenFileType as L;
+                    match a{
+                        crate::FileType::Assembly => L::LLVMAssemblyFile,
+                        crate::FileType::Object => L::LLVMObjectFile,
+                    }
+                }
+            }
+            impl<'a> crate::Target<'a> for crate::LLHandle<'a, Normal, llvm_sys::target_machine::LLVMTarget> {
+                type TargetMachine<'b>
+                    = crate::LLHandle<'b, Normal, llvm_sys::target_machine::LLVMOpaqueTargetMachine>
+                where
+                    Self: 'b;
+                fn from_triple(triple: &CStr) -> Result<Self, String> {
+                    let mut target = std::ptr::null_mut();
+                    let mut err = std::ptr::null_mut();
+                    unsafe {
+                        if llvm_sys::target_machine::LLVMGetTargetFromTriple(triple.as_ptr(), &mut target, &mut err) != 0 {
+                            return Err(take_message(err));
+                        }
+                        Ok(crate::LLHandle::leaked(target, Normal))
+                    }
+                }
+                fn create_target_machine(
+                    &self,
+                    triple: &CStr,
+                    cpu: &CStr,
+                    features: &CStr,
+                    opt_level: crate::CodeGenOptLevel,
+                    reloc: crate::RelocMode,
+                    code_model: crate::CodeModel,
+                ) -> Result<Self::TargetMachine<'a>, String> {
+                    let ptr = unsafe {
+                        llvm_sys::target_machine::LLVMCreateTargetMachine(
+                            self.ptr(),
+                            triple.as_ptr(),
+                            cpu.as_ptr(),
+                            features.as_ptr(),
+                            opt_level.into(),
+                            reloc.into(),
+                            code_model.into(),
+                        )
+                    };
+                    if ptr.is_null() {
+                        return Err(format!("cannot create a target machine for {}", triple.to_string_lossy()));
+                    }
+                    Ok(unsafe {
+                        crate::LLHandle::from_raw_parts(
+                            ptr,
+                            |a, _| llvm_sys::target_machine::LLVMDisposeTargetMachine(a),
+                            Normal,
+                        )
+                    })
+                }
+            }
+            impl<'a> crate::TargetMachine<'a> for crate::LLHandle<'a, Normal, llvm_sys::target_machine::LLVMOpaqueTargetMachine> {
+                type Mod<'b> = crate::LLHandle<'b, Normal, llvm_sys::LLVMModule> where Self: 'b;
+                fn emit_to_file(
+                    &self,
+                    module: &Self::Mod<'a>,
+                    file_type: crate::FileType,
+                    path: &CStr,
+                ) -> Result<(), String> {
+                    let mut err = std::ptr::null_mut();
+                    unsafe {
+                        if llvm_sys::target_machine::LLVMTargetMachineEmitToFile(
+                            self.ptr(),
+                            module.ptr(),
+                            path.as_ptr() as _,
+                            file_type.into(),
+                            &mut err,
+                        ) != 0
+                        {
+                            return Err(take_message(err));
+                        }
+                    }
+                    Ok(())
+                }
+                fn emit_to_memory(
+                    &self,
+                    module: &Self::Mod<'a>,
+                    file_type: crate::FileType,
+                ) -> Result<Vec<u8>, String> {
+                    use llvm_sys::core::*;
+                    let mut err = std::ptr::null_mut();
+                    let mut buf = std::ptr::null_mut();
+                    unsafe {
+                        if llvm_sys::target_machine::LLVMTargetMachineEmitToMemoryBuffer(
+                            self.ptr(),
+                            module.ptr(),
+                            file_type.into(),
+                            &mut err,
+                            &mut buf,
+                        ) != 0
+                        {
+                            return Err(take_message(err));
+                        }
+                        let bytes =
+                            std::slice::from_raw_parts(LLVMGetBufferStart(buf) as *const u8, LLVMGetBufferSize(buf))
+                                .to_vec();
+                        LLVMDisposeMemoryBuffer(buf);
+                        Ok(bytes)
+                    }
+                }
+            }
+            impl From<crate::SourceLanguage> for llvm_sys::debuginfo::LLVMDWARFSourceLanguage{
+                fn from(a: crate::SourceLanguage) -> Self{
+                    use llvm_sys::debuginfo::LLVMDWARFSourceLanguage as L;
+                    match a{
+                        crate::SourceLanguage::C => L::LLVMDWARFSourceLanguageC,
+                        crate::SourceLanguage::C99 => L::LLVMDWARFSourceLanguageC99,
+                        crate::SourceLanguage::C11 => L::LLVMDWARFSourceLanguageC11,
+                        crate::SourceLanguage::CPlusPlus => L::LLVMDWARFSourceLanguageC_plus_plus,
+                        crate::SourceLanguage::Rust => L::LLVMDWARFSourceLanguageRust,
+                    }
+                }
+            }
+            impl From<crate::ICmp> for llvm_sys::LLVMIntPredicate{
+                fn from(a: crate::ICmp) -> Self{
+                    match a{
+                        crate::ICmp::Eq => llvm_sys::LLVMIntPredicate::LLVMIntEQ,
+                        crate ::ICmp::Lt => llvm_sys::LLVMIntPredicate::LLVMIntULT,
+                        crate ::ICmp::Lts => llvm_sys::LLVMIntPredicate::LLVMIntSLT,
+                    }
+                }
+            }
+            since!(18 $l {
+            impl From<crate::TailCallKind> for llvm_sys::LLVMTailCallKind{
+                fn from(a: crate::TailCallKind) -> Self{
+                    match a{
+                        crate::TailCallKind::None => llvm_sys::LLVMTailCallKind::LLVMTailCallKindNone,
+                        crate::TailCallKind::Tail => llvm_sys::LLVMTailCallKind::LLVMTailCallKindTail,
+                        crate::TailCallKind::MustTail => llvm_sys::LLVMTailCallKind::LLVMTailCallKindMustTail,
+                        crate::TailCallKind::NoTail => llvm_sys::LLVMTailCallKind::LLVMTailCallKindNoTail,
+                    }
+                }
+            }
+            } else {});
+            impl<'a> crate::LLHandle<'a, Normal, llvm_sys::LLVMContext> {
+                /// Wraps a context owned elsewhere.
+                ///
+                /// # Safety
+                ///
+                /// `ptr` must be a valid context that outlives the handle.
+                pub unsafe fn from_raw_leaked(ptr: llvm_sys::prelude::LLVMContextRef) -> Self {
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                /// Takes ownership of a context, disposing of it with the last handle.
+                ///
+                /// # Safety
+                ///
+                /// `ptr` must be a valid context that is not disposed of by other means.
+                pub unsafe fn from_raw_owned(ptr: llvm_sys::prelude::LLVMContextRef) -> Self {
+                    unsafe { crate::LLHandle::from_raw_parts(ptr, dispose_context, Normal) }
+                }
+            }
+            impl<'a> crate::LLHandle<'a, Normal, llvm_sys::LLVMModule> {
+                /// Wraps a module owned elsewhere.
+                ///
+                /// # Safety
+                ///
+                /// `ptr` must be a valid module that outlives the handle.
+                pub unsafe fn from_raw_leaked(ptr: llvm_sys::prelude::LLVMModuleRef) -> Self {
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                /// Takes ownership of a module, disposing of it with the last handle.
+                ///
+                /// # Safety
+                ///
+                /// `ptr` must be a valid module that is not disposed of by other means.
+                pub unsafe fn from_raw_owned(ptr: llvm_sys::prelude::LLVMModuleRef) -> Self {
+                    let handle =
+                        unsafe { crate::LLHandle::from_raw_parts(ptr, |a, _| llvm_sys::core::LLVMDisposeModule(a), Normal) };
+                    register_module(&handle);
+                    handle
+                }
+            }
+            impl<'a> crate::LLHandle<'a, Normal, llvm_sys::LLVMBuilder> {
+                /// Wraps a builder owned elsewhere.
+                ///
+                /// # Safety
+                ///
+                /// `ptr` must be a valid builder that outlives the handle.
+                pub unsafe fn from_raw_leaked(ptr: llvm_sys::prelude::LLVMBuilderRef) -> Self {
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                /// Takes ownership of a builder, disposing of it with the last handle.
+                ///
+                /// # Safety
+                ///
+                /// `ptr` must be a valid builder that is not disposed of by other means.
+                pub unsafe fn from_raw_owned(ptr: llvm_sys::prelude::LLVMBuilderRef) -> Self {
+                    unsafe {
+                        crate::LLHandle::from_raw_parts(
+                            ptr,
+                            |a, _| {
+                                set_mid_block(a, false);
+                                llvm_sys::core::LLVMDisposeBuilder(a)
+                            },
+                            Normal,
+                        )
+                    }
+                }
+            }
+            impl<'a> crate::LLHandle<'a, FuncTag, llvm_sys::LLVMValue> {
+                /// Wraps a function. Functions are owned by their module, so there is
+                /// no owning counterpart.
+                ///
+                /// # Safety
+                ///
+                /// `ptr` must be a valid value whose module outlives the handle.
+                ///
+                /// # Panics
+                ///
+                /// Panics if `ptr` is not a function.
+                pub unsafe fn from_raw_leaked(ptr: llvm_sys::prelude::LLVMValueRef) -> Self {
+                    assert!(!unsafe { llvm_sys::core::LLVMIsAFunction(ptr) }.is_null(), "value is not a function");
+                    unsafe { crate::LLHandle::leaked(ptr, FuncTag) }
+                }
+            }
+            impl<'a> crate::LLHandle<'a, Normal, llvm_sys::LLVMValue> {
+                /// Wraps a value. Values are owned by their module or context, so there
+                /// is no owning counterpart.
+                ///
+                /// # Safety
+                ///
+                /// `ptr` must be a valid value whose owner outlives the handle.
+                pub unsafe fn from_raw_leaked(ptr: llvm_sys::prelude::LLVMValueRef) -> Self {
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+            }
+            impl<'a> crate::LLHandle<'a, Normal, llvm_sys::LLVMBasicBlock> {
+                /// Wraps a basic block. Blocks are owned by their function, so there is
+                /// no owning counterpart.
+                ///
+                /// # Safety
+                ///
+                /// `ptr` must be a valid basic block whose function outlives the handle.
+                pub unsafe fn from_raw_leaked(ptr: llvm_sys::prelude::LLVMBasicBlockRef) -> Self {
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+            }
+            impl<'a> crate::LLHandle<'a, Normal, llvm_sys::LLVMType> {
+                /// Wraps a type. Types are owned by their context, so there is no
+                /// owning counterpart.
+                ///
+                /// # Safety
+                ///
+                /// `ptr` must be a valid type whose context outlives the handle.
+                pub unsafe fn from_raw_leaked(ptr: llvm_sys::prelude::LLVMTypeRef) -> Self {
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+            }
+            #[cfg(feature = "inkwell-interop")]
+            const _: () = {
+                use inkwell::types::AsTypeRef;
+                use inkwell::values::AsValueRef;
+                impl<'ctx> From<&'ctx inkwell::context::Context> for crate::LLHandle<'ctx, Normal, llvm_sys::LLVMContext> {
+                    fn from(a: &'ctx inkwell::context::Context) -> Self {
+                        unsafe { crate::LLHandle::leaked(a.raw(), Normal) }
+                    }
+                }
+                impl<'ctx> From<&inkwell::module::Module<'ctx>> for crate::LLHandle<'ctx, Normal, llvm_sys::LLVMModule> {
+                    fn from(a: &inkwell::module::Module<'ctx>) -> Self {
+                        unsafe { crate::LLHandle::leaked(a.as_mut_ptr(), Normal) }
+                    }
+                }
+                impl<'ctx> From<&inkwell::builder::Builder<'ctx>> for crate::LLHandle<'ctx, Normal, llvm_sys::LLVMBuilder> {
+                    fn from(a: &inkwell::builder::Builder<'ctx>) -> Self {
+                        unsafe { crate::LLHandle::leaked(a.as_mut_ptr(), Normal) }
+                    }
+                }
+                impl<'ctx> From<inkwell::basic_block::BasicBlock<'ctx>> for crate::LLHandle<'ctx, Normal, llvm_sys::LLVMBasicBlock> {
+                    fn from(a: inkwell::basic_block::BasicBlock<'ctx>) -> Self {
+                        unsafe { crate::LLHandle::leaked(a.as_mut_ptr(), Normal) }
+                    }
+                }
+                impl<'ctx> From<inkwell::values::FunctionValue<'ctx>> for crate::LLHandle<'ctx, FuncTag, llvm_sys::LLVMValue> {
+                    fn from(a: inkwell::values::FunctionValue<'ctx>) -> Self {
+                        unsafe { crate::LLHandle::leaked(a.as_value_ref(), FuncTag) }
+                    }
+                }
+                impl<'ctx> From<inkwell::values::BasicValueEnum<'ctx>> for crate::LLHandle<'ctx, Normal, llvm_sys::LLVMValue> {
+                    fn from(a: inkwell::values::BasicValueEnum<'ctx>) -> Self {
+                        unsafe { crate::LLHandle::leaked(a.as_value_ref(), Normal) }
+                    }
+                }
+                impl<'ctx> From<inkwell::values::AnyValueEnum<'ctx>> for crate::LLHandle<'ctx, Normal, llvm_sys::LLVMValue> {
+                    fn from(a: inkwell::values::AnyValueEnum<'ctx>) -> Self {
+                        unsafe { crate::LLHandle::leaked(a.as_value_ref(), Normal) }
+                    }
+                }
+                impl<'ctx> From<inkwell::types::BasicTypeEnum<'ctx>> for crate::LLHandle<'ctx, Normal, llvm_sys::LLVMType> {
+                    fn from(a: inkwell::types::BasicTypeEnum<'ctx>) -> Self {
+                        unsafe { crate::LLHandle::leaked(a.as_type_ref(), Normal) }
+                    }
+                }
+                impl<'ctx> From<inkwell::types::AnyTypeEnum<'ctx>> for crate::LLHandle<'ctx, Normal, llvm_sys::LLVMType> {
+                    fn from(a: inkwell::types::AnyTypeEnum<'ctx>) -> Self {
+                        unsafe { crate::LLHandle::leaked(a.as_type_ref(), Normal) }
+                    }
+                }
+                impl<'ctx> TryFrom<crate::LLHandle<'ctx, Normal, llvm_sys::LLVMModule>> for inkwell::module::Module<'ctx> {
+                    type Error = crate::LLHandle<'ctx, Normal, llvm_sys::LLVMModule>;
+                    fn try_from(a: crate::LLHandle<'ctx, Normal, llvm_sys::LLVMModule>) -> Result<Self, Self::Error> {
+                        Ok(unsafe { inkwell::module::Module::new(a.try_into_raw()?) })
+                    }
+                }
+                impl<'ctx> From<crate::LLHandle<'ctx, Normal, llvm_sys::LLVMBasicBlock>> for inkwell::basic_block::BasicBlock<'ctx> {
+                    fn from(a: crate::LLHandle<'ctx, Normal, llvm_sys::LLVMBasicBlock>) -> Self {
+                        unsafe { inkwell::basic_block::BasicBlock::new(a.ptr()) }.expect("basic block handle is null")
+                    }
+                }
+                impl<'ctx> From<crate::LLHandle<'ctx, FuncTag, llvm_sys::LLVMValue>> for inkwell::values::FunctionValue<'ctx> {
+                    fn from(a: crate::LLHandle<'ctx, FuncTag, llvm_sys::LLVMValue>) -> Self {
+                        unsafe { inkwell::values::FunctionValue::new(a.ptr()) }.expect("function handle is not a function")
+                    }
+                }
+                impl<'ctx> From<crate::LLHandle<'ctx, Normal, llvm_sys::LLVMValue>> for inkwell::values::AnyValueEnum<'ctx> {
+                    fn from(a: crate::LLHandle<'ctx, Normal, llvm_sys::LLVMValue>) -> Self {
+                        unsafe { inkwell::values::AnyValueEnum::new(a.ptr()) }
+                    }
+                }
+                impl<'ctx> From<crate::LLHandle<'ctx, Normal, llvm_sys::LLVMType>> for inkwell::types::AnyTypeEnum<'ctx> {
+                    fn from(a: crate::LLHandle<'ctx, Normal, llvm_sys::LLVMType>) -> Self {
+                        unsafe { inkwell::types::AnyTypeEnum::new(a.ptr()) }
+                    }
+                }
+            };
+            impl From<llvm_sys::LLVMDiagnosticSeverity> for crate::DiagnosticSeverity{
+                fn from(a: llvm_sys::LLVMDiagnosticSeverity) -> Self{
+                    use llvm_sys::LLVMDiagnosticSeverity as S;
+                    match a{
+                        S::LLVMDSError => crate::DiagnosticSeverity::Error,
+                        S::LLVMDSWarning => crate::DiagnosticSeverity::Warning,
+                        S::LLVMDSRemark => crate::DiagnosticSeverity::Remark,
+                        S::LLVMDSNote => crate::DiagnosticSeverity::Note,
+                    }
+                }
+            }
+            impl From<crate::AsmDialect> for llvm_sys::LLVMInlineAsmDialect{
+                fn from(a: crate::AsmDialect) -> Self{
+                    match a{
+                        crate::AsmDialect::Att => llvm_sys::LLVMInlineAsmDialect::LLVMInlineAsmDialectATT,
+                        crate::AsmDialect::Intel => llvm_sys::LLVMInlineAsmDialect::LLVMInlineAsmDialectIntel,
+                    }
+                }
+            }
+            impl From<crate::ThreadLocalMode> for llvm_sys::LLVMThreadLocalMode{
+                fn from(a: crate::ThreadLocalMode) -> Self{
+                    use llvm_sys::LLVMThreadLocalMode as M;
+                    match a{
+                        crate::ThreadLocalMode::NotThreadLocal => M::LLVMNotThreadLocal,
+                        crate::ThreadLocalMode::GeneralDynamic => M::LLVMGeneralDynamicTLSModel,
+                        crate::ThreadLocalMode::LocalDynamic => M::LLVMLocalDynamicTLSModel,
+                        crate::ThreadLocalMode::InitialExec => M::LLVMInitialExecTLSModel,
+                        crate::ThreadLocalMode::LocalExec => M::LLVMLocalExecTLSModel,
+                    }
+                }
+            }
+            impl From<llvm_sys::LLVMThreadLocalMode> for crate::ThreadLocalMode{
+                fn from(a: llvm_sys::LLVMThreadLocalMode) -> Self{
+                    use llvm_sys::LLVMThreadLocalMode as M;
+                    match a{
+                        M::LLVMNotThreadLocal => crate::ThreadLocalMode::NotThreadLocal,
+                        M::LLVMGeneralDynamicTLSModel => crate::ThreadLocalMode::GeneralDynamic,
+                        M::LLVMLocalDynamicTLSModel => crate::ThreadLocalMode::LocalDynamic,
+                        M::LLVMInitialExecTLSModel => crate::ThreadLocalMode::InitialExec,
+                        M::LLVMLocalExecTLSModel => crate::ThreadLocalMode::LocalExec,
+                    }
+                }
+            }
+            impl From<crate::UnnamedAddr> for llvm_sys::LLVMUnnamedAddr{
+                fn from(a: crate::UnnamedAddr) -> Self{
+                    use llvm_sys::LLVMUnnamedAddr as U;
+                    match a{
+                        crate::UnnamedAddr::None => U::LLVMNoUnnamedAddr,
+                        crate::UnnamedAddr::Local => U::LLVMLocalUnnamedAddr,
+                        crate::UnnamedAddr::Global => U::LLVMGlobalUnnamedAddr,
+                    }
+                }
+            }
+            impl From<llvm_sys::LLVMUnnamedAddr> for crate::UnnamedAddr{
+                fn from(a: llvm_sys::LLVMUnnamedAddr) -> Self{
+                    use llvm_sys::LLVMUnnamedAddr as U;
+                    match a{
+                        U::LLVMNoUnnamedAddr => crate::UnnamedAddr::None,
+                        U::LLVMLocalUnnamedAddr => crate::UnnamedAddr::Local,
+                        U::LLVMGlobalUnnamedAddr => crate::UnnamedAddr::Global,
+                    }
+                }
+            }
+            impl From<crate::BinOp> for llvm_sys::LLVMOpcode{
+                fn from(a: crate::BinOp) -> Self{
+                    match a{
+                        crate::BinOp::Add => llvm_sys::LLVMOpcode::LLVMAdd,
+                        crate::BinOp::Sub => llvm_sys::LLVMOpcode::LLVMSub,
+                        crate::BinOp::Mul => llvm_sys::LLVMOpcode::LLVMMul,
+                        crate::BinOp::And => llvm_sys::LLVMOpcode::LLVMAnd,
+                        crate::BinOp::Or => llvm_sys::LLVMOpcode::LLVMOr,
+                        crate::BinOp::Xor => llvm_sys::LLVMOpcode::LLVMXor,
+                    }
+                }
+            }
+            impl From<llvm_sys::LLVMValueKind> for crate::ValueClass{
+                fn from(a: llvm_sys::LLVMValueKind) -> Self{
+                    use llvm_sys::LLVMValueKind as K;
+                    use crate::ValueClass as C;
+                    #[allow(unreachable_patterns)]
+                    match a{
+                        K::LLVMArgumentValueKind => C::Argument,
+                        K::LLVMBasicBlockValueKind => C::BasicBlock,
+                        K::LLVMMemoryUseValueKind => C::MemoryUse,
+                        K::LLVMMemoryDefValueKind => C::MemoryDef,
+                        K::LLVMMemoryPhiValueKind => C::MemoryPhi,
+                        K::LLVMFunctionValueKind => C::Function,
+                        K::LLVMGlobalAliasValueKind => C::GlobalAlias,
+                        K::LLVMGlobalIFuncValueKind => C::GlobalIFunc,
+                        K::LLVMGlobalVariableValueKind => C::GlobalVariable,
+                        K::LLVMBlockAddressValueKind => C::BlockAddress,
+                        K::LLVMConstantExprValueKind => C::ConstantExpr,
+                        K::LLVMConstantArrayValueKind => C::ConstantArray,
+                        K::LLVMConstantStructValueKind => C::ConstantStruct,
+                        K::LLVMConstantVectorValueKind => C::ConstantVector,
+                        K::LLVMUndefValueValueKind => C::UndefValue,
+                        K::LLVMConstantAggregateZeroValueKind => C::ConstantAggregateZero,
+                        K::LLVMConstantDataArrayValueKind => C::ConstantDataArray,
+                        K::LLVMConstantDataVectorValueKind => C::ConstantDataVector,
+                        K::LLVMConstantIntValueKind => C::ConstantInt,
+                        K::LLVMConstantFPValueKind => C::ConstantFP,
+                        K::LLVMConstantPointerNullValueKind => C::ConstantPointerNull,
+                        K::LLVMConstantTokenNoneValueKind => C::ConstantTokenNone,
+                        K::LLVMMetadataAsValueValueKind => C::MetadataAsValue,
+                        K::LLVMInlineAsmValueKind => C::InlineAsm,
+                        K::LLVMInstructionValueKind => C::Instruction,
+                        K::LLVMPoisonValueKind => C::PoisonValue,
+                        K::LLVMConstantTargetNoneValueKind => C::ConstantTargetNone,
+                        _ => C::Other,
+                    }
+                }
+            }
+            impl From<llvm_sys::LLVMOpcode> for crate::Opcode{
+                fn from(a: llvm_sys::LLVMOpcode) -> Self{
+                    use llvm_sys::LLVMOpcode as O;
+                    use crate::Opcode as C;
+                    #[allow(unreachable_patterns)]
+                    match a{
+                        O::LLVMRet => C::Ret,
+                        O::LLVMBr => C::Br,
+                        O::LLVMSwitch => C::Switch,
+                        O::LLVMIndirectBr => C::IndirectBr,
+                        O::LLVMInvoke => C::Invoke,
+                        O::LLVMUnreachable => C::Unreachable,
+                        O::LLVMCallBr => C::CallBr,
+                        O::LLVMFNeg => C::FNeg,
+                        O::LLVMAdd => C::Add,
+                        O::LLVMFAdd => C::FAdd,
+                        O::LLVMSub => C::Sub,
+                        O::LLVMFSub => C::FSub,
+                        O::LLVMMul => C::Mul,
+                        O::LLVMFMul => C::FMul,
+                        O::LLVMUDiv => C::UDiv,
+                        O::LLVMSDiv => C::SDiv,
+                        O::LLVMFDiv => C::FDiv,
+                        O::LLVMURem => C::URem,
+                        O::LLVMSRem => C::SRem,
+                        O::LLVMFRem => C::FRem,
+                        O::LLVMShl => C::Shl,
+                        O::LLVMLShr => C::LShr,
+                        O::LLVMAShr => C::AShr,
+                        O::LLVMAnd => C::And,
+                        O::LLVMOr => C::Or,
+                        O::LLVMXor => C::Xor,
+                        O::LLVMAlloca => C::Alloca,
+                        O::LLVMLoad => C::Load,
+                        O::LLVMStore => C::Store,
+                        O::LLVMGetElementPtr => C::GetElementPtr,
+                        O::LLVMTrunc => C::Trunc,
+                        O::LLVMZExt => C::ZExt,
+                        O::LLVMSExt => C::SExt,
+                        O::LLVMFPToUI => C::FPToUI,
+                        O::LLVMFPToSI => C::FPToSI,
+                        O::LLVMUIToFP => C::UIToFP,
+                        O::LLVMSIToFP => C::SIToFP,
+                        O::LLVMFPTrunc => C::FPTrunc,
+                        O::LLVMFPExt => C::FPExt,
+                        O::LLVMPtrToInt => C::PtrToInt,
+                        O::LLVMIntToPtr => C::IntToPtr,
+                        O::LLVMBitCast => C::BitCast,
+                        O::LLVMAddrSpaceCast => C::AddrSpaceCast,
+                        O::LLVMICmp => C::ICmp,
+                        O::LLVMFCmp => C::FCmp,
+                        O::LLVMPHI => C::Phi,
+                        O::LLVMCall => C::Call,
+                        O::LLVMSelect => C::Select,
+                        O::LLVMVAArg => C::VAArg,
+                        O::LLVMExtractElement => C::ExtractElement,
+                        O::LLVMInsertElement => C::InsertElement,
+                        O::LLVMShuffleVector => C::ShuffleVector,
+                        O::LLVMExtractValue => C::ExtractValue,
+                        O::LLVMInsertValue => C::InsertValue,
+                        O::LLVMFreeze => C::Freeze,
+                        O::LLVMFence => C::Fence,
+                        O::LLVMAtomicCmpXchg => C::AtomicCmpXchg,
+                        O::LLVMAtomicRMW => C::AtomicRMW,
+                        O::LLVMResume => C::Resume,
+                        O::LLVMLandingPad => C::LandingPad,
+                        O::LLVMCleanupRet => C::CleanupRet,
+                        O::LLVMCatchRet => C::CatchRet,
+                        O::LLVMCatchPad => C::CatchPad,
+                        O::LLVMCleanupPad => C::CleanupPad,
+                        O::LLVMCatchSwitch => C::CatchSwitch,
+                        _ => C::Other,
+                    }
+                }
+            }
+            impl<'a, K> private::Sealed for crate::LLHandle<'a, K, llvm_sys::LLVMValue> {}
+            impl<'a> crate::private::Eh<'a> for crate::LLHandle<'a, Normal, llvm_sys::LLVMValue> {
+                fn add_clause<'b>(&self, clause: <Self::Kind as crate::ValueKind>::Val<'b, Normal>) {
+                    unsafe { llvm_sys::core::LLVMAddClause(self.ptr(), clause.ptr()) }
+                }
+                fn add_filter<'b>(
+                    &self,
+                    type_infos: impl Iterator<Item = <Self::Kind as crate::ValueKind>::Val<'b, Normal>>,
+                ) {
+                    use llvm_sys::core::*;
+                    let mut type_infos = type_infos.map(|t| t.ptr()).collect::<Vec<_>>();
+                    unsafe {
+                        let ptr_ty = LLVMPointerTypeInContext(LLVMGetTypeContext(LLVMTypeOf(self.ptr())), 0);
+                        LLVMAddClause(self.ptr(), const_array(ptr_ty, &mut type_infos));
+                    }
+                }
+                fn set_cleanup(&self, cleanup: bool) {
+                    unsafe { llvm_sys::core::LLVMSetCleanup(self.ptr(), cleanup as _) }
+                }
+                fn add_handler<'b>(
+                    &self,
+                    dest: <<Self::Kind as crate::ValueKind>::Func<'b> as crate::Func<'b>>::BB,
+                ) {
+                    unsafe { llvm_sys::core::LLVMAddHandler(self.ptr(), dest.ptr()) }
+                }
+            }
+            impl<'a, K: 'a> crate::Value<'a> for crate::LLHandle<'a, K, llvm_sys::LLVMValue> {
+                type Tag = K;
+                type Kind = llvm_sys::LLVMValue;
+                type Mod<'b> = crate::LLHandle<'b, Normal, llvm_sys::LLVMModule>;
+                fn r#mod<'b: 'a>(&'b self) -> Self::Mod<'b> {
+                    let ptr = self.ptr();
+                    let ptr = unsafe { llvm_sys::core::LLVMGetGlobalParent(ptr) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn allocated_type(&self) -> Option<<Self::Kind as crate::ValueKind>::Ty<'a>> {
+                    let ptr = self.ptr();
+                    if unsafe { llvm_sys::core::LLVMIsAAllocaInst(ptr) }.is_null() {
+                        return None;
+                    }
+                    let ptr = unsafe { llvm_sys::core::LLVMGetAllocatedType(ptr) };
+                    Some(unsafe { crate::LLHandle::leaked(ptr, Normal) })
+                }
+                fn value_kind(&self) -> crate::ValueClass {
+                    unsafe { llvm_sys::core::LLVMGetValueKind(self.ptr()) }.into()
+                }
+                fn opcode(&self) -> Option<crate::Opcode> {
+                    use llvm_sys::core::*;
+                    if unsafe { LLVMIsAInstruction(self.ptr()) }.is_null() {
+                        return None;
+                    }
+                    Some(unsafe { LLVMGetInstructionOpcode(self.ptr()) }.into())
+                }
+                fn as_func(&self) -> Option<<Self::Kind as crate::ValueKind>::Func<'a>> {
+                    let ptr = unsafe { llvm_sys::core::LLVMIsAFunction(self.ptr()) };
+                    if ptr.is_null() {
+                        return None;
+                    }
+                    Some(unsafe { crate::LLHandle::leaked(ptr, FuncTag) })
+                }
+                fn as_basic_block(
+                    &self,
+                ) -> Option<<<Self::Kind as crate::ValueKind>::Func<'a> as crate::Func<'a>>::BB> {
+                    use llvm_sys::core::*;
+                    if unsafe { LLVMValueIsBasicBlock(self.ptr()) } == 0 {
+                        return None;
+                    }
+                    Some(unsafe { crate::LLHandle::leaked(LLVMValueAsBasicBlock(self.ptr()), Normal) })
+                }
+                fn set_nontemporal(&self) {
+                    use llvm_sys::core::*;
+                    let ptr = self.ptr();
+                    unsafe {
+                        let ctx = LLVMGetTypeContext(LLVMTypeOf(ptr));
+                        let name = c"nontemporal";
+                        let kind = LLVMGetMDKindIDInContext(
+                            ctx,
+                            name.as_ptr(),
+                            name.count_bytes().try_into().unwrap(),
+                        );
+                        let mut one = LLVMValueAsMetadata(LLVMConstInt(
+                            LLVMInt32TypeInContext(ctx),
+                            1,
+                            0,
+                        ));
+                        let node = LLVMMDNodeInContext2(ctx, &mut one, 1);
+                        LLVMSetMetadata(ptr, kind, LLVMMetadataAsValue(ctx, node));
+                    }
+                }
+                fn set_tail_call_kind(&self, kind: crate::TailCallKind) {
+                    since!(18 $l {
+                        unsafe { llvm_sys::core::LLVMSetTailCallKind(self.ptr(), kind.into()) }
+                    } else {
+                        // Only the plain `tail` flag is exposed before LLVM 18.
+                        let tail = match kind {
+                            crate::TailCallKind::None => 0,
+                            crate::TailCallKind::Tail => 1,
+                            kind => panic!("{kind:?} requires LLVM 18 or newer"),
+                        };
+                        unsafe { llvm_sys::core::LLVMSetTailCall(self.ptr(), tail) }
+                    });
+                }
+                fn set_metadata<'b>(&self, kind_id: u32, node: <Self::Kind as crate::ValueKind>::Metadata<'b>) {
+                    use llvm_sys::core::*;
+                    let ptr = self.ptr();
+                    unsafe {
+                        if !LLVMIsAGlobalObject(ptr).is_null() {
+                            LLVMGlobalSetMetadata(ptr, kind_id, node.ptr());
+                        } else {
+                            assert!(!LLVMIsAInstruction(ptr).is_null(), "only instructions and global objects carry metadata");
+                            let ctx = LLVMGetTypeContext(LLVMTypeOf(ptr));
+                            LLVMSetMetadata(ptr, kind_id, LLVMMetadataAsValue(ctx, node.ptr()));
+                        }
+                    }
+                }
+                fn metadata(&self, kind_id: u32) -> Option<<Self::Kind as crate::ValueKind>::Metadata<'a>> {
+                    use llvm_sys::core::*;
+                    let ptr = self.ptr();
+                    let md = unsafe {
+                        if !LLVMIsAGlobalObject(ptr).is_null() {
+                            // `LLVMGetMetadata` only handles instructions.
+                            let mut len = 0;
+                            let entries = LLVMGlobalCopyAllMetadata(ptr, &mut len);
+                            let md = (0..len.try_into().unwrap())
+                                .find(|&i| LLVMValueMetadataEntriesGetKind(entries, i) == kind_id)
+                                .map_or(std::ptr::null_mut(), |i| LLVMValueMetadataEntriesGetMetadata(entries, i));
+                            if !entries.is_null() {
+                                LLVMDisposeValueMetadataEntries(entries);
+                            }
+                            md
+                        } else {
+                            assert!(!LLVMIsAInstruction(ptr).is_null(), "only instructions and global objects carry metadata");
+                            let val = LLVMGetMetadata(ptr, kind_id);
+                            if val.is_null() {
+                                return None;
+                            }
+                            LLVMValueAsMetadata(val)
+                        }
+                    };
+                    if md.is_null() {
+                        return None;
+                    }
+                    Some(unsafe { crate::LLHandle::leaked(md, Normal) })
+                }
+                fn set_name(&self, name: &CStr) {
+                    unsafe { llvm_sys::core::LLVMSetValueName2(self.ptr(), name.as_ptr(), name.count_bytes()) }
+                }
+                fn name(&self) -> Option<String> {
+                    let mut len = 0;
+                    let ptr = unsafe { llvm_sys::core::LLVMGetValueName2(self.ptr(), &mut len) };
+                    if len == 0 {
+                        return None;
+                    }
+                    let bytes = unsafe { std::slice::from_raw_parts(ptr as *const u8, len) };
+                    Some(String::from_utf8_lossy(bytes).into_owned())
+                }
+                fn type_of(&self) -> <Self::Kind as crate::ValueKind>::Ty<'a> {
+                    unsafe { crate::LLHandle::leaked(llvm_sys::core::LLVMTypeOf(self.ptr()), Normal) }
+                }
+                fn replace_all_uses_with<'b>(&self, new: <Self::Kind as crate::ValueKind>::Val<'b, Normal>) {
+                    use llvm_sys::core::*;
+                    debug_assert!(
+                        unsafe { LLVMTypeOf(self.ptr()) == LLVMTypeOf(new.ptr()) },
+                        "replace_all_uses_with requires a value of the same type"
+                    );
+                    unsafe { LLVMReplaceAllUsesWith(self.ptr(), new.ptr()) }
+                }
+                unsafe fn erase_from_parent(self) {
+                    assert!(!unsafe { llvm_sys::core::LLVMIsAInstruction(self.ptr()) }.is_null(), "not an instruction");
+                    assert!(unsafe { llvm_sys::core::LLVMGetFirstUse(self.ptr()) }.is_null(), "instruction still has uses");
+                    unsafe { llvm_sys::core::LLVMInstructionEraseFromParent(self.ptr()) }
+                }
+                fn remove_from_parent(self) -> Self {
+                    assert!(!unsafe { llvm_sys::core::LLVMIsAInstruction(self.ptr()) }.is_null(), "not an instruction");
+                    unsafe { llvm_sys::core::LLVMInstructionRemoveFromParent(self.ptr()) };
+                    self
+                }
+                fn clone_instruction(&self) -> <Self::Kind as crate::ValueKind>::Val<'a, Normal> {
+                    assert!(!unsafe { llvm_sys::core::LLVMIsAInstruction(self.ptr()) }.is_null(), "not an instruction");
+                    unsafe { crate::LLHandle::leaked(llvm_sys::core::LLVMInstructionClone(self.ptr()), Normal) }
+                }
+                fn operands(&self) -> impl Iterator<Item = <Self::Kind as crate::ValueKind>::Val<'a, Normal>> {
+                    let ptr = self.ptr();
+                    let n = unsafe { llvm_sys::core::LLVMGetNumOperands(ptr) };
+                    (0..n.max(0) as u32).map(move |i| unsafe { crate::LLHandle::leaked(llvm_sys::core::LLVMGetOperand(ptr, i), Normal) })
+                }
+                fn uses(&self) -> impl Iterator<Item = <Self::Kind as crate::ValueKind>::Val<'a, Normal>> {
+                    let mut cur = unsafe { llvm_sys::core::LLVMGetFirstUse(self.ptr()) };
+                    std::iter::from_fn(move || {
+                        if cur.is_null() {
+                            return None;
+                        }
+                        let user = unsafe { llvm_sys::core::LLVMGetUser(cur) };
+                        cur = unsafe { llvm_sys::core::LLVMGetNextUse(cur) };
+                        Some(unsafe { crate::LLHandle::leaked(user, Normal) })
+                    })
+                }
+                fn set_thread_local_mode(&self, mode: crate::ThreadLocalMode) {
+                    assert!(!unsafe { llvm_sys::core::LLVMIsAGlobalVariable(self.ptr()) }.is_null(), "not a global variable");
+                    unsafe { llvm_sys::core::LLVMSetThreadLocalMode(self.ptr(), mode.into()) }
+                }
+                fn thread_local_mode(&self) -> crate::ThreadLocalMode {
+                    assert!(!unsafe { llvm_sys::core::LLVMIsAGlobalVariable(self.ptr()) }.is_null(), "not a global variable");
+                    unsafe { llvm_sys::core::LLVMGetThreadLocalMode(self.ptr()) }.into()
+                }
+                fn set_unnamed_addr(&self, unnamed_addr: crate::UnnamedAddr) {
+                    unsafe { llvm_sys::core::LLVMSetUnnamedAddress(self.ptr(), unnamed_addr.into()) }
+                }
+                fn unnamed_addr(&self) -> crate::UnnamedAddr {
+                    unsafe { llvm_sys::core::LLVMGetUnnamedAddress(self.ptr()) }.into()
+                }
+                fn set_comdat(&self, comdat: <Self::Mod<'a> as crate::Mod<'a>>::Comdat<'a>) {
+                    unsafe { llvm_sys::comdat::LLVMSetComdat(self.ptr(), comdat.ptr()) }
+                }
+                fn comdat(&self) -> Option<<Self::Mod<'a> as crate::Mod<'a>>::Comdat<'a>> {
+                    let ptr = unsafe { llvm_sys::comdat::LLVMGetComdat(self.ptr()) };
+                    if ptr.is_null() {
+                        return None;
+                    }
+                    Some(unsafe { crate::LLHandle::leaked(ptr, Normal) })
+                }
+                fn set_section(&self, name: &CStr) {
+                    assert!(!unsafe { llvm_sys::core::LLVMIsAGlobalValue(self.ptr()) }.is_null(), "not a global value");
+                    unsafe { llvm_sys::core::LLVMSetSection(self.ptr(), name.as_ptr()) }
+                }
+                fn section(&self) -> Option<String> {
+                    assert!(!unsafe { llvm_sys::core::LLVMIsAGlobalValue(self.ptr()) }.is_null(), "not a global value");
+                    let ptr = unsafe { llvm_sys::core::LLVMGetSection(self.ptr()) };
+                    if ptr.is_null() {
+                        return None;
+                    }
+                    let section = unsafe { CStr::from_ptr(ptr) };
+                    (!section.is_empty()).then(|| section.to_string_lossy().into_owned())
+                }
+                fn set_alignment(&self, align: u32) {
+                    use llvm_sys::core::*;
+                    assert!(align.is_power_of_two(), "alignment {align} is not a power of two");
+                    let ptr = self.ptr();
+                    let aligned = unsafe {
+                        [LLVMIsAGlobalObject(ptr), LLVMIsAAllocaInst(ptr), LLVMIsALoadInst(ptr), LLVMIsAStoreInst(ptr)]
+                    };
+                    assert!(
+                        aligned.iter().any(|p| !p.is_null()),
+                        "only global objects, allocas, loads and stores have an alignment"
+                    );
+                    unsafe { LLVMSetAlignment(ptr, align) }
+                }
+                fn const_int_value(&self) -> Option<u64> {
+                    use llvm_sys::core::*;
+                    unsafe {
+                        if LLVMIsAConstantInt(self.ptr()).is_null() || LLVMGetIntTypeWidth(LLVMTypeOf(self.ptr())) > 64 {
+                            return None;
+                        }
+                        Some(LLVMConstIntGetZExtValue(self.ptr()))
+                    }
+                }
+                fn const_int_value_signed(&self) -> Option<i64> {
+                    use llvm_sys::core::*;
+                    unsafe {
+                        if LLVMIsAConstantInt(self.ptr()).is_null() || LLVMGetIntTypeWidth(LLVMTypeOf(self.ptr())) > 64 {
+                            return None;
+                        }
+                        Some(LLVMConstIntGetSExtValue(self.ptr()))
+                    }
+                }
+            }
+            impl<'a> crate::Ty<'a> for crate::LLHandle<'a, Normal, llvm_sys::LLVMType> {
+                type Ctx<'b>
+                    = crate::LLHandle<'b, Normal, llvm_sys::LLVMContext>
+                where
+                    Self: 'b;
+                fn int_ty(ctx: Self::Ctx<'a>, size: u32) -> Self {
+                    let ptr = ctx.ptr();
+                    let ptr = unsafe { llvm_sys::core::LLVMIntTypeInContext(ptr, size) };
+                    unsafe { LLHandle::leaked(ptr, Normal) }
+                }
+                fn ptr_ty(ctx: Self::Ctx<'a>, address_space: u32) -> Self {
+                    let ptr = ctx.ptr();
+                    let ptr =
+                        unsafe { llvm_sys::core::LLVMPointerTypeInContext(ptr, address_space) };
+                    unsafe { LLHandle::leaked(ptr, Normal) }
+                }
+                fn fun_ty(self, params: impl Iterator<Item = Self>) -> Self {
+                    let mut params = params.map(|p| p.ptr()).collect::<smallvec::SmallVec<[_; 8]>>();
+                    unsafe { LLHandle::leaked(function_type(self.ptr(), &mut params, false), Normal) }
+                }
+                fn var_arg_fun_ty(self, params: impl Iterator<Item = Self>) -> Self {
+                    let mut params = params.map(|p| p.ptr()).collect::<smallvec::SmallVec<[_; 8]>>();
+                    unsafe { LLHandle::leaked(function_type(self.ptr(), &mut params, true), Normal) }
+                }
+                fn struct_ty(ctx: Self::Ctx<'a>, fields: impl Iterator<Item = Self>, packed: bool) -> Self{
+                    let mut fields = fields.map(|p| p.ptr()).collect::<smallvec::SmallVec<[_; 8]>>();
+                    let ptr = unsafe{
+                        llvm_sys::core::LLVMStructTypeInContext(ctx.ptr(),fields.as_mut_ptr(),fields.len().try_into().unwrap(),if packed{1}else{0})
+                    };
+                    unsafe { LLHandle::leaked(ptr, Normal) }
+                }
+                fn kind(&self) -> crate::TypeKind {
+                    unsafe { llvm_sys::core::LLVMGetTypeKind(self.ptr()) }.into()
+                }
+                fn int_width(&self) -> Option<u32> {
+                    if self.kind() != crate::TypeKind::Integer {
+                        return None;
+                    }
+                    Some(unsafe { llvm_sys::core::LLVMGetIntTypeWidth(self.ptr()) })
+                }
+                fn element_type(&self) -> Option<Self> {
+                    use crate::TypeKind as T;
+                    if !matches!(self.kind(), T::Array | T::Vector | T::ScalableVector) {
+                        return None;
+                    }
+                    let ptr = unsafe { llvm_sys::core::LLVMGetElementType(self.ptr()) };
+                    Some(unsafe { LLHandle::leaked(ptr, Normal) })
+                }
+                fn struct_field_types(&self) -> Option<Vec<Self>> {
+                    use llvm_sys::core::*;
+                    if self.kind() != crate::TypeKind::Struct {
+                        return None;
+                    }
+                    let n = unsafe { LLVMCountStructElementTypes(self.ptr()) };
+                    let mut fields = vec![std::ptr::null_mut(); n as usize];
+                    unsafe { LLVMGetStructElementTypes(self.ptr(), fields.as_mut_ptr()) };
+                    Some(fields.into_iter().map(|f| unsafe { LLHandle::leaked(f, Normal) }).collect())
+                }
+                fn pointee_type(&self) -> Option<Self> {
+                    since!(17 $l {{
+                        None
+                    }} else {{
+                        use llvm_sys::core::*;
+                        if self.kind() != crate::TypeKind::Pointer
+                            || unsafe { LLVMPointerTypeIsOpaque(self.ptr()) } != 0
+                        {
+                            return None;
+                        }
+                        let ptr = unsafe { LLVMGetElementType(self.ptr()) };
+                        Some(unsafe { LLHandle::leaked(ptr, Normal) })
+                    }})
+                }
+                fn return_type(&self) -> Option<Self> {
+                    if self.kind() != crate::TypeKind::Function {
+                        return None;
+                    }
+                    let ptr = unsafe { llvm_sys::core::LLVMGetReturnType(self.ptr()) };
+                    Some(unsafe { LLHandle::leaked(ptr, Normal) })
+                }
+                fn count_param_types(&self) -> Option<u32> {
+                    if self.kind() != crate::TypeKind::Function {
+                        return None;
+                    }
+                    Some(unsafe { llvm_sys::core::LLVMCountParamTypes(self.ptr()) })
+                }
+                fn param_types(&self) -> Option<Vec<Self>> {
+                    let n = self.count_param_types()?;
+                    let mut params = vec![std::ptr::null_mut(); n as usize];
+                    unsafe { llvm_sys::core::LLVMGetParamTypes(self.ptr(), params.as_mut_ptr()) };
+                    Some(params.into_iter().map(|p| unsafe { LLHandle::leaked(p, Normal) }).collect())
+                }
+                fn is_var_arg(&self) -> Option<bool> {
+                    if self.kind() != crate::TypeKind::Function {
+                        return None;
+                    }
+                    Some(unsafe { llvm_sys::core::LLVMIsFunctionVarArg(self.ptr()) != 0 })
+                }
+                fn is_sized(&self) -> bool {
+                    unsafe { llvm_sys::core::LLVMTypeIsSized(self.ptr()) != 0 }
+                }
+            }
+            impl From<llvm_sys::LLVMTypeKind> for crate::TypeKind{
+                fn from(a: llvm_sys::LLVMTypeKind) -> Self{
+                    use llvm_sys::LLVMTypeKind as K;
+                    use crate::TypeKind as T;
+                    #[allow(unreachable_patterns)]
+                    match a{
+                        K::LLVMVoidTypeKind => T::Void,
+                        K::LLVMHalfTypeKind => T::Half,
+                        K::LLVMBFloatTypeKind => T::BFloat,
+                        K::LLVMFloatTypeKind => T::Float,
+                        K::LLVMDoubleTypeKind => T::Double,
+                        K::LLVMX86_FP80TypeKind => T::X86Fp80,
+                        K::LLVMFP128TypeKind => T::Fp128,
+                        K::LLVMPPC_FP128TypeKind => T::PpcFp128,
+                        K::LLVMLabelTypeKind => T::Label,
+                        K::LLVMIntegerTypeKind => T::Integer,
+                        K::LLVMFunctionTypeKind => T::Function,
+                        K::LLVMStructTypeKind => T::Struct,
+                        K::LLVMArrayTypeKind => T::Array,
+                        K::LLVMPointerTypeKind => T::Pointer,
+                        K::LLVMVectorTypeKind => T::Vector,
+                        K::LLVMScalableVectorTypeKind => T::ScalableVector,
+                        K::LLVMMetadataTypeKind => T::Metadata,
+                        K::LLVMX86_AMXTypeKind => T::X86Amx,
+                        K::LLVMTokenTypeKind => T::Token,
+                        K::LLVMTargetExtTypeKind => T::TargetExt,
+                        _ => T::Other,
+                    }
+                }
+            }
+            impl crate::ValueKind for llvm_sys::LLVMValue {
+                type Val<'a, K: 'a> = crate::LLHandle<'a, K, llvm_sys::LLVMValue>;
+                type Mod<'a> = crate::LLHandle<'a, Normal, llvm_sys::LLVMModule>;
+                type Func<'a> = crate::LLHandle<'a, FuncTag, llvm_sys::LLVMValue>;
+                type Ty<'a> = crate::LLHandle<'a, Normal, llvm_sys::LLVMType>;
+                type Metadata<'a> = crate::LLHandle<'a, Normal, llvm_sys::LLVMOpaqueMetadata>;
+                type Ctx<'a> = crate::LLHandle<'a, Normal, llvm_sys::LLVMContext>;
+                fn value_as_metadata<'a>(val: Self::Val<'a, Normal>) -> Self::Metadata<'a> {
+                    let ptr = unsafe { llvm_sys::core::LLVMValueAsMetadata(val.ptr()) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn const_int<'a>(ty: Self::Ty<'a>, n: u64, sext: bool) -> Self::Val<'a, Normal> {
+                    let ptr = ty.ptr();
+                    let ptr =
+                        unsafe { llvm_sys::core::LLVMConstInt(ptr, n, if sext { 1 } else { 0 }) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn const_bool<'a>(ctx: Self::Ctx<'a>, b: bool) -> Self::Val<'a, Normal> {
+                    Self::const_int(crate::Ty::i1_ty(ctx), b as u64, false)
+                }
+                fn const_i32<'a>(ctx: Self::Ctx<'a>, n: i32) -> Self::Val<'a, Normal> {
+                    Self::const_int(crate::Ty::int_ty(ctx, 32), n as u64, true)
+                }
+                fn const_i64<'a>(ctx: Self::Ctx<'a>, n: i64) -> Self::Val<'a, Normal> {
+                    Self::const_int(crate::Ty::int_ty(ctx, 64), n as u64, true)
+                }
+                fn const_like<'a>(val: Self::Val<'a, Normal>, n: u64) -> Self::Val<'a, Normal> {
+                    let ty = crate::Value::type_of(&val);
+                    assert!(ty.int_width().is_some(), "const_like requires an integer value");
+                    Self::const_int(ty, n, false)
+                }
+                fn const_int_of_string<'a>(ty: Self::Ty<'a>, s: &CStr, radix: u8) -> Self::Val<'a, Normal> {
+                    assert!(matches!(radix, 2 | 8 | 10 | 16), "unsupported radix {radix}");
+                    let width = ty.int_width().expect("const_int_of_string requires an integer type");
+                    let s = int_literal(s, radix, width);
+                    let ptr = unsafe {
+                        llvm_sys::core::LLVMConstIntOfStringAndSize(
+                            ty.ptr(),
+                            s.as_ptr(),
+                            s.count_bytes().try_into().unwrap(),
+                            radix,
+                        )
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn const_int_of_words<'a>(ty: Self::Ty<'a>, words: &[u64]) -> Self::Val<'a, Normal> {
+                    let ptr = unsafe {
+                        llvm_sys::core::LLVMConstIntOfArbitraryPrecision(
+                            ty.ptr(),
+                            words.len().try_into().unwrap(),
+                            words.as_ptr(),
+                        )
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn const_zero<'a>(ty: Self::Ty<'a>) -> Self::Val<'a, Normal> {
+                    let ptr = unsafe { llvm_sys::core::LLVMConstNull(ty.ptr()) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn const_array<'a>(
+                    elem_ty: Self::Ty<'a>,
+                    vals: impl Iterator<Item = Self::Val<'a, Normal>>,
+                ) -> Self::Val<'a, Normal> {
+                    let mut vals = vals.map(|v| v.ptr()).collect::<Vec<_>>();
+                    let ptr = unsafe { const_array(elem_ty.ptr(), &mut vals) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn const_data_array<'a>(elem_ty: Self::Ty<'a>, data: &[u64]) -> Self::Val<'a, Normal> {
+                    assert!(elem_ty.int_width().is_some(), "const_data_array requires an integer element type");
+                    let ty = elem_ty.ptr();
+                    let mut vals = data
+                        .iter()
+                        .map(|d| unsafe { llvm_sys::core::LLVMConstInt(ty, *d, 0) })
+                        .collect::<Vec<_>>();
+                    let ptr = unsafe { const_array(ty, &mut vals) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn const_bytes<'a>(ctx: Self::Ctx<'a>, data: &[u8]) -> Self::Val<'a, Normal> {
+                    let (ctx, bytes) = (ctx.ptr(), data.as_ptr() as *const _);
+                    let ptr = since!(19 $l {{
+                        unsafe { llvm_sys::core::LLVMConstStringInContext2(ctx, bytes, data.len(), 1) }
+                    }} else {{
+                        unsafe { llvm_sys::core::LLVMConstStringInContext(ctx, bytes, data.len().try_into().unwrap(), 1) }
+                    }});
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn const_struct<'a>(
+                    ctx: Self::Ctx<'a>,
+                    vals: impl Iterator<Item = Self::Val<'a, Normal>>,
+                    packed: bool,
+                ) -> Self::Val<'a, Normal> {
+                    let mut vals = vals.map(|v| v.ptr()).collect::<Vec<_>>();
+                    let ptr = unsafe {
+                        llvm_sys::core::LLVMConstStructInContext(
+                            ctx.ptr(),
+                            vals.as_mut_ptr(),
+                            vals.len().try_into().unwrap(),
+                            packed as _,
+                        )
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn const_named_struct<'a>(
+                    struct_ty: Self::Ty<'a>,
+                    vals: impl Iterator<Item = Self::Val<'a, Normal>>,
+                ) -> Self::Val<'a, Normal> {
+                    use llvm_sys::core::*;
+                    let mut vals = vals.map(|v| v.ptr()).collect::<Vec<_>>();
+                    let ty = struct_ty.ptr();
+                    let ptr = unsafe {
+                        assert!(
+                            LLVMGetTypeKind(ty) == llvm_sys::LLVMTypeKind::LLVMStructTypeKind,
+                            "const_named_struct requires a struct type"
+                        );
+                        let fields = LLVMCountStructElementTypes(ty) as usize;
+                        assert_eq!(vals.len(), fields, "struct constant has the wrong number of fields");
+                        LLVMConstNamedStruct(ty, vals.as_mut_ptr(), vals.len().try_into().unwrap())
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn const_gep<'a>(
+                    ty: Self::Ty<'a>,
+                    ptr: Self::Val<'a, Normal>,
+                    indices: impl Iterator<Item = Self::Val<'a, Normal>>,
+                ) -> Self::Val<'a, Normal> {
+                    let mut indices = indices.map(|v| v.ptr()).collect::<Vec<_>>();
+                    let ptr = unsafe {
+                        llvm_sys::core::LLVMConstGEP2(
+                            ty.ptr(),
+                            ptr.ptr(),
+                            indices.as_mut_ptr(),
+                            indices.len().try_into().unwrap(),
+                        )
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn const_bitcast<'a>(val: Self::Val<'a, Normal>, ty: Self::Ty<'a>) -> Self::Val<'a, Normal> {
+                    let ptr = unsafe { llvm_sys::core::LLVMConstBitCast(val.ptr(), ty.ptr()) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn const_trunc<'a>(val: Self::Val<'a, Normal>, ty: Self::Ty<'a>) -> Self::Val<'a, Normal> {
+                    let ptr = unsafe { llvm_sys::core::LLVMConstTrunc(val.ptr(), ty.ptr()) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn const_zext<'a>(val: Self::Val<'a, Normal>, ty: Self::Ty<'a>) -> Self::Val<'a, Normal> {
+                    let ptr = since!(18 $l {{
+                        unsafe { fold_cast(llvm_sys::LLVMOpcode::LLVMZExt, val.ptr(), ty.ptr()) }
+                    }} else {{
+                        unsafe { llvm_sys::core::LLVMConstZExt(val.ptr(), ty.ptr()) }
+                    }});
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn const_sext<'a>(val: Self::Val<'a, Normal>, ty: Self::Ty<'a>) -> Self::Val<'a, Normal> {
+                    let ptr = since!(18 $l {{
+                        unsafe { fold_cast(llvm_sys::LLVMOpcode::LLVMSExt, val.ptr(), ty.ptr()) }
+                    }} else {{
+                        unsafe { llvm_sys::core::LLVMConstSExt(val.ptr(), ty.ptr()) }
+                    }});
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn block_address<'a>(
+                    func: Self::Func<'a>,
+                    bb: <Self::Func<'a> as crate::Func<'a>>::BB,
+                ) -> Self::Val<'a, Normal> {
+                    use llvm_sys::core::*;
+                    assert!(
+                        unsafe { LLVMGetBasicBlockParent(bb.ptr()) } == func.ptr(),
+                        "block does not belong to the function"
+                    );
+                    let ptr = unsafe { LLVMBlockAddress(func.ptr(), bb.ptr()) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn const_neg<'a>(val: Self::Val<'a, Normal>) -> Self::Val<'a, Normal> {
+                    let ptr = unsafe { llvm_sys::core::LLVMConstNeg(val.ptr()) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn const_add<'a>(lhs: Self::Val<'a, Normal>, rhs: Self::Val<'a, Normal>) -> Self::Val<'a, Normal> {
+                    let ptr = unsafe { llvm_sys::core::LLVMConstAdd(lhs.ptr(), rhs.ptr()) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn const_sub<'a>(lhs: Self::Val<'a, Normal>, rhs: Self::Val<'a, Normal>) -> Self::Val<'a, Normal> {
+                    let ptr = unsafe { llvm_sys::core::LLVMConstSub(lhs.ptr(), rhs.ptr()) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn const_mul<'a>(lhs: Self::Val<'a, Normal>, rhs: Self::Val<'a, Normal>) -> Self::Val<'a, Normal> {
+                    // The newest bindings still declare `LLVMConstMul`, but libLLVM no
+                    // longer exports it.
+                    since!(21 $l {{
+                        let _ = (lhs, rhs);
+                        panic!("const_mul requires LLVM 20 or older")
+                    }} else {{
+                        let ptr = unsafe { llvm_sys::core::LLVMConstMul(lhs.ptr(), rhs.ptr()) };
+                        unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                    }})
+                }
+                fn const_xor<'a>(lhs: Self::Val<'a, Normal>, rhs: Self::Val<'a, Normal>) -> Self::Val<'a, Normal> {
+                    let ptr = unsafe { llvm_sys::core::LLVMConstXor(lhs.ptr(), rhs.ptr()) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn inline_asm<'a>(
+                    ty: Self::Ty<'a>,
+                    asm: &CStr,
+                    constraints: &CStr,
+                    has_side_effects: bool,
+                    is_align_stack: bool,
+                    dialect: crate::AsmDialect,
+                ) -> Self::Val<'a, Normal> {
+                    // The string parameters are `*mut` in older bindings and `*const` in newer ones.
+                    let ptr = unsafe {
+                        llvm_sys::core::LLVMGetInlineAsm(
+                            ty.ptr(),
+                            asm.as_ptr() as _,
+                            asm.count_bytes(),
+                            constraints.as_ptr() as _,
+                            constraints.count_bytes(),
+                            has_side_effects as _,
+                            is_align_stack as _,
+                            dialect.into(),
+                            0,
+                        )
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn function<'a, 'b, 'c, 'd: 'a + 'b + 'c>(
+                    r#mod: Self::Mod<'a>,
+                    name: &'b CStr,
+                    ty: Self::Ty<'c>,
+                ) -> Self::Func<'d> {
+                    let ptr = unsafe {
+                        llvm_sys::core::LLVMAddFunction(r#mod.ptr(), name.as_ptr(), ty.ptr())
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, FuncTag) }
+                }
+                fn declare<'a, 'b, 'c, 'd: 'a + 'b + 'c>(
+                    r#mod: Self::Mod<'a>,
+                    name: &'b CStr,
+                    ty: Self::Ty<'c>,
+                ) -> Self::Func<'d> {
+                    use llvm_sys::core::*;
+                    let ptr = unsafe {
+                        let existing = LLVMGetNamedFunction(r#mod.ptr(), name.as_ptr());
+                        if existing.is_null() {
+                            let f = LLVMAddFunction(r#mod.ptr(), name.as_ptr(), ty.ptr());
+                            LLVMSetLinkage(f, llvm_sys::LLVMLinkage::LLVMExternalLinkage);
+                            f
+                        } else {
+                            assert!(
+                                LLVMCountBasicBlocks(existing) == 0,
+                                "{name:?} is already defined in this module"
+                            );
+                            existing
+                        }
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, FuncTag) }
+                }
+                fn build_ptr_array_global<'a, 'b, 'c, 'd: 'a + 'b + 'c>(
+                    r#mod: Self::Mod<'a>,
+                    name: &'b CStr,
+                    ptrs: impl Iterator<Item = Self::Val<'c, Normal>>,
+                ) -> Self::Val<'d, Normal> {
+                    let ctx = unsafe { llvm_sys::core::LLVMGetModuleContext(r#mod.ptr()) };
+                    let ptr_ty = unsafe { llvm_sys::core::LLVMPointerTypeInContext(ctx, 0) };
+                    let null = unsafe { llvm_sys::core::LLVMConstPointerNull(ptr_ty) };
+                    let mut ptrs = ptrs
+                        .map(|p| p.ptr())
+                        .chain(std::iter::once(null))
+                        .collect::<Vec<_>>();
+                    let len: u64 = ptrs.len().try_into().unwrap();
+                    let ptr = unsafe {
+                        let init = const_array(ptr_ty, &mut ptrs);
+                        let global = llvm_sys::core::LLVMAddGlobal(
+                            r#mod.ptr(),
+                            array_type(ptr_ty, len),
+                            name.as_ptr(),
+                        );
+                        llvm_sys::core::LLVMSetInitializer(global, init);
+                        llvm_sys::core::LLVMSetGlobalConstant(global, 1);
+                        global
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn add_constant_global<'a, 'b, 'c, 'd: 'a + 'b + 'c>(
+                    r#mod: Self::Mod<'a>,
+                    name: &'b CStr,
+                    init: Self::Val<'c, Normal>,
+                ) -> Self::Val<'d, Normal> {
+                    use llvm_sys::core::*;
+                    let ptr = unsafe {
+                        let global = LLVMAddGlobal(r#mod.ptr(), LLVMTypeOf(init.ptr()), name.as_ptr());
+                        LLVMSetInitializer(global, init.ptr());
+                        LLVMSetGlobalConstant(global, 1);
+                        LLVMSetLinkage(global, llvm_sys::LLVMLinkage::LLVMPrivateLinkage);
+                        LLVMSetUnnamedAddress(global, llvm_sys::LLVMUnnamedAddr::LLVMGlobalUnnamedAddr);
+                        global
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+            }
+            impl<'a> crate::Ctx<'a> for crate::LLHandle<'a, Normal, llvm_sys::LLVMContext> {
+                type Branded<'c> = crate::LLHandle<'c, Normal, llvm_sys::LLVMContext>;
+                fn create_context() -> Self {
+                    let ptr = unsafe { llvm_sys::core::LLVMContextCreate() };
+                    unsafe { crate::LLHandle::from_raw_parts(ptr, dispose_context, Normal) }
+                }
+                fn set_diagnostic_handler(&self, f: impl FnMut(crate::DiagnosticInfo) + 'static) {
+                    let handler: *mut DiagnosticHandler = Box::into_raw(Box::new(Box::new(f)));
+                    unsafe {
+                        let old = diagnostic_handler(self.ptr());
+                        llvm_sys::core::LLVMContextSetDiagnosticHandler(
+                            self.ptr(),
+                            Some(diagnostic_trampoline),
+                            handler as *mut std::ffi::c_void,
+                        );
+                        if let Some(old) = old {
+                            drop(Box::from_raw(old));
+                        }
+                    }
+                }
+                type Mod<'b>
+                    = crate::LLHandle<'b, Normal, llvm_sys::LLVMModule>
+                where
+                    Self: 'b;
+                fn modules(&self) -> Vec<Self::Mod<'a>> {
+                    MODULES.with_borrow_mut(|m| {
+                        let Some(mods) = m.get_mut(&(self.ptr() as usize)) else {
+                            return vec![];
+                        };
+                        mods.retain(|s| s.strong_count() != 0);
+                        mods.iter()
+                            .filter_map(|s| s.upgrade())
+                            .map(|shim| crate::LLHandle {
+                                val: shim.val,
+                                shim,
+                                phantom: PhantomData,
+                            })
+                            .collect()
+                    })
+                }
+                fn module(&self, name: &CStr) -> Option<Self::Mod<'a>> {
+                    self.modules().into_iter().find(|m| {
+                        let mut len = 0;
+                        let id = unsafe { llvm_sys::core::LLVMGetModuleIdentifier(m.ptr(), &mut len) };
+                        let id = unsafe { std::slice::from_raw_parts(id as *const u8, len) };
+                        id == name.to_bytes()
+                    })
+                }
+                fn metadata_kind_id(&self, name: &CStr) -> u32 {
+                    unsafe {
+                        llvm_sys::core::LLVMGetMDKindIDInContext(
+                            self.ptr(),
+                            name.as_ptr(),
+                            name.count_bytes().try_into().unwrap(),
+                        )
+                    }
+                }
+            }
+            impl<'a> crate::Mod<'a> for crate::LLHandle<'a, Normal, llvm_sys::LLVMModule> {
+                type Ctx<'b>
+                    = crate::LLHandle<'b, Normal, llvm_sys::LLVMContext>
+                where
+                    Self: 'b;
+                fn ctx<'b: 'a>(&'b self) -> Self::Ctx<'b> {
+                    if let Some(ctx) = self.owner() {
+                        return ctx;
+                    }
+                    let ptr = self.ptr();
+                    let ptr = unsafe { llvm_sys::core::LLVMGetModuleContext(ptr) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn create_mod<'b, 'c, 'd>(a: &'b CStr, ctx: &'c Self::Ctx<'d>) -> Self
+                where
+                    'a: 'b + 'c + 'd,
+                {
+                    let ptr = ctx.ptr();
+                    let ptr = unsafe {
+                        llvm_sys::core::LLVMModuleCreateWithNameInContext(a.as_ptr(), ptr)
+                    };
+                    let handle = unsafe {
+                        crate::LLHandle::from_raw_parts(
+                            ptr,
+                            |a, _| llvm_sys::core::LLVMDisposeModule(a),
+                            Normal,
+                        )
+                    }
+                    .owned_by(ctx);
+                    register_module(&handle);
+                    handle
+                }
+                type Ty<'b>
+                    = crate::LLHandle<'b, Normal, llvm_sys::LLVMType>
+                where
+                    Self: 'b;
+                type Func<'b>
+                    = crate::LLHandle<'b, FuncTag, llvm_sys::LLVMValue>
+                where
+                    Self: 'b;
+                fn intrinsic(
+                    &self,
+                    name: &CStr,
+                    overloads: impl Iterator<Item = Self::Ty<'a>>,
+                ) -> Option<Self::Func<'a>> {
+                    use llvm_sys::core::*;
+                    let mut overloads = overloads.map(|t| t.ptr()).collect::<Vec<_>>();
+                    let ptr = unsafe {
+                        let id = LLVMLookupIntrinsicID(name.as_ptr(), name.count_bytes());
+                        if id == 0 {
+                            return None;
+                        }
+                        LLVMGetIntrinsicDeclaration(self.ptr(), id, overloads.as_mut_ptr(), overloads.len())
+                    };
+                    Some(unsafe { crate::LLHandle::leaked(ptr, FuncTag) })
+                }
+                type TargetMachine<'b>
+                    = crate::LLHandle<'b, Normal, llvm_sys::target_machine::LLVMOpaqueTargetMachine>
+                where
+                    Self: 'b;
+                fn run_passes(
+                    &self,
+                    passes: &CStr,
+                    target_machine: Option<&Self::TargetMachine<'a>>,
+                ) -> Result<(), String> {
+                    use llvm_sys::transforms::pass_builder::*;
+                    unsafe {
+                        let options = LLVMCreatePassBuilderOptions();
+                        let err = LLVMRunPasses(
+                            self.ptr(),
+                            passes.as_ptr(),
+                            target_machine.map_or(std::ptr::null_mut(), |t| t.ptr()),
+                            options,
+                        );
+                        LLVMDisposePassBuilderOptions(options);
+                        check_error(err)
+                    }
+                }
+                fn link(self, other: Self) -> Result<Self, String> {
+                    use llvm_sys::core::*;
+                    struct Diagnostics {
+                        errors: Vec<String>,
+                        prev: llvm_sys::LLVMDiagnosticHandler,
+                        prev_ctx: *mut std::ffi::c_void,
+                    }
+                    extern "C" fn capture(info: llvm_sys::prelude::LLVMDiagnosticInfoRef, diags: *mut std::ffi::c_void) {
+                        let diags = unsafe { &mut *(diags as *mut Diagnostics) };
+                        if unsafe { LLVMGetDiagInfoSeverity(info) } == llvm_sys::LLVMDiagnosticSeverity::LLVMDSError {
+                            diags.errors.push(unsafe { take_message(LLVMGetDiagInfoDescription(info)) });
+                        } else if let Some(prev) = diags.prev {
+                            prev(info, diags.prev_ctx);
+                        }
+                    }
+                    if unsafe { LLVMGetModuleContext(self.ptr()) != LLVMGetModuleContext(other.ptr()) } {
+                        return Err("cannot link modules from different contexts".to_owned());
+                    }
+                    let Ok(other) = other.try_into_raw() else {
+                        return Err("linking requires the only owning handle to the source module".to_owned());
+                    };
+                    unsafe {
+                        let ctx = LLVMGetModuleContext(self.ptr());
+                        let mut diags = Diagnostics {
+                            errors: vec![],
+                            prev: LLVMContextGetDiagnosticHandler(ctx),
+                            prev_ctx: LLVMContextGetDiagnosticContext(ctx),
+                        };
+                        LLVMContextSetDiagnosticHandler(ctx, Some(capture), &mut diags as *mut Diagnostics as *mut _);
+                        let failed = llvm_sys::linker::LLVMLinkModules2(self.ptr(), other) != 0;
+                        LLVMContextSetDiagnosticHandler(ctx, diags.prev, diags.prev_ctx);
+                        if failed {
+                            return Err(diags.errors.join("\n"));
+                        }
+                    }
+                    Ok(self)
+                }
+                fn clone_module(&self) -> Self {
+                    let ptr = unsafe { llvm_sys::core::LLVMCloneModule(self.ptr()) };
+                    let handle = unsafe {
+                        crate::LLHandle::from_raw_parts(
+                            ptr,
+                            |a, _| llvm_sys::core::LLVMDisposeModule(a),
+                            Normal,
+                        )
+                    };
+                    let handle = match self.owner::<Normal, llvm_sys::LLVMContext>() {
+                        Some(ctx) => handle.owned_by(&ctx),
+                        None => handle,
+                    };
+                    register_module(&handle);
+                    handle
+                }
+                type Val<'b>
+                    = crate::LLHandle<'b, Normal, llvm_sys::LLVMValue>
+                where
+                    Self: 'b;
+                fn add_alias(
+                    &self,
+                    ty: Self::Ty<'a>,
+                    addr_space: u32,
+                    aliasee: Self::Val<'a>,
+                    name: &CStr,
+                ) -> Self::Val<'a> {
+                    let ptr = unsafe {
+                        llvm_sys::core::LLVMAddAlias2(self.ptr(), ty.ptr(), addr_space, aliasee.ptr(), name.as_ptr())
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn add_ifunc(
+                    &self,
+                    ty: Self::Ty<'a>,
+                    addr_space: u32,
+                    resolver: Self::Func<'a>,
+                    name: &CStr,
+                ) -> Self::Val<'a> {
+                    let ptr = unsafe {
+                        llvm_sys::core::LLVMAddGlobalIFunc(
+                            self.ptr(),
+                            name.as_ptr(),
+                            name.count_bytes(),
+                            ty.ptr(),
+                            addr_space,
+                            resolver.ptr(),
+                        )
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                type Comdat<'b>
+                    = crate::LLHandle<'b, Normal, llvm_sys::LLVMComdat>
+                where
+                    Self: 'b;
+                fn comdat(&self, name: &CStr) -> Self::Comdat<'a> {
+                    let ptr = unsafe { llvm_sys::comdat::LLVMGetOrInsertComdat(self.ptr(), name.as_ptr()) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn append_inline_asm(&self, asm: &CStr) {
+                    unsafe { llvm_sys::core::LLVMAppendModuleInlineAsm(self.ptr(), asm.as_ptr(), asm.count_bytes()) }
+                }
+                fn inline_asm(&self) -> String {
+                    let mut len = 0;
+                    let ptr = unsafe { llvm_sys::core::LLVMGetModuleInlineAsm(self.ptr(), &mut len) };
+                    if len == 0 {
+                        return String::new();
+                    }
+                    let bytes = unsafe { std::slice::from_raw_parts(ptr as *const u8, len) };
+                    String::from_utf8_lossy(bytes).into_owned()
+                }
+            }
+            impl<'a> crate::Comdat<'a> for crate::LLHandle<'a, Normal, llvm_sys::LLVMComdat> {
+                fn selection_kind(&self) -> crate::ComdatSelectionKind {
+                    unsafe { llvm_sys::comdat::LLVMGetComdatSelectionKind(self.ptr()) }.into()
+                }
+                fn set_selection_kind(&self, kind: crate::ComdatSelectionKind) {
+                    unsafe { llvm_sys::comdat::LLVMSetComdatSelectionKind(self.ptr(), kind.into()) }
+                }
+            }
+            impl From<crate::ComdatSelectionKind> for llvm_sys::comdat::LLVMComdatSelectionKind{
+                fn from(a: crate::ComdatSelectionKind) -> Self{
+                    use llvm_sys::comdat::LLVMComdatSelectionKind as S;
+                    match a{
+                        crate::ComdatSelectionKind::Any => S::LLVMAnyComdatSelectionKind,
+                        crate::ComdatSelectionKind::ExactMatch => S::LLVMExactMatchComdatSelectionKind,
+                        crate::ComdatSelectionKind::Largest => S::LLVMLargestComdatSelectionKind,
+                        crate::ComdatSelectionKind::NoDeduplicate => S::LLVMNoDuplicatesComdatSelectionKind,
+                        crate::ComdatSelectionKind::SameSize => S::LLVMSameSizeComdatSelectionKind,
+                    }
+                }
+            }
+            impl From<llvm_sys::comdat::LLVMComdatSelectionKind> for crate::ComdatSelectionKind{
+                fn from(a: llvm_sys::comdat::LLVMComdatSelectionKind) -> Self{
+                    use llvm_sys::comdat::LLVMComdatSelectionKind as S;
+                    match a{
+                        S::LLVMAnyComdatSelectionKind => crate::ComdatSelectionKind::Any,
+                        S::LLVMExactMatchComdatSelectionKind => crate::ComdatSelectionKind::ExactMatch,
+                        S::LLVMLargestComdatSelectionKind => crate::ComdatSelectionKind::Largest,
+                        S::LLVMNoDuplicatesComdatSelectionKind => crate::ComdatSelectionKind::NoDeduplicate,
+                        S::LLVMSameSizeComdatSelectionKind => crate::ComdatSelectionKind::SameSize,
+                    }
+                }
+            }
+            impl<'a> crate::Func<'a> for crate::LLHandle<'a, FuncTag, llvm_sys::LLVMValue> {
+                type BB = crate::LLHandle<'a, Normal, llvm_sys::LLVMBasicBlock>;
+                fn basic_blocks(&self) -> impl Iterator<Item = Self::BB> {
+                    let mut cur = unsafe { llvm_sys::core::LLVMGetFirstBasicBlock(self.ptr()) };
+                    std::iter::from_fn(move || {
+                        if cur.is_null() {
+                            return None;
+                        }
+                        let bb = cur;
+                        cur = unsafe { llvm_sys::core::LLVMGetNextBasicBlock(cur) };
+                        Some(unsafe { crate::LLHandle::leaked(bb, Normal) })
+                    })
+                }
+                fn params(&self) -> impl Iterator<Item = <Self::Kind as ValueKind>::Val<'a, Normal>> {
+                    let ptr = self.ptr();
+                    let n = unsafe { llvm_sys::core::LLVMCountParams(ptr) };
+                    (0..n).map(move |i| unsafe { crate::LLHandle::leaked(llvm_sys::core::LLVMGetParam(ptr, i), Normal) })
+                }
+                fn is_declaration(&self) -> bool {
+                    unsafe { llvm_sys::core::LLVMIsDeclaration(self.ptr()) != 0 }
+                }
+                fn verify(&self) -> Result<(), ()> {
+                    use llvm_sys::analysis::*;
+                    match unsafe { LLVMVerifyFunction(self.ptr(), LLVMVerifierFailureAction::LLVMReturnStatusAction) } {
+                        0 => Ok(()),
+                        _ => Err(()),
+                    }
+                }
+                fn verify_with_message(&self) -> Result<(), ()> {
+                    use llvm_sys::analysis::*;
+                    match unsafe { LLVMVerifyFunction(self.ptr(), LLVMVerifierFailureAction::LLVMPrintMessageAction) } {
+                        0 => Ok(()),
+                        _ => Err(()),
+                    }
+                }
+                fn set_personality<'b>(&self, personality: <Self::Kind as crate::ValueKind>::Func<'b>) {
+                    unsafe { llvm_sys::core::LLVMSetPersonalityFn(self.ptr(), personality.ptr()) }
+                }
+                fn set_gc(&self, name: &CStr) {
+                    unsafe { llvm_sys::core::LLVMSetGC(self.ptr(), name.as_ptr()) }
+                }
+                fn gc(&self) -> Option<String> {
+                    let ptr = unsafe { llvm_sys::core::LLVMGetGC(self.ptr()) };
+                    if ptr.is_null() {
+                        return None;
+                    }
+                    Some(unsafe { CStr::from_ptr(ptr) }.to_string_lossy().into_owned())
+                }
+            }
+            impl<'a> crate::BB<'a> for crate::LLHandle<'a, Normal, llvm_sys::LLVMBasicBlock> {
+                type Func<'b>
+                    = crate::LLHandle<'b, FuncTag, llvm_sys::LLVMValue>
+                where
+                    'a: 'b,
+                    Self: 'b;
+                fn new<'b, 'c>(f: Self::Func<'b>, name: &'c CStr) -> Self
+                where
+                    'a: 'b + 'c,
+                {
+                    let ptr = f.ptr();
+                    let ptr = unsafe { llvm_sys::core::LLVMAppendBasicBlock(ptr, name.as_ptr()) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn create_in_context(ctx: crate::LLHandle<'a, Normal, llvm_sys::LLVMContext>, name: &CStr) -> Self {
+                    let ptr = unsafe { llvm_sys::core::LLVMCreateBasicBlockInContext(ctx.ptr(), name.as_ptr()) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn append_existing<'b>(func: Self::Func<'b>, bb: &Self)
+                where
+                    'a: 'b,
+                {
+                    unsafe { llvm_sys::core::LLVMAppendExistingBasicBlock(func.ptr(), bb.ptr()) }
+                }
+                fn insert_before<'c>(before: &Self, name: &'c CStr) -> Self
+                where
+                    'a: 'c,
+                {
+                    let ptr = unsafe { llvm_sys::core::LLVMInsertBasicBlock(before.ptr(), name.as_ptr()) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                unsafe fn delete(self) {
+                    use llvm_sys::core::*;
+                    assert!(
+                        unsafe { LLVMGetFirstUse(LLVMBasicBlockAsValue(self.ptr())) }.is_null(),
+                        "basic block still has predecessors or other uses"
+                    );
+                    unsafe { LLVMDeleteBasicBlock(self.ptr()) }
+                }
+                fn move_before(&self, other: &Self) {
+                    unsafe { llvm_sys::core::LLVMMoveBasicBlockBefore(self.ptr(), other.ptr()) }
+                }
+                fn move_after(&self, other: &Self) {
+                    unsafe { llvm_sys::core::LLVMMoveBasicBlockAfter(self.ptr(), other.ptr()) }
+                }
+                fn terminator(&self) -> Option<crate::LLHandle<'a, Normal, llvm_sys::LLVMValue>> {
+                    let ptr = unsafe { llvm_sys::core::LLVMGetBasicBlockTerminator(self.ptr()) };
+                    if ptr.is_null() {
+                        return None;
                     }
+                    Some(unsafe { crate::LLHandle::leaked(ptr, Normal) })
+                }
+                fn instructions(&self) -> impl Iterator<Item = crate::LLHandle<'a, Normal, llvm_sys::LLVMValue>> {
+                    let mut cur = unsafe { llvm_sys::core::LLVMGetFirstInstruction(self.ptr()) };
+                    std::iter::from_fn(move || {
+                        if cur.is_null() {
+                            return None;
+                        }
+                        let inst = cur;
+                        cur = unsafe { llvm_sys::core::LLVMGetNextInstruction(cur) };
+                        Some(unsafe { crate::LLHandle::leaked(inst, Normal) })
+                    })
+                }
+                fn as_value(&self) -> crate::LLHandle<'a, Normal, llvm_sys::LLVMValue> {
+                    unsafe { crate::LLHandle::leaked(llvm_sys::core::LLVMBasicBlockAsValue(self.ptr()), Normal) }
                 }
             }
-            impl<'a, K> private::Sealed for crate::LLHandle<'a, K, llvm_sys::LLVMValue> {}
-            impl<'a, K: 'a> crate::Value<'a> for crate::LLHandle<'a, K, llvm_sys::LLVMValue> {
-                type Tag = K;
-                type Kind = llvm_sys::LLVMValue;
-                type Mod<'b> = crate::LLHandle<'b, Normal, llvm_sys::LLVMModule>;
-                fn r#mod<'b: 'a>(&'b self) -> Self::Mod<'b> {
-                    let ptr = self.ptr();
-                    let ptr = unsafe { llvm_sys::core::LLVMGetGlobalParent(ptr) };
+            impl<'a> crate::Metadata<'a> for crate::LLHandle<'a, Normal, llvm_sys::LLVMOpaqueMetadata> {
+                type Ctx<'b>
+                    = crate::LLHandle<'b, Normal, llvm_sys::LLVMContext>
+                where
+                    Self: 'b;
+                fn md_string(ctx: Self::Ctx<'a>, s: &CStr) -> Self {
+                    let ptr = unsafe {
+                        llvm_sys::core::LLVMMDStringInContext2(ctx.ptr(), s.as_ptr(), s.count_bytes())
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn md_node(ctx: Self::Ctx<'a>, elems: impl Iterator<Item = Self>) -> Self {
+                    let mut elems = elems.map(|e| e.ptr()).collect::<Vec<_>>();
+                    let ptr = unsafe {
+                        llvm_sys::core::LLVMMDNodeInContext2(ctx.ptr(), elems.as_mut_ptr(), elems.len())
+                    };
                     unsafe { crate::LLHandle::leaked(ptr, Normal) }
                 }
             }
-            impl<'a> crate::Ty<'a> for crate::LLHandle<'a, Normal, llvm_sys::LLVMType> {
+            impl<'a> crate::DIBuilder<'a> for crate::LLHandle<'a, Normal, llvm_sys::LLVMOpaqueDIBuilder> {
+                type Mod<'b> = crate::LLHandle<'b, Normal, llvm_sys::LLVMModule> where Self: 'b;
+                type Metadata<'b> = crate::LLHandle<'b, Normal, llvm_sys::LLVMOpaqueMetadata> where Self: 'b;
+                type Builder<'b> = crate::LLHandle<'b, Normal, llvm_sys::LLVMBuilder> where Self: 'b;
+                type Func<'b> = crate::LLHandle<'b, FuncTag, llvm_sys::LLVMValue> where Self: 'b;
+                fn new_in_mod(r#mod: Self::Mod<'a>) -> Self {
+                    let ptr = unsafe { llvm_sys::debuginfo::LLVMCreateDIBuilder(r#mod.ptr()) };
+                    unsafe {
+                        crate::LLHandle::from_raw_parts(
+                            ptr,
+                            |a, _| {
+                                llvm_sys::debuginfo::LLVMDIBuilderFinalize(a);
+                                llvm_sys::debuginfo::LLVMDisposeDIBuilder(a)
+                            },
+                            Normal,
+                        )
+                    }
+                    .owned_by(&r#mod)
+                }
+                fn finalize(&self) {
+                    unsafe { llvm_sys::debuginfo::LLVMDIBuilderFinalize(self.ptr()) }
+                }
+                fn create_file(&self, filename: &CStr, directory: &CStr) -> Self::Metadata<'a> {
+                    let ptr = unsafe {
+                        llvm_sys::debuginfo::LLVMDIBuilderCreateFile(
+                            self.ptr(),
+                            filename.as_ptr(),
+                            filename.count_bytes(),
+                            directory.as_ptr(),
+                            directory.count_bytes(),
+                        )
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn create_compile_unit(
+                    &self,
+                    lang: crate::SourceLanguage,
+                    file: Self::Metadata<'a>,
+                    producer: &CStr,
+                    optimized: bool,
+                ) -> Self::Metadata<'a> {
+                    let ptr = unsafe {
+                        llvm_sys::debuginfo::LLVMDIBuilderCreateCompileUnit(
+                            self.ptr(),
+                            lang.into(),
+                            file.ptr(),
+                            producer.as_ptr(),
+                            producer.count_bytes(),
+                            optimized as _,
+                            c"".as_ptr(),
+                            0,
+                            0,
+                            c"".as_ptr(),
+                            0,
+                            llvm_sys::debuginfo::LLVMDWARFEmissionKind::LLVMDWARFEmissionKindFull,
+                            0,
+                            0,
+                            0,
+                            c"".as_ptr(),
+                            0,
+                            c"".as_ptr(),
+                            0,
+                        )
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn create_basic_type(&self, name: &CStr, size_in_bits: u64, encoding: u32) -> Self::Metadata<'a> {
+                    let ptr = unsafe {
+                        llvm_sys::debuginfo::LLVMDIBuilderCreateBasicType(
+                            self.ptr(),
+                            name.as_ptr(),
+                            name.count_bytes(),
+                            size_in_bits,
+                            encoding,
+                            llvm_sys::debuginfo::LLVMDIFlagZero,
+                        )
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn create_subroutine_type(
+                    &self,
+                    file: Self::Metadata<'a>,
+                    types: impl Iterator<Item = Option<Self::Metadata<'a>>>,
+                ) -> Self::Metadata<'a> {
+                    let mut types = types
+                        .map(|t| t.map_or(std::ptr::null_mut(), |t| t.ptr()))
+                        .collect::<Vec<_>>();
+                    let ptr = unsafe {
+                        llvm_sys::debuginfo::LLVMDIBuilderCreateSubroutineType(
+                            self.ptr(),
+                            file.ptr(),
+                            types.as_mut_ptr(),
+                            types.len().try_into().unwrap(),
+                            llvm_sys::debuginfo::LLVMDIFlagZero,
+                        )
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn create_function(
+                    &self,
+                    func: Self::Func<'a>,
+                    scope: Self::Metadata<'a>,
+                    name: &CStr,
+                    file: Self::Metadata<'a>,
+                    line: u32,
+                    ty: Self::Metadata<'a>,
+                ) -> Self::Metadata<'a> {
+                    let ptr = unsafe {
+                        let sp = llvm_sys::debuginfo::LLVMDIBuilderCreateFunction(
+                            self.ptr(),
+                            scope.ptr(),
+                            name.as_ptr(),
+                            name.count_bytes(),
+                            c"".as_ptr(),
+                            0,
+                            file.ptr(),
+                            line,
+                            ty.ptr(),
+                            0,
+                            1,
+                            line,
+                            llvm_sys::debuginfo::LLVMDIFlagZero,
+                            0,
+                        );
+                        llvm_sys::debuginfo::LLVMSetSubprogram(func.ptr(), sp);
+                        sp
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn set_location(
+                    &self,
+                    builder: &Self::Builder<'a>,
+                    line: u32,
+                    col: u32,
+                    scope: Self::Metadata<'a>,
+                ) {
+                    crate::Builder::set_debug_location(builder, line, col, scope)
+                }
+            }
+            impl<'a> crate::Builder<'a> for crate::LLHandle<'a, Normal, llvm_sys::LLVMBuilder> {
+                type BB<'b,'e,'d>
+                    = crate::LLHandle<'b, Normal, llvm_sys::LLVMBasicBlock>
+                where
+                    Self: 'b,
+                    'a: 'b, Self: 'e, Self: 'd;
+                type ValKind<'d, 'b> = llvm_sys::LLVMValue  where
+                Self: 'd,
+                Self: 'b;
+                // type InternalValShim<'b: 'a, 'd, 'e, K: 'b> = crate::LLHandle<'b,K,llvm_sys::LLVMValue> where K: Sized, K: 'b, Self: 'd, Self: 'e;
+                // type Val<'b: 'a,K: 'b> = crate::LLHandle<'b,K,llvm_sys::LLVMValue> where K: Sized, K: 'b;
+                type Ty<'b>
+                    = crate::LLHandle<'b, Normal, llvm_sys::LLVMType>
+                where
+                    Self: 'b;
                 type Ctx<'b>
                     = crate::LLHandle<'b, Normal, llvm_sys::LLVMContext>
                 where
                     Self: 'b;
-                fn int_ty(ctx: Self::Ctx<'a>, size: u32) -> Self {
+                type Mod<'b> = crate::LLHandle<'b, Normal, llvm_sys::LLVMModule> where Self: 'b;
+                fn new_in_ctx(ctx: Self::Ctx<'a>) -> Self {
                     let ptr = ctx.ptr();
-                    let ptr = unsafe { llvm_sys::core::LLVMIntTypeInContext(ptr, size) };
-                    unsafe { LLHandle::leaked(ptr, Normal) }
+                    let ptr = unsafe { llvm_sys::core::LLVMCreateBuilderInContext(ptr) };
+                    unsafe {
+                        crate::LLHandle::from_raw_parts(
+                            ptr,
+                            |a, _| {
+                                set_mid_block(a, false);
+                                llvm_sys::core::LLVMDisposeBuilder(a)
+                            },
+                            Normal,
+                        )
+                    }
+                    .owned_by(&ctx)
                 }
-                fn ptr_ty(ctx: Self::Ctx<'a>, address_space: u32) -> Self {
-                    let ptr = ctx.ptr();
-                    let ptr =
-                        unsafe { llvm_sys::core::LLVMPointerTypeInContext(ptr, address_space) };
-                    unsafe { LLHandle::leaked(ptr, Normal) }
+                fn r#continue<'b, 'c>(&'b self, bb: Self::BB<'c,'a,'a>)
+                where
+                    'a: 'b + 'c,
+                {
+                    set_mid_block(self.ptr(), false);
+                    unsafe { llvm_sys::core::LLVMPositionBuilderAtEnd(self.ptr(), bb.ptr()) }
                 }
-                fn fun_ty(self, params: impl Iterator<Item = Self>) -> Self {
+                fn position_before<'d>(&self, instr: <Self::ValKind<'a,'a> as ValueKind>::Val<'d, Normal>) {
+                    set_mid_block(self.ptr(), true);
+                    unsafe { llvm_sys::core::LLVMPositionBuilderBefore(self.ptr(), instr.ptr()) }
+                }
+                fn clear_insertion_position(&self) {
+                    set_mid_block(self.ptr(), false);
+                    unsafe { llvm_sys::core::LLVMClearInsertionPosition(self.ptr()) }
+                }
+                fn set_debug_location<'d>(
+                    &self,
+                    line: u32,
+                    col: u32,
+                    scope: <Self::ValKind<'a, 'a> as ValueKind>::Metadata<'d>,
+                ) {
+                    unsafe {
+                        let bb = llvm_sys::core::LLVMGetInsertBlock(self.ptr());
+                        assert!(!bb.is_null(), "builder is not positioned");
+                        let ctx = llvm_sys::core::LLVMGetModuleContext(
+                            llvm_sys::core::LLVMGetGlobalParent(llvm_sys::core::LLVMGetBasicBlockParent(bb)),
+                        );
+                        let loc = llvm_sys::debuginfo::LLVMDIBuilderCreateDebugLocation(
+                            ctx,
+                            line,
+                            col,
+                            scope.ptr(),
+                            std::ptr::null_mut(),
+                        );
+                        llvm_sys::core::LLVMSetCurrentDebugLocation2(self.ptr(), loc);
+                    }
+                }
+                fn clear_debug_location(&self) {
+                    unsafe { llvm_sys::core::LLVMSetCurrentDebugLocation2(self.ptr(), std::ptr::null_mut()) }
+                }
+                fn insert<'d>(&self, instr: <Self::ValKind<'a,'a> as ValueKind>::Val<'d, Normal>, name: &CStr) {
+                    unsafe {
+                        check_insertion_point(self.ptr());
+                        llvm_sys::core::LLVMInsertIntoBuilderWithName(self.ptr(), instr.ptr(), name.as_ptr())
+                    }
+                }
+                fn ret_void<'b, 'res: 'b>(&'b self) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal> {
+                    unsafe { check_insertion_point(self.ptr()) };
+                    unsafe { crate::LLHandle::leaked(llvm_sys::core::LLVMBuildRetVoid(self.ptr()), Normal) }
+                }
+                fn insert_block<'b>(&'b self) -> Option<Self::BB<'b, 'a, 'a>>
+                where
+                    'a: 'b,
+                {
+                    let ptr = unsafe { llvm_sys::core::LLVMGetInsertBlock(self.ptr()) };
+                    if ptr.is_null() {
+                        return None;
+                    }
+                    Some(unsafe { crate::LLHandle::leaked(ptr, Normal) })
+                }
+                fn finish(&self) -> crate::Finished {
+                    self.clear_insertion_position();
+                    crate::Finished
+                }
+                fn invoke<'b, 'c, 'd, 'e, 'f, 'h, 'i, 'j, 'k, 'g: 'a + 'b + 'c + 'd + 'e + 'f + 'h + 'i>(
+                    &'b self,
+                    resty: Self::Ty<'c>,
+                    r#fn: <Self::ValKind<'a,'a> as ValueKind>::Val<'d, Normal>,
+                    args: impl Iterator<Item = <Self::ValKind<'h,'i> as ValueKind>::Val<'e, Normal>>,
+                    then: Self::BB<'j, 'a, 'a>,
+                    catch: Self::BB<'k, 'a, 'a>,
+                    name: &'f CStr,
+                ) -> <Self::ValKind<'b,'b> as ValueKind>::Val<'g, Normal> where 'a: 'h + 'i + 'j + 'k, Self: 'c{
+                    let mut args = args.map(|a| a.ptr()).collect::<Vec<_>>();
+                    let res = unsafe {
+                        check_insertion_point(self.ptr());
+                        llvm_sys::core::LLVMBuildInvoke2(
+                            self.ptr(),
+                            resty.ptr(),
+                            r#fn.ptr(),
+                            args.as_mut_ptr(),
+                            args.len().try_into().unwrap(),
+                            then.ptr(),
+                            catch.ptr(),
+                            name.as_ptr(),
+                        )
+                    };
+                    unsafe { crate::LLHandle::leaked(res, Normal) }
+                }
+                fn landing_pad<'b, 'c, 'f, 'g: 'a + 'b + 'c + 'f>(
+                    &'b self,
+                    ty: Self::Ty<'c>,
+                    name: &'f CStr,
+                ) -> crate::eh::LandingPad<<Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>>
+                where
+                    Self: 'c,
+                {
+                    let res = unsafe {
+                        check_insertion_point(self.ptr());
+                        llvm_sys::core::LLVMBuildLandingPad(self.ptr(), ty.ptr(), std::ptr::null_mut(), 0, name.as_ptr())
+                    };
+                    crate::eh::LandingPad(unsafe { crate::LLHandle::leaked(res, Normal) })
+                }
+                fn catch_switch<'b, 'd, 'k, 'f, 'g: 'a + 'b + 'd + 'f>(
+                    &'b self,
+                    parent: Option<<Self::ValKind<'a, 'a> as ValueKind>::Val<'d, Normal>>,
+                    unwind: Option<Self::BB<'k, 'a, 'a>>,
+                    name: &'f CStr,
+                ) -> crate::eh::CatchSwitch<<Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>>
+                where
+                    'a: 'k,
+                {
+                    let res = unsafe {
+                        check_insertion_point(self.ptr());
+                        llvm_sys::core::LLVMBuildCatchSwitch(
+                            self.ptr(),
+                            parent.map_or(std::ptr::null_mut(), |p| p.ptr()),
+                            unwind.map_or(std::ptr::null_mut(), |bb| bb.ptr()),
+                            0,
+                            name.as_ptr(),
+                        )
+                    };
+                    crate::eh::CatchSwitch(unsafe { crate::LLHandle::leaked(res, Normal) })
+                }
+                fn catch_pad<'b, 'd, 'e, 'f, 'h, 'i, 'g: 'a + 'b + 'd + 'e + 'f + 'h + 'i>(
+                    &'b self,
+                    catch_switch: &crate::eh::CatchSwitch<<Self::ValKind<'a, 'a> as ValueKind>::Val<'d, Normal>>,
+                    args: impl Iterator<Item = <Self::ValKind<'h, 'i> as ValueKind>::Val<'e, Normal>>,
+                    name: &'f CStr,
+                ) -> crate::eh::CatchPad<<Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>>
+                where
+                    'a: 'h + 'i,
+                {
+                    let mut args = args.map(|a| a.ptr()).collect::<Vec<_>>();
+                    let res = unsafe {
+                        check_insertion_point(self.ptr());
+                        llvm_sys::core::LLVMBuildCatchPad(
+                            self.ptr(),
+                            catch_switch.0.ptr(),
+                            args.as_mut_ptr(),
+                            args.len().try_into().unwrap(),
+                            name.as_ptr(),
+                        )
+                    };
+                    crate::eh::CatchPad(unsafe { crate::LLHandle::leaked(res, Normal) })
+                }
+                fn cleanup_pad<'b, 'd, 'e, 'f, 'h, 'i, 'g: 'a + 'b + 'd + 'e + 'f + 'h + 'i>(
+                    &'b self,
+                    parent: Option<<Self::ValKind<'a, 'a> as ValueKind>::Val<'d, Normal>>,
+                    args: impl Iterator<Item = <Self::ValKind<'h, 'i> as ValueKind>::Val<'e, Normal>>,
+                    name: &'f CStr,
+                ) -> crate::eh::CleanupPad<<Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>>
+                where
+                    'a: 'h + 'i,
+                {
+                    let mut args = args.map(|a| a.ptr()).collect::<Vec<_>>();
+                    let res = unsafe {
+                        check_insertion_point(self.ptr());
+                        llvm_sys::core::LLVMBuildCleanupPad(
+                            self.ptr(),
+                            parent.map_or(std::ptr::null_mut(), |p| p.ptr()),
+                            args.as_mut_ptr(),
+                            args.len().try_into().unwrap(),
+                            name.as_ptr(),
+                        )
+                    };
+                    crate::eh::CleanupPad(unsafe { crate::LLHandle::leaked(res, Normal) })
+                }
+                fn indirect_br<'b, 'd, 'j, 'g: 'a + 'b + 'd>(
+                    &'b self,
+                    addr: <Self::ValKind<'a, 'a> as ValueKind>::Val<'d, Normal>,
+                    dests: impl Iterator<Item = Self::BB<'j, 'a, 'a>>,
+                ) -> <Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>
+                where
+                    'a: 'j,
+                {
+                    let dests = dests.collect::<Vec<_>>();
+                    let res = unsafe {
+                        check_insertion_point(self.ptr());
+                        llvm_sys::core::LLVMBuildIndirectBr(self.ptr(), addr.ptr(), dests.len().try_into().unwrap())
+                    };
+                    for dest in dests {
+                        unsafe { llvm_sys::core::LLVMAddDestination(res, dest.ptr()) }
+                    }
+                    unsafe { crate::LLHandle::leaked(res, Normal) }
+                }
+                fn call<'b, 'c, 'd, 'e, 'f,'h,'i, 'g: 'a + 'b + 'c + 'd + 'e + 'f + 'h + 'i>(
+                    &'b self,
+                    resty: Self::Ty<'c>,
+                    r#fn: <Self::ValKind<'a,'a> as ValueKind>::Val<'d, Normal>,
+                    args: impl Iterator<Item = <Self::ValKind<'h,'i> as ValueKind>::Val<'e, Normal>>,
+                    name: &'f CStr,
+                ) -> <Self::ValKind<'b,'b> as ValueKind>::Val<'g, Normal> where 'a: 'h + 'i, Self: 'c{
+                    let mut args = args.map(|a| a.ptr()).collect::<smallvec::SmallVec<[_; 8]>>();
+                    let res = unsafe { build_call(self.ptr(), resty.ptr(), r#fn.ptr(), &mut args, name) };
+                    unsafe { crate::LLHandle::leaked(res, Normal) }
+                }
+                fn call0<'b, 'c, 'd, 'f, 'g: 'a + 'b + 'c + 'd + 'f>(
+                    &'b self,
+                    resty: Self::Ty<'c>,
+                    r#fn: <Self::ValKind<'a, 'a> as ValueKind>::Val<'d, Normal>,
+                    name: &'f CStr,
+                ) -> <Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>
+                where
+                    Self: 'c,
+                {
+                    let res = unsafe { build_call(self.ptr(), resty.ptr(), r#fn.ptr(), &mut [], name) };
+                    unsafe { crate::LLHandle::leaked(res, Normal) }
+                }
+                fn call1<'b, 'c, 'd, 'e, 'f, 'g: 'a + 'b + 'c + 'd + 'e + 'f>(
+                    &'b self,
+                    resty: Self::Ty<'c>,
+                    r#fn: <Self::ValKind<'a, 'a> as ValueKind>::Val<'d, Normal>,
+                    a0: <Self::ValKind<'a, 'a> as ValueKind>::Val<'e, Normal>,
+                    name: &'f CStr,
+                ) -> <Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>
+                where
+                    Self: 'c,
+                {
+                    let res = unsafe { build_call(self.ptr(), resty.ptr(), r#fn.ptr(), &mut [a0.ptr()], name) };
+                    unsafe { crate::LLHandle::leaked(res, Normal) }
+                }
+                fn call2<'b, 'c, 'd, 'e, 'h, 'f, 'g: 'a + 'b + 'c + 'd + 'e + 'h + 'f>(
+                    &'b self,
+                    resty: Self::Ty<'c>,
+                    r#fn: <Self::ValKind<'a, 'a> as ValueKind>::Val<'d, Normal>,
+                    a0: <Self::ValKind<'a, 'a> as ValueKind>::Val<'e, Normal>,
+                    a1: <Self::ValKind<'a, 'a> as ValueKind>::Val<'h, Normal>,
+                    name: &'f CStr,
+                ) -> <Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>
+                where
+                    Self: 'c,
+                {
+                    let mut args = [a0.ptr(), a1.ptr()];
+                    let res = unsafe { build_call(self.ptr(), resty.ptr(), r#fn.ptr(), &mut args, name) };
+                    unsafe { crate::LLHandle::leaked(res, Normal) }
+                }
+                fn call3<'b, 'c, 'd, 'e, 'h, 'i, 'f, 'g: 'a + 'b + 'c + 'd + 'e + 'h + 'i + 'f>(
+                    &'b self,
+                    resty: Self::Ty<'c>,
+                    r#fn: <Self::ValKind<'a, 'a> as ValueKind>::Val<'d, Normal>,
+                    a0: <Self::ValKind<'a, 'a> as ValueKind>::Val<'e, Normal>,
+                    a1: <Self::ValKind<'a, 'a> as ValueKind>::Val<'h, Normal>,
+                    a2: <Self::ValKind<'a, 'a> as ValueKind>::Val<'i, Normal>,
+                    name: &'f CStr,
+                ) -> <Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>
+                where
+                    Self: 'c,
+                {
+                    let mut args = [a0.ptr(), a1.ptr(), a2.ptr()];
+                    let res = unsafe { build_call(self.ptr(), resty.ptr(), r#fn.ptr(), &mut args, name) };
+                    unsafe { crate::LLHandle::leaked(res, Normal) }
+                }
+                fn call_struct<'b, 'c, 'd, 'e, 'f, 'h, 'i, 'g: 'a + 'b + 'c + 'd + 'e + 'f + 'h + 'i>(
+                    &'b self,
+                    resty: Self::Ty<'c>,
+                    r#fn: <Self::ValKind<'a, 'a> as ValueKind>::Val<'d, Normal>,
+                    args: impl Iterator<Item = <Self::ValKind<'h, 'i> as ValueKind>::Val<'e, Normal>>,
+                    name: &'f CStr,
+                ) -> Vec<<Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>>
+                where
+                    'a: 'h + 'i,
+                    Self: 'c,
+                {
+                    use llvm_sys::core::*;
+                    let b = self.ptr();
+                    let ret = unsafe { LLVMGetReturnType(resty.ptr()) };
+                    assert!(
+                        unsafe { LLVMGetTypeKind(ret) } == llvm_sys::LLVMTypeKind::LLVMStructTypeKind,
+                        "call_struct requires a function returning a struct"
+                    );
+                    let mut args = args.map(|a| a.ptr()).collect::<Vec<_>>();
+                    unsafe {
+                        check_insertion_point(b);
+                        let call = LLVMBuildCall2(
+                            b,
+                            resty.ptr(),
+                            r#fn.ptr(),
+                            args.as_mut_ptr(),
+                            args.len().try_into().unwrap(),
+                            name.as_ptr(),
+                        );
+                        (0..LLVMCountStructElementTypes(ret))
+                            .map(|i| crate::LLHandle::leaked(LLVMBuildExtractValue(b, call, i, c"".as_ptr()), Normal))
+                            .collect()
+                    }
+                }
+                fn gep2<'b, 'c, 'd, 'e, 'f, 'h, 'i, 'g: 'a + 'b + 'c + 'd + 'e + 'f + 'h + 'i>(
+                    &'b self,
+                    resty: Self::Ty<'c>,
+                    ptr2: <Self::ValKind<'a, 'a> as ValueKind>::Val<'d, Normal>,
+                    args: impl Iterator<Item = <Self::ValKind<'h, 'i> as ValueKind>::Val<'e, Normal>>,
+                    name: &'f CStr,
+                ) -> <Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>
+                where
+                    'a: 'h + 'i, Self: 'c{
+                        let ptr = self.ptr();
+                        let resty = resty.ptr();
+                        let r#fn = ptr2.ptr();
+                        let mut args = args.map(|a| a.ptr()).collect::<smallvec::SmallVec<[_; 8]>>();
+                        let res = unsafe {
+                            check_insertion_point(self.ptr());
+                            llvm_sys::core::LLVMBuildGEP2(
+                                ptr,
+                                resty,
+                                r#fn,
+                                args.as_mut_ptr(),
+                                args.len().try_into().unwrap(),
+                                name.as_ptr(),
+                            )
+                        };
+                        unsafe { crate::LLHandle::leaked(res, Normal) }
+                    }
+                fn gep2_in_bounds<'b, 'c, 'd, 'e, 'f, 'h, 'i, 'g: 'a + 'b + 'c + 'd + 'e + 'f + 'h + 'i>(
+                    &'b self,
+                    resty: Self::Ty<'c>,
+                    ptr2: <Self::ValKind<'a, 'a> as ValueKind>::Val<'d, Normal>,
+                    args: impl Iterator<Item = <Self::ValKind<'h, 'i> as ValueKind>::Val<'e, Normal>>,
+                    name: &'f CStr,
+                ) -> <Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>
+                where
+                    'a: 'h + 'i, Self: 'c{
+                        let ptr = self.ptr();
+                        let resty = resty.ptr();
+                        let r#fn = ptr2.ptr();
+                        let mut args = args.map(|a| a.ptr()).collect::<smallvec::SmallVec<[_; 8]>>();
+                        let res = unsafe {
+                            check_insertion_point(self.ptr());
+                            llvm_sys::core::LLVMBuildInBoundsGEP2(
+                                ptr,
+                                resty,
+                                r#fn,
+                                args.as_mut_ptr(),
+                                args.len().try_into().unwrap(),
+                                name.as_ptr(),
+                            )
+                        };
+                        unsafe { crate::LLHandle::leaked(res, Normal) }
+                    }
+                fn try_call<'b, 'c, 'd, 'e, 'f,'h,'i, 'g: 'a + 'b + 'c + 'd + 'e + 'f + 'h + 'i>(
+                    &'b self,
+                    resty: Self::Ty<'c>,
+                    r#fn: <Self::ValKind<'a,'a> as ValueKind>::Val<'d, Normal>,
+                    args: impl Iterator<Item = <Self::ValKind<'h,'i> as ValueKind>::Val<'e, Normal>>,
+                    name: &'f CStr,
+                ) -> Result<<Self::ValKind<'b,'b> as ValueKind>::Val<'g, Normal>, crate::BuildError> where 'a: 'h + 'i, Self: 'c{
+                    use llvm_sys::core::*;
                     let ptr = self.ptr();
-                    let mut args = params.map(|p| p.ptr()).collect::<Vec<_>>();
-                    let ptr = unsafe {
-                        llvm_sys::core::LLVMFunctionType(
+                    let resty = resty.ptr();
+                    let r#fn = r#fn.ptr();
+                    let mut args = args.map(|a| a.ptr()).collect::<Vec<_>>();
+                    unsafe {
+                        if LLVMGetTypeKind(resty) != llvm_sys::LLVMTypeKind::LLVMFunctionTypeKind {
+                            return Err(crate::BuildError::TypeMismatch);
+                        }
+                        let expected: usize = LLVMCountParamTypes(resty).try_into().unwrap();
+                        let vararg = LLVMIsFunctionVarArg(resty) != 0;
+                        if args.len() < expected || (!vararg && args.len() != expected) {
+                            return Err(crate::BuildError::ArityMismatch { expected, found: args.len() });
+                        }
+                        let mut params = vec![std::ptr::null_mut(); expected];
+                        LLVMGetParamTypes(resty, params.as_mut_ptr());
+                        if params.iter().zip(&args).any(|(p, a)| *p != LLVMTypeOf(*a)) {
+                            return Err(crate::BuildError::TypeMismatch);
+                        }
+                    }
+                    let res = unsafe {
+                        check_insertion_point(ptr);
+                        LLVMBuildCall2(
                             ptr,
+                            resty,
+                            r#fn,
                             args.as_mut_ptr(),
                             args.len().try_into().unwrap(),
-                            0,
+                            name.as_ptr(),
                         )
                     };
-                    unsafe { LLHandle::leaked(ptr, Normal) }
+                    if res.is_null() {
+                        return Err(crate::BuildError::Null);
+                    }
+                    Ok(unsafe { crate::LLHandle::leaked(res, Normal) })
                 }
-                fn struct_ty(ctx: Self::Ctx<'a>, fields: impl Iterator<Item = Self>, packed: bool) -> Self{
-                    let mut fields = fields.map(|p| p.ptr()).collect::<Vec<_>>();
-                    let ptr = unsafe{
-                        llvm_sys::core::LLVMStructTypeInContext(ctx.ptr(),fields.as_mut_ptr(),fields.len().try_into().unwrap(),if packed{1}else{0})
+                fn call_with_signature<'b, 'd, 'e, 'f, 'h, 'i, 'g: 'a + 'b + 'd + 'e + 'f + 'h + 'i>(
+                    &'b self,
+                    sig: &crate::Signature<Self::Ty<'a>>,
+                    r#fn: <Self::ValKind<'a, 'a> as ValueKind>::Val<'d, Normal>,
+                    args: impl Iterator<Item = <Self::ValKind<'h, 'i> as ValueKind>::Val<'e, Normal>>,
+                    name: &'f CStr,
+                ) -> Result<<Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>, crate::BuildError>
+                where
+                    'a: 'h + 'i,
+                {
+                    let mut args = args.map(|a| a.ptr()).collect::<smallvec::SmallVec<[_; 8]>>();
+                    let params = sig.params();
+                    if args.len() < params.len() || (!sig.is_var_arg() && args.len() != params.len()) {
+                        return Err(crate::BuildError::ArityMismatch { expected: params.len(), found: args.len() });
+                    }
+                    if cfg!(debug_assertions)
+                        && params.iter().zip(&args).any(|(p, a)| p.ptr() != unsafe { llvm_sys::core::LLVMTypeOf(*a) })
+                    {
+                        return Err(crate::BuildError::TypeMismatch);
+                    }
+                    let res = unsafe { build_call(self.ptr(), sig.fn_ty().ptr(), r#fn.ptr(), &mut args, name) };
+                    if res.is_null() {
+                        return Err(crate::BuildError::Null);
+                    }
+                    Ok(unsafe { crate::LLHandle::leaked(res, Normal) })
+                }
+                fn try_gep2<'b, 'c, 'd, 'e, 'f, 'h, 'i, 'g: 'a + 'b + 'c + 'd + 'e + 'f + 'h + 'i>(
+                    &'b self,
+                    resty: Self::Ty<'c>,
+                    ptr2: <Self::ValKind<'a, 'a> as ValueKind>::Val<'d, Normal>,
+                    args: impl Iterator<Item = <Self::ValKind<'h, 'i> as ValueKind>::Val<'e, Normal>>,
+                    name: &'f CStr,
+                ) -> Result<<Self::ValKind<'b, 'b> as ValueKind>::Val<'g, Normal>, crate::BuildError>
+                where
+                    'a: 'h + 'i, Self: 'c{
+                        use llvm_sys::core::*;
+                        use llvm_sys::LLVMTypeKind::*;
+                        let args = args.collect::<Vec<_>>();
+                        let valid = unsafe {
+                            let mut ty = resty.ptr();
+                            LLVMGetTypeKind(LLVMTypeOf(ptr2.ptr())) == LLVMPointerTypeKind
+                                && LLVMTypeIsSized(ty) != 0
+                                && args.iter().enumerate().all(|(i, index)| {
+                                    let index = index.ptr();
+                                    if LLVMGetTypeKind(LLVMTypeOf(index)) != LLVMIntegerTypeKind {
+                                        return false;
+                                    }
+                                    // The first index steps over whole `resty`s.
+                                    if i == 0 {
+                                        return true;
+                                    }
+                                    match LLVMGetTypeKind(ty) {
+                                        LLVMStructTypeKind => {
+                                            if LLVMIsAConstantInt(index).is_null()
+                                                || LLVMGetIntTypeWidth(LLVMTypeOf(index)) != 32
+                                            {
+                                                return false;
+                                            }
+                                            let field = LLVMConstIntGetZExtValue(index);
+                                            if field >= LLVMCountStructElementTypes(ty).into() {
+                                                return false;
+                                            }
+                                            ty = LLVMStructGetTypeAtIndex(ty, field as u32);
+                                            true
+                                        }
+                                        LLVMArrayTypeKind | LLVMVectorTypeKind => {
+                                            ty = LLVMGetElementType(ty);
+                                            true
+                                        }
+                                        _ => false,
+                                    }
+                                })
+                        };
+                        if !valid {
+                            return Err(crate::BuildError::TypeMismatch);
+                        }
+                        Ok(self.gep2(resty, ptr2, args.into_iter(), name))
+                    }
+                fn try_binop<'b, 'lhs, 'rhs, 'name, 'res: 'lhs + 'rhs + 'name + 'b>(
+                    &'b self,
+                    op: crate::BinOp,
+                    lhs: <Self::ValKind<'a, 'a> as ValueKind>::Val<'lhs, Normal>,
+                    rhs: <Self::ValKind<'a, 'a> as ValueKind>::Val<'rhs, Normal>,
+                    name: &'name CStr,
+                ) -> Result<<Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>, crate::BuildError>
+                where
+                    'a: 'lhs + 'rhs + 'name,
+                {
+                    use llvm_sys::core::*;
+                    use llvm_sys::LLVMTypeKind::*;
+                    let (lhs, rhs) = (lhs.ptr(), rhs.ptr());
+                    let integer = unsafe {
+                        let ty = LLVMTypeOf(lhs);
+                        let scalar = match LLVMGetTypeKind(ty) {
+                            LLVMVectorTypeKind | LLVMScalableVectorTypeKind => LLVMGetElementType(ty),
+                            _ => ty,
+                        };
+                        LLVMGetTypeKind(scalar) == LLVMIntegerTypeKind
                     };
-                    unsafe { LLHandle::leaked(ptr, Normal) }
+                    if !integer || unsafe { LLVMTypeOf(lhs) != LLVMTypeOf(rhs) } {
+                        return Err(crate::BuildError::TypeMismatch);
+                    }
+                    let res = unsafe {
+                        check_insertion_point(self.ptr());
+                        LLVMBuildBinOp(self.ptr(), op.into(), lhs, rhs, name.as_ptr()) };
+                    if res.is_null() {
+                        return Err(crate::BuildError::Null);
+                    }
+                    Ok(unsafe { crate::LLHandle::leaked(res, Normal) })
                 }
-            }
-            impl crate::ValueKind for llvm_sys::LLVMValue {
-                type Val<'a, K: 'a> = crate::LLHandle<'a, K, llvm_sys::LLVMValue>;
-                type Mod<'a> = crate::LLHandle<'a, Normal, llvm_sys::LLVMModule>;
-                type Func<'a> = crate::LLHandle<'a, FuncTag, llvm_sys::LLVMValue>;
-                type Ty<'a> = crate::LLHandle<'a, Normal, llvm_sys::LLVMType>;
-                fn const_int<'a>(ty: Self::Ty<'a>, n: u64, sext: bool) -> Self::Val<'a, Normal> {
-                    let ptr = ty.ptr();
-                    let ptr =
-                        unsafe { llvm_sys::core::LLVMConstInt(ptr, n, if sext { 1 } else { 0 }) };
-                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                fn build_bounds_check<'b, 'idx, 'len>(
+                    &'b self,
+                    index: <Self::ValKind<'a, 'a> as ValueKind>::Val<'idx, Normal>,
+                    len: <Self::ValKind<'a, 'a> as ValueKind>::Val<'len, Normal>,
+                    on_fail: impl FnOnce(&Self),
+                ) where
+                    'a: 'idx + 'len,
+                {
+                    use llvm_sys::core::*;
+                    let b = self.ptr();
+                    unsafe {
+                        let block = LLVMGetInsertBlock(b);
+                        assert!(!block.is_null(), "builder is not positioned");
+                        check_insertion_point(b);
+                        let func = LLVMGetBasicBlockParent(block);
+                        let ctx = LLVMGetTypeContext(LLVMTypeOf(index.ptr()));
+                        let fail = LLVMAppendBasicBlockInContext(ctx, func, c"bounds.fail".as_ptr());
+                        let ok = LLVMAppendBasicBlockInContext(ctx, func, c"bounds.ok".as_ptr());
+                        let oob = LLVMBuildICmp(
+                            b,
+                            llvm_sys::LLVMIntPredicate::LLVMIntUGE,
+                            index.ptr(),
+                            len.ptr(),
+                            c"".as_ptr(),
+                        );
+                        LLVMBuildCondBr(b, oob, fail, ok);
+                        set_mid_block(b, false);
+                        LLVMPositionBuilderAtEnd(b, fail);
+                        on_fail(self);
+                        let end = LLVMGetInsertBlock(b);
+                        if !end.is_null() && LLVMGetBasicBlockTerminator(end).is_null() {
+                            LLVMBuildUnreachable(b);
+                        }
+                        set_mid_block(b, false);
+                        LLVMPositionBuilderAtEnd(b, ok);
+                    }
                 }
-                fn function<'a, 'b, 'c, 'd: 'a + 'b + 'c>(
-                    r#mod: Self::Mod<'a>,
-                    name: &'b CStr,
-                    ty: Self::Ty<'c>,
-                ) -> Self::Func<'d> {
+                fn mem_set<'b, 'dst, 'val, 'len, 'res: 'dst + 'val + 'len + 'b>(
+                    &'b self,
+                    dst: <Self::ValKind<'a, 'a> as ValueKind>::Val<'dst, Normal>,
+                    val: <Self::ValKind<'a, 'a> as ValueKind>::Val<'val, Normal>,
+                    len: <Self::ValKind<'a, 'a> as ValueKind>::Val<'len, Normal>,
+                    align: u32,
+                ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
+                where
+                    'a: 'dst + 'val + 'len,
+                {
                     let ptr = unsafe {
-                        llvm_sys::core::LLVMAddFunction(r#mod.ptr(), name.as_ptr(), ty.ptr())
+                        assert!(!llvm_sys::core::LLVMGetInsertBlock(self.ptr()).is_null(), "builder is not positioned");
+                        check_insertion_point(self.ptr());
+                        llvm_sys::core::LLVMBuildMemSet(self.ptr(), dst.ptr(), val.ptr(), len.ptr(), align)
                     };
-                    unsafe { crate::LLHandle::leaked(ptr, FuncTag) }
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
                 }
-            }
-            impl<'a> crate::Ctx<'a> for crate::LLHandle<'a, Normal, llvm_sys::LLVMContext> {}
-            impl<'a> crate::Mod<'a> for crate::LLHandle<'a, Normal, llvm_sys::LLVMModule> {
-                type Ctx<'b>
-                    = crate::LLHandle<'b, Normal, llvm_sys::LLVMContext>
+                fn mem_cpy<'b, 'dst, 'src, 'len, 'res: 'dst + 'src + 'len + 'b>(
+                    &'b self,
+                    dst: <Self::ValKind<'a, 'a> as ValueKind>::Val<'dst, Normal>,
+                    dst_align: u32,
+                    src: <Self::ValKind<'a, 'a> as ValueKind>::Val<'src, Normal>,
+                    src_align: u32,
+                    len: <Self::ValKind<'a, 'a> as ValueKind>::Val<'len, Normal>,
+                ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
                 where
-                    Self: 'b;
-                fn ctx<'b: 'a>(&'b self) -> Self::Ctx<'b> {
-                    let ptr = self.ptr();
-                    let ptr = unsafe { llvm_sys::core::LLVMGetModuleContext(ptr) };
+                    'a: 'dst + 'src + 'len,
+                {
+                    let ptr = unsafe {
+                        assert!(!llvm_sys::core::LLVMGetInsertBlock(self.ptr()).is_null(), "builder is not positioned");
+                        check_insertion_point(self.ptr());
+                        llvm_sys::core::LLVMBuildMemCpy(self.ptr(), dst.ptr(), dst_align, src.ptr(), src_align, len.ptr())
+                    };
                     unsafe { crate::LLHandle::leaked(ptr, Normal) }
                 }
-                fn create_mod<'b, 'c, 'd>(a: &'b CStr, ctx: &'c Self::Ctx<'d>) -> Self
+                fn mem_move<'b, 'dst, 'src, 'len, 'res: 'dst + 'src + 'len + 'b>(
+                    &'b self,
+                    dst: <Self::ValKind<'a, 'a> as ValueKind>::Val<'dst, Normal>,
+                    dst_align: u32,
+                    src: <Self::ValKind<'a, 'a> as ValueKind>::Val<'src, Normal>,
+                    src_align: u32,
+                    len: <Self::ValKind<'a, 'a> as ValueKind>::Val<'len, Normal>,
+                ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
                 where
-                    'a: 'b + 'c + 'd,
+                    'a: 'dst + 'src + 'len,
                 {
-                    let ptr = ctx.ptr();
                     let ptr = unsafe {
-                        llvm_sys::core::LLVMModuleCreateWithNameInContext(a.as_ptr(), ptr)
+                        assert!(!llvm_sys::core::LLVMGetInsertBlock(self.ptr()).is_null(), "builder is not positioned");
+                        check_insertion_point(self.ptr());
+                        llvm_sys::core::LLVMBuildMemMove(self.ptr(), dst.ptr(), dst_align, src.ptr(), src_align, len.ptr())
                     };
-                    unsafe {
-                        crate::LLHandle::from_raw_parts(
-                            ptr,
-                            |a, _| llvm_sys::core::LLVMDisposeModule(a),
-                            Normal,
-                        )
-                    }
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
                 }
-            }
-            impl<'a> crate::Func<'a> for crate::LLHandle<'a, FuncTag, llvm_sys::LLVMValue> {}
-            impl<'a> crate::BB<'a> for crate::LLHandle<'a, Normal, llvm_sys::LLVMBasicBlock> {
-                type Func<'b>
-                    = crate::LLHandle<'b, FuncTag, llvm_sys::LLVMValue>
-                where
-                    'a: 'b,
-                    Self: 'b;
-                fn new<'b, 'c>(f: Self::Func<'b>, name: &'c CStr) -> Self
+                fn va_start<'b, 'list, 'res: 'list + 'b>(
+                    &'b self,
+                    list: <Self::ValKind<'a, 'a> as ValueKind>::Val<'list, Normal>,
+                ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
                 where
-                    'a: 'b + 'c,
+                    'a: 'list,
                 {
-                    let ptr = f.ptr();
-                    let ptr = unsafe { llvm_sys::core::LLVMAppendBasicBlock(ptr, name.as_ptr()) };
+                    let ptr = unsafe { call_va_intrinsic(self.ptr(), c"llvm.va_start", list.ptr()) };
                     unsafe { crate::LLHandle::leaked(ptr, Normal) }
                 }
-            }
-            impl<'a> crate::Builder<'a> for crate::LLHandle<'a, Normal, llvm_sys::LLVMBuilder> {
-                type BB<'b,'e,'d>
-                    = crate::LLHandle<'b, Normal, llvm_sys::LLVMBasicBlock>
-                where
-                    Self: 'b,
-                    'a: 'b, Self: 'e, Self: 'd;
-                type ValKind<'d, 'b> = llvm_sys::LLVMValue  where
-                Self: 'd,
-                Self: 'b;
-                // type InternalValShim<'b: 'a, 'd, 'e, K: 'b> = crate::LLHandle<'b,K,llvm_sys::LLVMValue> where K: Sized, K: 'b, Self: 'd, Self: 'e;
-                // type Val<'b: 'a,K: 'b> = crate::LLHandle<'b,K,llvm_sys::LLVMValue> where K: Sized, K: 'b;
-                type Ty<'b>
-                    = crate::LLHandle<'b, Normal, llvm_sys::LLVMType>
-                where
-                    Self: 'b;
-                type Ctx<'b>
-                    = crate::LLHandle<'b, Normal, llvm_sys::LLVMContext>
+                fn va_end<'b, 'list, 'res: 'list + 'b>(
+                    &'b self,
+                    list: <Self::ValKind<'a, 'a> as ValueKind>::Val<'list, Normal>,
+                ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
                 where
-                    Self: 'b;
-                type Mod<'b> = crate::LLHandle<'b, Normal, llvm_sys::LLVMModule> where Self: 'b;
-                fn new_in_ctx(ctx: Self::Ctx<'a>) -> Self {
-                    let ptr = ctx.ptr();
-                    let ptr = unsafe { llvm_sys::core::LLVMCreateBuilderInContext(ptr) };
-                    unsafe {
-                        crate::LLHandle::from_raw_parts(
-                            ptr,
-                            |a, _| llvm_sys::core::LLVMDisposeBuilder(a),
-                            Normal,
-                        )
-                    }
+                    'a: 'list,
+                {
+                    let ptr = unsafe { call_va_intrinsic(self.ptr(), c"llvm.va_end", list.ptr()) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
                 }
-                fn r#continue<'b, 'c>(&'b self, bb: Self::BB<'c,'a,'a>)
+                fn struct_gep_typed<'b, 'ptr, 'name, 'res: 'ptr + 'name + 'b>(
+                    &'b self,
+                    ty: Self::Ty<'a>,
+                    ptr: <Self::ValKind<'a, 'a> as ValueKind>::Val<'ptr, Normal>,
+                    idx: u32,
+                    name: &'name CStr,
+                ) -> (<Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>, Self::Ty<'a>)
                 where
-                    'a: 'b + 'c,
+                    'a: 'ptr + 'name,
                 {
-                    unsafe { llvm_sys::core::LLVMPositionBuilderAtEnd(self.ptr(), bb.ptr()) }
+                    assert!(ty.kind() == crate::TypeKind::Struct, "struct_gep_typed requires a struct type");
+                    let n = unsafe { llvm_sys::core::LLVMCountStructElementTypes(ty.ptr()) };
+                    assert!(idx < n, "struct has no field {idx}");
+                    let field = unsafe {
+                        crate::LLHandle::leaked(llvm_sys::core::LLVMStructGetTypeAtIndex(ty.ptr(), idx), Normal)
+                    };
+                    let gep = unsafe {
+                        check_insertion_point(self.ptr());
+                        llvm_sys::core::LLVMBuildStructGEP2(self.ptr(), ty.ptr(), ptr.ptr(), idx, name.as_ptr())
+                    };
+                    (unsafe { crate::LLHandle::leaked(gep, Normal) }, field)
                 }
-                fn call<'b, 'c, 'd, 'e, 'f,'h,'i, 'g: 'a + 'b + 'c + 'd + 'e + 'f + 'h + 'i>(
+                fn gep_indices<'b, 'ptr, 'name, 'res: 'ptr + 'name + 'b>(
                     &'b self,
-                    resty: Self::Ty<'c>,
-                    r#fn: <Self::ValKind<'a,'a> as ValueKind>::Val<'d, Normal>,
-                    args: impl Iterator<Item = <Self::ValKind<'h,'i> as ValueKind>::Val<'e, Normal>>,
-                    name: &'f CStr,
-                ) -> <Self::ValKind<'_,'_> as ValueKind>::Val<'g, Normal> where 'a: 'h + 'i, Self: 'c{
-                    let ptr = self.ptr();
-                    let resty = resty.ptr();
-                    let r#fn = r#fn.ptr();
-                    let mut args = args.map(|a| a.ptr()).collect::<Vec<_>>();
+                    ty: Self::Ty<'a>,
+                    ptr: <Self::ValKind<'a, 'a> as ValueKind>::Val<'ptr, Normal>,
+                    indices: &[u64],
+                    name: &'name CStr,
+                ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
+                where
+                    'a: 'ptr + 'name,
+                {
+                    use llvm_sys::core::*;
+                    use llvm_sys::LLVMTypeKind as K;
                     let res = unsafe {
-                        llvm_sys::core::LLVMBuildCall2(
-                            ptr,
-                            resty,
-                            r#fn,
+                        check_insertion_point(self.ptr());
+                        let llctx = LLVMGetTypeContext(ty.ptr());
+                        let (i32_ty, i64_ty) = (LLVMInt32TypeInContext(llctx), LLVMInt64TypeInContext(llctx));
+                        let mut cur = ty.ptr();
+                        let mut args = Vec::with_capacity(indices.len());
+                        for (i, &idx) in indices.iter().enumerate() {
+                            if i == 0 {
+                                args.push(LLVMConstInt(i64_ty, idx, 0));
+                                continue;
+                            }
+                            match LLVMGetTypeKind(cur) {
+                                K::LLVMStructTypeKind => {
+                                    assert!(
+                                        idx < LLVMCountStructElementTypes(cur) as u64,
+                                        "struct has no field {idx}"
+                                    );
+                                    args.push(LLVMConstInt(i32_ty, idx, 0));
+                                    cur = LLVMStructGetTypeAtIndex(cur, idx as u32);
+                                }
+                                K::LLVMArrayTypeKind | K::LLVMVectorTypeKind => {
+                                    args.push(LLVMConstInt(i64_ty, idx, 0));
+                                    cur = LLVMGetElementType(cur);
+                                }
+                                _ => panic!("index {i} descends into a non-aggregate type"),
+                            }
+                        }
+                        LLVMBuildGEP2(
+                            self.ptr(),
+                            ty.ptr(),
+                            ptr.ptr(),
                             args.as_mut_ptr(),
                             args.len().try_into().unwrap(),
                             name.as_ptr(),
@@ -743,35 +6058,339 @@ macro_rules! impls {
                     };
                     unsafe { crate::LLHandle::leaked(res, Normal) }
                 }
-                fn gep2<'b, 'c, 'd, 'e, 'f, 'h, 'i, 'g: 'a + 'b + 'c + 'd + 'e + 'f + 'h + 'i>(
+                fn global_string_ptr<'b, 's, 'name, 'res: 's + 'name + 'b>(
                     &'b self,
-                    resty: Self::Ty<'c>,
-                    ptr2: <Self::ValKind<'_, '_> as ValueKind>::Val<'d, Normal>,
-                    args: impl Iterator<Item = <Self::ValKind<'h, 'i> as ValueKind>::Val<'e, Normal>>,
-                    name: &'f CStr,
-                ) -> <Self::ValKind<'_, '_> as ValueKind>::Val<'g, Normal>
+                    s: &'s CStr,
+                    name: &'name CStr,
+                ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal> {
+                    use llvm_sys::core::*;
+                    assert!(
+                        unsafe { !LLVMGetInsertBlock(self.ptr()).is_null() },
+                        "builder is not positioned"
+                    );
+                    // With opaque pointers the global itself already is the
+                    // pointer to element 0, which is all `LLVMBuildGlobalStringPtr`
+                    // used to add.
+                    let ptr = unsafe { LLVMBuildGlobalString(self.ptr(), s.as_ptr(), name.as_ptr()) };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn alloca_aligned<'b, 'name, 'res: 'name + 'b>(
+                    &'b self,
+                    ty: Self::Ty<'a>,
+                    align: u32,
+                    name: &'name CStr,
+                ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal> {
+                    use llvm_sys::core::*;
+                    assert!(align.is_power_of_two(), "alignment {align} is not a power of two");
+                    let ptr = unsafe {
+                        check_insertion_point(self.ptr());
+                        let a = LLVMBuildAlloca(self.ptr(), ty.ptr(), name.as_ptr());
+                        LLVMSetAlignment(a, align);
+                        a
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn zext_nneg<'b, 'val, 'name, 'res: 'val + 'name + 'b>(
+                    &'b self,
+                    val: <Self::ValKind<'a, 'a> as ValueKind>::Val<'val, Normal>,
+                    ty: Self::Ty<'a>,
+                    name: &'name CStr,
+                ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
                 where
-                    Self: 'h + 'i{
-                        let ptr = self.ptr();
-                        let resty = resty.ptr();
-                        let r#fn = ptr2.ptr();
-                        let mut args = args.map(|a| a.ptr()).collect::<Vec<_>>();
-                        let res = unsafe {
-                            llvm_sys::core::LLVMBuildGEP2(
-                                ptr,
-                                resty,
-                                r#fn,
-                                args.as_mut_ptr(),
-                                args.len().try_into().unwrap(),
+                    'a: 'val + 'name,
+                {
+                    since!(18 $l {{
+                        let ptr = unsafe {
+                            check_insertion_point(self.ptr());
+                            llvm_sys::core::LLVMBuildZExt(self.ptr(), val.ptr(), ty.ptr(), name.as_ptr())
+                        };
+                        unsafe { llvm_sys::core::LLVMSetNNeg(ptr, 1) };
+                        unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                    }} else {{
+                        let _ = (val, ty, name);
+                        panic!("`zext nneg` requires LLVM 18 or newer")
+                    }})
+                }
+                fn or_disjoint<'b, 'lhs, 'rhs, 'name, 'res: 'lhs + 'rhs + 'name + 'b>(
+                    &'b self,
+                    lhs: <Self::ValKind<'a, 'a> as ValueKind>::Val<'lhs, Normal>,
+                    rhs: <Self::ValKind<'a, 'a> as ValueKind>::Val<'rhs, Normal>,
+                    name: &'name CStr,
+                ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
+                where
+                    'a: 'lhs + 'rhs + 'name,
+                {
+                    since!(18 $l {{
+                        let ptr = unsafe {
+                            check_insertion_point(self.ptr());
+                            llvm_sys::core::LLVMBuildOr(self.ptr(), lhs.ptr(), rhs.ptr(), name.as_ptr())
+                        };
+                        unsafe { llvm_sys::core::LLVMSetIsDisjoint(ptr, 1) };
+                        unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                    }} else {{
+                        let _ = (lhs, rhs, name);
+                        panic!("`or disjoint` requires LLVM 18 or newer")
+                    }})
+                }
+                fn gc_statepoint<'b, 'fun, 'arg, 'live, 'name, 'res: 'fun + 'arg + 'live + 'name + 'b>(
+                    &'b self,
+                    id: u64,
+                    num_patch_bytes: u32,
+                    fn_ty: Self::Ty<'a>,
+                    r#fn: <Self::ValKind<'a, 'a> as ValueKind>::Val<'fun, Normal>,
+                    args: impl Iterator<Item = <Self::ValKind<'a, 'a> as ValueKind>::Val<'arg, Normal>>,
+                    gc_live: impl Iterator<Item = <Self::ValKind<'a, 'a> as ValueKind>::Val<'live, Normal>>,
+                    name: &'name CStr,
+                ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
+                where
+                    'a: 'fun + 'arg + 'live + 'name,
+                {
+                    since!(18 $l {{
+                        use llvm_sys::core::*;
+                        let b = self.ptr();
+                        let call = unsafe {
+                            let block = LLVMGetInsertBlock(b);
+                            assert!(!block.is_null(), "builder is not positioned");
+                            check_insertion_point(b);
+                            let ctx = LLVMGetTypeContext(fn_ty.ptr());
+                            let i32_ty = LLVMInt32TypeInContext(ctx);
+                            let args = args.map(|a| a.ptr()).collect::<smallvec::SmallVec<[_; 8]>>();
+                            let mut sp_args = vec![
+                                LLVMConstInt(LLVMInt64TypeInContext(ctx), id, 0),
+                                LLVMConstInt(i32_ty, num_patch_bytes.into(), 0),
+                                r#fn.ptr(),
+                                LLVMConstInt(i32_ty, args.len() as u64, 0),
+                                LLVMConstInt(i32_ty, 0, 0),
+                            ];
+                            sp_args.extend(args);
+                            // No transition or deopt arguments.
+                            sp_args.extend([LLVMConstInt(i32_ty, 0, 0), LLVMConstInt(i32_ty, 0, 0)]);
+                            let mut live = gc_live.map(|v| v.ptr()).collect::<smallvec::SmallVec<[_; 8]>>();
+                            let tag = c"gc-live";
+                            let mut bundle = LLVMCreateOperandBundle(
+                                tag.as_ptr(),
+                                tag.count_bytes(),
+                                live.as_mut_ptr(),
+                                live.len().try_into().unwrap(),
+                            );
+                            let r#mod = LLVMGetGlobalParent(LLVMGetBasicBlockParent(block));
+                            let statepoint = c"llvm.experimental.gc.statepoint";
+                            let intrinsic = LLVMLookupIntrinsicID(statepoint.as_ptr(), statepoint.count_bytes());
+                            let decl = LLVMGetIntrinsicDeclaration(r#mod, intrinsic, &mut LLVMTypeOf(r#fn.ptr()), 1);
+                            let call = LLVMBuildCallWithOperandBundles(
+                                b,
+                                LLVMGlobalGetValueType(decl),
+                                decl,
+                                sp_args.as_mut_ptr(),
+                                sp_args.len().try_into().unwrap(),
+                                &mut bundle,
+                                1,
                                 name.as_ptr(),
-                            )
+                            );
+                            LLVMDisposeOperandBundle(bundle);
+                            // The target (argument 2) must carry `elementtype(fn_ty)`.
+                            let kind = c"elementtype";
+                            let kind = LLVMGetEnumAttributeKindForName(kind.as_ptr(), kind.count_bytes());
+                            LLVMAddCallSiteAttribute(call, 3, LLVMCreateTypeAttribute(ctx, kind, fn_ty.ptr()));
+                            call
                         };
-                        unsafe { crate::LLHandle::leaked(res, Normal) }
+                        unsafe { crate::LLHandle::leaked(call, Normal) }
+                    }} else {{
+                        let _ = (id, num_patch_bytes, fn_ty, r#fn, args, gc_live, name);
+                        panic!("`gc.statepoint` requires LLVM 18 or newer")
+                    }})
+                }
+                fn gc_result<'b, 'tok, 'name, 'res: 'tok + 'name + 'b>(
+                    &'b self,
+                    token: <Self::ValKind<'a, 'a> as ValueKind>::Val<'tok, Normal>,
+                    ty: Self::Ty<'a>,
+                    name: &'name CStr,
+                ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
+                where
+                    'a: 'tok + 'name,
+                {
+                    let ptr = unsafe {
+                        let ptr = call_intrinsic(
+                            self.ptr(),
+                            c"llvm.experimental.gc.result",
+                            &mut [ty.ptr()],
+                            &mut [token.ptr()],
+                        );
+                        llvm_sys::core::LLVMSetValueName2(ptr, name.as_ptr(), name.count_bytes());
+                        ptr
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn gc_relocate<'b, 'tok, 'name, 'res: 'tok + 'name + 'b>(
+                    &'b self,
+                    token: <Self::ValKind<'a, 'a> as ValueKind>::Val<'tok, Normal>,
+                    base: u32,
+                    derived: u32,
+                    ty: Self::Ty<'a>,
+                    name: &'name CStr,
+                ) -> <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>
+                where
+                    'a: 'tok + 'name,
+                {
+                    use llvm_sys::core::*;
+                    let ptr = unsafe {
+                        let i32_ty = LLVMInt32TypeInContext(LLVMGetTypeContext(ty.ptr()));
+                        let ptr = call_intrinsic(
+                            self.ptr(),
+                            c"llvm.experimental.gc.relocate",
+                            &mut [ty.ptr()],
+                            &mut [token.ptr(), LLVMConstInt(i32_ty, base.into(), 0), LLVMConstInt(i32_ty, derived.into(), 0)],
+                        );
+                        LLVMSetValueName2(ptr, name.as_ptr(), name.count_bytes());
+                        ptr
+                    };
+                    unsafe { crate::LLHandle::leaked(ptr, Normal) }
+                }
+                fn build_lookup<'b, 'key, 'name, 'res: 'key + 'name + 'b>(
+                    &'b self,
+                    key: <Self::ValKind<'a, 'a> as ValueKind>::Val<'key, Normal>,
+                    keys: &[u64],
+                    name: &'name CStr,
+                ) -> (
+                    <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>,
+                    <Self::ValKind<'a, 'a> as ValueKind>::Val<'res, Normal>,
+                )
+                where
+                    'a: 'key + 'name,
+                {
+                    use llvm_sys::core::*;
+                    let b = self.ptr();
+                    let key = key.ptr();
+                    unsafe {
+                        let key_ty = LLVMTypeOf(key);
+                        assert!(
+                            LLVMGetTypeKind(key_ty) == llvm_sys::LLVMTypeKind::LLVMIntegerTypeKind,
+                            "lookup key is not an integer"
+                        );
+                        assert!(!keys.is_empty(), "lookup needs at least one key");
+                        // Keys, the modulus and the `slot + 1` fillers must all fit the key type.
+                        let max = u64::MAX >> (64 - LLVMGetIntTypeWidth(key_ty).min(64));
+                        assert!(keys.iter().all(|k| *k <= max), "lookup key does not fit the key type");
+                        let m = perfect_modulus(keys, crate::MAX_LOOKUP_SLOTS.min(max))
+                            .expect("no perfect modulus for the lookup keys");
+                        let slots: usize = m.try_into().unwrap();
+                        let block = LLVMGetInsertBlock(b);
+                        assert!(!block.is_null(), "builder is not positioned");
+                        check_insertion_point(b);
+                        let r#mod = LLVMGetGlobalParent(LLVMGetBasicBlockParent(block));
+                        let i32_ty = LLVMInt32TypeInContext(LLVMGetTypeContext(key_ty));
+                        // Empty slots hold `slot + 1`, which never maps back to `slot`.
+                        let mut slot_keys = (0..m)
+                            .map(|s| LLVMConstInt(key_ty, s + 1, 0))
+                            .collect::<Vec<_>>();
+                        let mut slot_index = vec![LLVMConstInt(i32_ty, 0, 0); slots];
+                        for (i, k) in keys.iter().enumerate() {
+                            let s = (k % m) as usize;
+                            slot_keys[s] = LLVMConstInt(key_ty, *k, 0);
+                            slot_index[s] = LLVMConstInt(i32_ty, i as u64, 0);
+                        }
+                        let table = |ty, vals: &mut Vec<_>| {
+                            let arr_ty = array_type(ty, m);
+                            let global = LLVMAddGlobal(r#mod, arr_ty, c"".as_ptr());
+                            LLVMSetInitializer(global, const_array(ty, vals));
+                            LLVMSetGlobalConstant(global, 1);
+                            LLVMSetLinkage(global, llvm_sys::LLVMLinkage::LLVMPrivateLinkage);
+                            (arr_ty, global)
+                        };
+                        let (keys_ty, keys_global) = table(key_ty, &mut slot_keys);
+                        let (index_ty, index_global) = table(i32_ty, &mut slot_index);
+                        let slot = LLVMBuildURem(b, key, LLVMConstInt(key_ty, m, 0), c"".as_ptr());
+                        let load = |arr_ty, global, ty| {
+                            let mut idx = [LLVMConstInt(key_ty, 0, 0), slot];
+                            let p = LLVMBuildInBoundsGEP2(b, arr_ty, global, idx.as_mut_ptr(), 2, c"".as_ptr());
+                            LLVMBuildLoad2(b, ty, p, c"".as_ptr())
+                        };
+                        let found = load(keys_ty, keys_global, key_ty);
+                        let hit = LLVMBuildICmp(b, llvm_sys::LLVMIntPredicate::LLVMIntEQ, found, key, c"".as_ptr());
+                        let index = load(index_ty, index_global, i32_ty);
+                        LLVMSetValueName2(index, name.as_ptr(), name.count_bytes());
+                        (
+                            crate::LLHandle::leaked(hit, Normal),
+                            crate::LLHandle::leaked(index, Normal),
+                        )
                     }
+                }
                 default_insts!('a @ llvm_sys);
             }
         };
     };
 }
+#[allow(unused_macros)]
+macro_rules! version {
+    ($l:ident {}) => {
+        /// The LLVM version selected by the enabled `llvm-sys-*` feature, as
+        /// `(major, minor, patch)`.
+        ///
+        /// Feature names only encode the major and minor version (`llvm-sys-180`
+        /// is LLVM 18.0), so the patch component is always `0`; use
+        /// [`runtime_version`] for the exact version of the linked library.
+        pub const LLVM_VERSION: (u32, u32, u32) = {
+            // `llvm_sys_180` -> 180 -> (18, 0, 0)
+            let name = stringify!($l).as_bytes();
+            let mut n = 0;
+            let mut i = 0;
+            while i < name.len() {
+                if name[i].is_ascii_digit() {
+                    n = n * 10 + (name[i] - b'0') as u32;
+                }
+                i += 1;
+            }
+            (n / 10, n % 10, 0)
+        };
+        /// Queries the version of the LLVM library linked at runtime, as
+        /// `(major, minor, patch)`.
+        ///
+        /// LLVM 16 does not expose this through the C API, so there it returns
+        /// [`LLVM_VERSION`].
+        pub fn runtime_version() -> (u32, u32, u32) {
+            since!(17 $l {{
+                let (mut major, mut minor, mut patch) = (0, 0, 0);
+                unsafe { $l::core::LLVMGetVersion(&mut major, &mut minor, &mut patch) };
+                (major, minor, patch)
+            }} else {
+                LLVM_VERSION
+            })
+        }
+    };
+}
+
+#[allow(unused_macros)]
+macro_rules! target {
+    ($l:ident {}) => {
+        /// Initializes the host's target, along with its assembly printer and
+        /// parser, so that it can be found by [`Target::from_triple`] and emit code.
+        ///
+        /// Safe to call more than once. Fails if LLVM was built without the host
+        /// target.
+        pub fn init_native_target() -> Result<(), String> {
+            use $l::target::*;
+            let failed = unsafe {
+                LLVM_InitializeNativeTarget() != 0
+                    || LLVM_InitializeNativeAsmPrinter() != 0
+                    || LLVM_InitializeNativeAsmParser() != 0
+            };
+            if failed {
+                return Err("the native target is not available".to_owned());
+            }
+            Ok(())
+        }
+        /// Returns the triple of the host LLVM generates code for by default.
+        pub fn default_target_triple() -> std::ffi::CString {
+            unsafe {
+                let msg = $l::target_machine::LLVMGetDefaultTargetTriple();
+                let s = CStr::from_ptr(msg).to_owned();
+                $l::core::LLVMDisposeMessage(msg);
+                s
+            }
+        }
+    };
+}
 
 llvm_codegen_utils_version_macros::vers!({} impls);
+llvm_codegen_utils_version_macros::vers!({} version);
+llvm_codegen_utils_version_macros::vers!({} target);
@@ -0,0 +1,80 @@
+//! Reference counting of `LLHandle`, independent of any LLVM version.
+
+use px_llvm_codegen_utils_core::LLHandle;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn count_drop(_: *mut u8, drops: &'static AtomicUsize) {
+    drops.fetch_add(1, Ordering::SeqCst);
+}
+
+#[test]
+fn dropper_runs_once_under_clone_churn() {
+    static DROPS: [AtomicUsize; 8] = [const { AtomicUsize::new(0) }; 8];
+    let threads = (0..DROPS.len())
+        .map(|t| {
+            std::thread::spawn(move || {
+                let mut obj = 0u8;
+                let handle = unsafe { LLHandle::<'_, _, u8>::from_raw_parts(&mut obj, count_drop, &DROPS[t]) };
+                let weak = handle.downgrade();
+                let mut clones = vec![];
+                for i in 0..10_000 {
+                    clones.push(handle.clone());
+                    if i % 3 == 0 {
+                        clones.swap_remove(i % clones.len());
+                    }
+                    if i % 7 == 0 {
+                        clones.push(weak.upgrade().unwrap());
+                    }
+                }
+                drop(handle);
+                assert_eq!(DROPS[t].load(Ordering::SeqCst), 0);
+                while clones.pop().is_some() {}
+                assert!(weak.upgrade().is_none());
+            })
+        })
+        .collect::<Vec<_>>();
+    for t in threads {
+        t.join().unwrap();
+    }
+    for drops in &DROPS {
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+    }
+}
+
+#[test]
+fn weak_handles_do_not_keep_the_resource_alive() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    let mut obj = 0u8;
+    let handle = unsafe { LLHandle::<'_, _, u8>::from_raw_parts(&mut obj, count_drop, &DROPS) };
+    let weak = handle.downgrade();
+    let upgraded = weak.upgrade().unwrap();
+    assert!(upgraded == handle);
+    drop(upgraded);
+    drop(handle);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+    assert!(weak.upgrade().is_none());
+    assert!(weak.clone().upgrade().is_none());
+}
+
+#[test]
+fn reused_addresses_are_not_conflated() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    let mut obj = 0u8;
+    let first = unsafe { LLHandle::<'_, _, u8>::from_raw_parts(&mut obj, count_drop, &DROPS) };
+    let weak = first.downgrade();
+    drop(first);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+
+    // A new resource at the freed address starts its own family.
+    let second = unsafe { LLHandle::<'_, _, u8>::from_raw_parts(&mut obj, count_drop, &DROPS) };
+    assert!(weak.upgrade().is_none());
+    // Two live handles to the same address count separately.
+    let third = unsafe { LLHandle::<'_, _, u8>::from_raw_parts(&mut obj, count_drop, &DROPS) };
+    let second_clone = second.clone();
+    drop(third);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 2);
+    drop(second);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 2);
+    drop(second_clone);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,327 @@
+use super::*;
+
+#[test]
+fn erase_a_dead_add() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"f", i32.clone(), [i32.clone(), i32]);
+    let dead = f.Add(f.param(0), f.param(1), c"dead");
+    let live = f.Mul(f.param(0), f.param(1), c"live");
+    f.Ret(live);
+    let entry = f.entry_block();
+    assert_eq!(entry.instructions().count(), 3);
+
+    unsafe { dead.erase_from_parent() };
+    assert_eq!(entry.instructions().count(), 2);
+    assert!(!format!("{module:?}").contains("%dead"));
+    verify(&module).unwrap();
+}
+
+#[test]
+#[should_panic = "instruction still has uses"]
+fn erase_refuses_a_used_instruction() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"f", i32.clone(), [i32]);
+    let sum = f.Add(f.param(0), f.param(0), c"sum");
+    f.Ret(sum.clone());
+    unsafe { sum.erase_from_parent() };
+}
+
+fn block_names(f: &Function) -> Vec<String> {
+    f.basic_blocks().map(|bb| bb.as_value().name().unwrap()).collect()
+}
+
+#[test]
+fn insert_move_and_delete_blocks() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = define(&ctx, &module, c"f", void(&ctx), []);
+    let exit = f.append_block(c"exit");
+    f.Br(exit.clone());
+    f.position_at_end(exit.clone());
+    f.ret_void();
+
+    let middle = Block::insert_before(&exit, c"middle");
+    assert_eq!(block_names(&f.func()), ["entry", "middle", "exit"]);
+    middle.move_after(&exit);
+    assert_eq!(block_names(&f.func()), ["entry", "exit", "middle"]);
+    middle.move_before(&f.entry_block());
+    assert_eq!(block_names(&f.func()), ["middle", "entry", "exit"]);
+    middle.move_after(&f.entry_block());
+
+    unsafe { middle.delete() };
+    assert_eq!(block_names(&f.func()), ["entry", "exit"]);
+    verify(&module).unwrap();
+}
+
+#[test]
+#[should_panic = "basic block still has predecessors"]
+fn delete_refuses_a_branch_target() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = define(&ctx, &module, c"f", void(&ctx), []);
+    let exit = f.append_block(c"exit");
+    f.Br(exit.clone());
+    unsafe { exit.delete() };
+}
+
+#[test]
+fn indirect_branch_to_a_block_address() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = define(&ctx, &module, c"f", void(&ctx), []);
+    let target = f.append_block(c"target");
+    let addr = Kind::block_address(f.func(), target.clone());
+    f.indirect_br(addr, [target.clone()].into_iter());
+    f.position_at_end(target);
+    f.ret_void();
+    verify(&module).unwrap();
+    assert!(format!("{module:?}").contains("indirectbr ptr blockaddress(@f, %target), [label %target]"));
+}
+
+#[test]
+#[cfg(feature = "safe-checks")]
+fn emitting_after_ret_panics_naming_the_block() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let (i8, i32) = (int(&ctx, 8), int(&ctx, 32));
+    let ptr = Type::ptr_ty(ctx.clone(), 0);
+    let f = define(&ctx, &module, c"f", i32.clone(), [i32.clone(), ptr]);
+    let (x, p) = (f.param(0), f.param(1));
+    let ret = f.Ret(x.clone());
+
+    let callee = unsafe { Val::from_raw_leaked(f.func().ptr()) };
+    let fn_ty = i32.clone().fun_ty([i32.clone(), Type::ptr_ty(ctx.clone(), 0)].into_iter());
+    let rejected = |what: &str, emit: &dyn Fn()| {
+        let err = catch_unwind(AssertUnwindSafe(emit)).expect_err(what);
+        let msg = err.downcast_ref::<String>().unwrap();
+        assert_eq!(msg, "instruction emitted after the terminator of block \"entry\"", "{what}");
+    };
+    rejected("add", &|| drop(f.Add(x.clone(), x.clone(), c"")));
+    rejected("store", &|| drop(f.Store(x.clone(), p.clone())));
+    rejected("call", &|| drop(f.call2(fn_ty.clone(), callee.clone(), x.clone(), p.clone(), c"")));
+    rejected("memset", &|| drop(f.mem_set(p.clone(), Kind::const_int(i8.clone(), 0, false), x.clone(), 0)));
+    assert_eq!(f.entry_block().instructions().count(), 1);
+
+    // Inserting ahead of the terminator is fine.
+    f.position_before(ret);
+    f.Store(x, p);
+    verify(&module).unwrap();
+}
+
+#[test]
+fn finish_clears_the_insertion_point() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = define(&ctx, &module, c"f", void(&ctx), []);
+    assert!(f.insert_block() == Some(f.entry_block()));
+    f.ret_void();
+    assert_eq!(f.finish(), Finished);
+    assert!(f.insert_block().is_none());
+
+    f.position_at_end(f.entry_block());
+    assert!(f.insert_block() == Some(f.entry_block()));
+    verify(&module).unwrap();
+}
+
+#[test]
+#[cfg(feature = "safe-checks")]
+#[should_panic = "builder is not positioned"]
+fn emitting_after_finish_panics() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"f", i32.clone(), [i32]);
+    f.Ret(f.param(0));
+    f.finish();
+    f.Add(f.param(0), f.param(0), c"late");
+}
+
+#[test]
+fn walk_the_blocks_of_a_function() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = define(&ctx, &module, c"f", void(&ctx), []);
+    let exit = f.append_block(c"exit");
+    assert!(exit.terminator().is_none());
+    let br = f.Br(exit.clone());
+    f.position_at_end(exit.clone());
+    let ret = f.ret_void();
+
+    let blocks = f.func().basic_blocks().collect::<Vec<_>>();
+    assert_eq!(blocks.len(), 2);
+    assert!(blocks[0] == f.entry_block() && blocks[1] == exit);
+    assert_eq!(blocks[0].terminator(), Some(br));
+    assert_eq!(blocks[1].terminator(), Some(ret));
+    let decl = Kind::declare(module.clone(), c"g", void(&ctx).fun_ty([].into_iter()));
+    assert_eq!(decl.basic_blocks().count(), 0);
+}
+
+#[test]
+fn instructions_end_at_the_terminator() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"f", i32.clone(), [i32]);
+    let a = f.Add(f.param(0), f.param(0), c"a");
+    let b = f.Add(a.clone(), f.param(0), c"b");
+    let c = f.Add(b.clone(), a.clone(), c"c");
+    let ret = f.Ret(c.clone());
+    let exit = f.append_block(c"unreached");
+    f.position_at_end(exit.clone());
+    f.Ret(f.param(0));
+
+    let insts = f.entry_block().instructions().collect::<Vec<_>>();
+    assert_eq!(insts, [a, b, c, ret]);
+    assert_eq!(insts[3].opcode(), Some(Opcode::Ret));
+    assert_eq!(exit.instructions().count(), 1);
+    assert_eq!(f.append_block(c"empty").instructions().count(), 0);
+}
+
+#[test]
+fn insert_an_add_before_the_ret() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"f", i32.clone(), [i32]);
+    let ret = f.Ret(f.param(0));
+    f.clear_insertion_position();
+    assert!(f.insert_block().is_none());
+
+    f.position_before(ret.clone());
+    assert!(f.insert_block() == Some(f.entry_block()));
+    let sum = f.Add(f.param(0), f.param(0), c"sum");
+    assert_eq!(f.entry_block().instructions().collect::<Vec<_>>(), [sum, ret]);
+    verify(&module).unwrap();
+}
+
+#[test]
+fn append_and_enter_moves_the_builder() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"chain", i32.clone(), [i32.clone()]);
+    let first = f.append_and_enter(f.func().clone(), c"first");
+    assert!(f.insert_block() == Some(first.clone()));
+    let doubled = f.Add(f.param(0), f.param(0), c"doubled");
+    let second = f.append_and_enter(f.func().clone(), c"second");
+    assert!(f.insert_block() == Some(second.clone()));
+    f.Ret(f.Add(doubled.clone(), Kind::const_i32(ctx.clone(), 1), c"inc"));
+
+    f.position_at_end(f.entry_block());
+    f.Br(first.clone());
+    f.position_at_end(first);
+    f.Br(second);
+    verify(&module).unwrap();
+
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    let chain = unsafe { jit.lookup_fn::<extern "C" fn(i32) -> i32>(c"chain") }.unwrap();
+    assert_eq!(chain(20), 41);
+}
+
+#[test]
+fn blocks_round_trip_through_values() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = define(&ctx, &module, c"f", void(&ctx), []);
+    let exit = f.append_block(c"exit");
+    let value = exit.as_value();
+    assert_eq!(value.ptr(), unsafe { llvm_sys::core::LLVMBasicBlockAsValue(exit.ptr()) });
+    assert_eq!(value.type_of().kind(), TypeKind::Label);
+    let back = value.as_basic_block().unwrap();
+    assert!(back == exit);
+    assert!(Kind::const_i32(ctx.clone(), 0).as_basic_block().is_none());
+}
+
+#[test]
+fn jump_table_of_block_addresses() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let (i32, i64, ptr) = (int(&ctx, 32), int(&ctx, 64), Type::ptr_ty(ctx.clone(), 0));
+    let f = define(&ctx, &module, c"pick", i32.clone(), [i64.clone()]);
+    let (one, two) = (f.append_block(c"one"), f.append_block(c"two"));
+    let targets = [one.clone(), two.clone()].map(|bb| Kind::block_address(f.func(), bb));
+    let table = Kind::const_array(ptr.clone(), targets.into_iter());
+    let table_ty = table.type_of();
+    let table = Kind::add_constant_global(module.clone(), c"table", table);
+
+    let zero = Kind::const_int(i64, 0, false);
+    let slot = f.gep2_in_bounds(table_ty, table, [zero, f.param(0)].into_iter(), c"slot");
+    let target = f.Load2(ptr, slot, c"target");
+    f.indirect_br(target, [one.clone(), two.clone()].into_iter());
+    f.position_at_end(one);
+    f.Ret(Kind::const_i32(ctx.clone(), 1));
+    f.position_at_end(two);
+    f.Ret(Kind::const_i32(ctx.clone(), 2));
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("[ptr blockaddress(@pick, %one), ptr blockaddress(@pick, %two)]"), "{ir}");
+
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    let pick = unsafe { jit.lookup_fn::<extern "C" fn(i64) -> i32>(c"pick") }.unwrap();
+    assert_eq!((pick(0), pick(1)), (1, 2));
+}
+
+#[test]
+fn detached_blocks_are_appended_later() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = define(&ctx, &module, c"f", void(&ctx), []);
+    // Build the exit first, before it belongs to any function.
+    let exit = Block::create_in_context(ctx.clone(), c"exit");
+    f.position_at_end(exit.clone());
+    f.ret_void();
+    let body = f.append_block(c"body");
+    f.position_at_end(body.clone());
+    f.Br(exit.clone());
+    f.position_at_end(f.entry_block());
+    f.Br(body);
+    assert_eq!(block_names(&f.func()), ["entry", "body"]);
+
+    Block::append_existing(f.func(), &exit);
+    assert_eq!(block_names(&f.func()), ["entry", "body", "exit"]);
+    verify(&module).unwrap();
+}
+
+#[test]
+fn two_way_computed_goto() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"goto", i32.clone(), [i32]);
+    let (even, odd) = (f.append_block(c"even"), f.append_block(c"odd"));
+    let bit = f.And(f.param(0), Kind::const_i32(ctx.clone(), 1), c"bit");
+    let is_odd = f.ICmp(ICmp::Eq, bit, Kind::const_i32(ctx.clone(), 1), c"is_odd");
+    // The crate has no `select` helper, so pick the target directly.
+    let target = unsafe {
+        Val::from_raw_leaked(llvm_sys::core::LLVMBuildSelect(
+            f.builder().ptr(),
+            is_odd.ptr(),
+            Kind::block_address(f.func(), odd.clone()).ptr(),
+            Kind::block_address(f.func(), even.clone()).ptr(),
+            c"target".as_ptr(),
+        ))
+    };
+    let br = f.indirect_br(target, [even.clone(), odd.clone()].into_iter());
+    assert_eq!(br.opcode(), Some(Opcode::IndirectBr));
+    f.position_at_end(even);
+    f.Ret(Kind::const_i32(ctx.clone(), 0));
+    f.position_at_end(odd);
+    f.Ret(Kind::const_i32(ctx.clone(), 1));
+    assert_eq!(f.func().verify(), Ok(()));
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("indirectbr ptr %target, [label %even, label %odd]"), "{ir}");
+
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    let goto = unsafe { jit.lookup_fn::<extern "C" fn(i32) -> i32>(c"goto") }.unwrap();
+    assert_eq!((goto(4), goto(7)), (0, 1));
+}
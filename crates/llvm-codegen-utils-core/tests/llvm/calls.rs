@@ -0,0 +1,148 @@
+use super::*;
+
+/// Defines `i64 countdown(i64 n)`, returning 0 once `n` is 0 and otherwise
+/// calling itself with `n - 1`, and returns the recursive call.
+fn countdown<'a>(ctx: &Context<'a>, module: &Module<'a>) -> Val<'a> {
+    let i64 = int(ctx, 64);
+    let ty = i64.clone().fun_ty([i64.clone()].into_iter());
+    let f = define(ctx, module, c"countdown", i64.clone(), [i64.clone()]);
+    let (done, recurse) = (f.append_block(c"done"), f.append_block(c"recurse"));
+    let zero = Kind::const_int(i64.clone(), 0, false);
+    let is_zero = f.ICmp(ICmp::Eq, f.param(0), zero.clone(), c"is_zero");
+    f.CondBr(is_zero, done.clone(), recurse.clone());
+    f.position_at_end(done);
+    f.Ret(zero);
+    f.position_at_end(recurse);
+    let next = f.Sub(f.param(0), Kind::const_int(i64, 1, false), c"next");
+    let this = unsafe { Val::from_raw_leaked(f.func().ptr()) };
+    let call = f.call1(ty, this, next, c"rest");
+    f.Ret(call.clone());
+    call
+}
+
+#[test]
+#[cfg(any(
+    feature = "llvm-sys-180",
+    feature = "llvm-sys-190",
+    feature = "llvm-sys-200",
+    feature = "llvm-sys-210",
+))]
+fn self_recursive_musttail_call_verifies() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let call = countdown(&ctx, &module);
+    call.set_tail_call_kind(TailCallKind::MustTail);
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("%rest = musttail call i64 @countdown(i64 %next)"), "{ir}");
+}
+
+#[test]
+fn tail_marker_round_trips_through_the_ir() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let call = countdown(&ctx, &module);
+    call.set_tail_call_kind(TailCallKind::Tail);
+    assert_eq!(unsafe { llvm_sys::core::LLVMIsTailCall(call.ptr()) }, 1);
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("%rest = tail call i64 @countdown(i64 %next)"), "{ir}");
+}
+
+#[test]
+#[cfg(not(any(
+    feature = "llvm-sys-180",
+    feature = "llvm-sys-190",
+    feature = "llvm-sys-200",
+    feature = "llvm-sys-210",
+)))]
+#[should_panic = "MustTail requires LLVM 18 or newer"]
+fn musttail_needs_llvm_18() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    countdown(&ctx, &module).set_tail_call_kind(TailCallKind::MustTail);
+}
+
+#[test]
+fn call_an_inline_asm_nop() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let ty = void(&ctx).fun_ty([].into_iter());
+    let f = define(&ctx, &module, c"pause", void(&ctx), []);
+    let nop = Kind::inline_asm(ty.clone(), c"nop", c"", true, false, AsmDialect::Att);
+    assert_eq!(nop.value_kind(), ValueClass::InlineAsm);
+    f.call0(ty.clone(), nop, c"");
+    let intel = Kind::inline_asm(ty.clone(), c"nop", c"", false, true, AsmDialect::Intel);
+    f.call0(ty, intel, c"");
+    f.ret_void();
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains(r#"call void asm sideeffect "nop", ""()"#), "{ir}");
+    assert!(ir.contains(r#"call void asm alignstack inteldialect "nop", ""()"#), "{ir}");
+}
+
+#[test]
+fn read_one_variadic_argument() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let (i32, ptr) = (int(&ctx, 32), Type::ptr_ty(ctx.clone(), 0));
+    let ty = i32.clone().var_arg_fun_ty([i32.clone()].into_iter());
+    let f = FunctionBuilder::new(IrBuilder::new_in_ctx(ctx.clone()), module.clone(), c"first_vararg", ty);
+    // Large enough for the x86-64 System V `va_list`, the biggest in common use.
+    let list_ty = Type::struct_ty(ctx.clone(), [i32.clone(), i32.clone(), ptr.clone(), ptr].into_iter(), false);
+    let list = f.Alloca(list_ty, c"list");
+    f.va_start(list.clone());
+    let first = f.VAArg(list.clone(), i32, c"first");
+    f.va_end(list);
+    f.Ret(first);
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("define i32 @first_vararg(i32 %0, ...)"), "{ir}");
+    assert!(ir.contains("call void @llvm.va_start"), "{ir}");
+    assert!(ir.contains("%first = va_arg ptr %list, i32"), "{ir}");
+    assert!(ir.contains("call void @llvm.va_end"), "{ir}");
+}
+
+#[test]
+fn call_struct_extracts_every_field() {
+    use llvm_sys::core::{LLVMBuildInsertValue, LLVMGetUndef};
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let pair_ty = Type::struct_ty(ctx.clone(), [i32.clone(), i32.clone()].into_iter(), false);
+    let pair = define(&ctx, &module, c"pair", pair_ty.clone(), [i32.clone()]);
+    let x = pair.param(0);
+    let doubled = pair.Add(x.clone(), x.clone(), c"doubled");
+    // The crate has no `insertvalue` helper, so assemble `{ x, 2 * x }` directly.
+    let res = unsafe {
+        let b = pair.builder().ptr();
+        let first = LLVMBuildInsertValue(b, LLVMGetUndef(pair_ty.ptr()), x.ptr(), 0, c"".as_ptr());
+        Val::from_raw_leaked(LLVMBuildInsertValue(b, first, doubled.ptr(), 1, c"res".as_ptr()))
+    };
+    pair.Ret(res);
+    let callee = unsafe { Val::from_raw_leaked(pair.func().ptr()) };
+
+    let f = define(&ctx, &module, c"sum_pair", i32.clone(), [i32.clone()]);
+    let fields = f.call_struct(pair_ty.fun_ty([i32].into_iter()), callee, [f.param(0)].into_iter(), c"call");
+    assert_eq!(fields.len(), 2);
+    assert!(fields.iter().all(|v| v.opcode() == Some(Opcode::ExtractValue)));
+    f.Ret(f.Add(fields[0].clone(), fields[1].clone(), c"sum"));
+    verify(&module).unwrap();
+
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    let sum_pair = unsafe { jit.lookup_fn::<extern "C" fn(i32) -> i32>(c"sum_pair") }.unwrap();
+    assert_eq!(sum_pair(5), 15);
+}
+
+#[test]
+#[should_panic = "call_struct requires a function returning a struct"]
+fn call_struct_needs_a_struct_return() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let callee = Kind::declare(module.clone(), c"scalar", i32.clone().fun_ty([].into_iter()));
+    let callee = unsafe { Val::from_raw_leaked(callee.ptr()) };
+    let f = define(&ctx, &module, c"f", void(&ctx), []);
+    f.call_struct(i32.fun_ty([].into_iter()), callee, [].into_iter(), c"");
+}
@@ -0,0 +1,340 @@
+use super::*;
+
+#[test]
+fn empty_arrays_keep_their_element_type() {
+    let ctx = Context::create_context();
+    let i32 = int(&ctx, 32);
+    let empty = Kind::const_array(i32.clone(), std::iter::empty());
+    let ty = empty.type_of();
+    assert_eq!(ty.kind(), TypeKind::Array);
+    assert_eq!(ty.element_type(), Some(i32));
+    assert_eq!(format!("{ty:?}"), "[0 x i32]");
+}
+
+#[test]
+fn increment_by_a_type_matched_constant() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i16 = int(&ctx, 16);
+    let f = define(&ctx, &module, c"inc", i16.clone(), [i16.clone()]);
+    let one = Kind::const_like(f.param(0), 1);
+    assert_eq!(one.type_of(), i16);
+    assert_eq!(one.const_int_value(), Some(1));
+    f.Ret(f.Add(f.param(0), one, c"next"));
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("%next = add i16 %0, 1"), "{ir}");
+}
+
+#[test]
+#[should_panic = "const_like requires an integer value"]
+fn const_like_needs_an_integer() {
+    let ctx = Context::create_context();
+    let null = Kind::const_zero(Type::ptr_ty(ctx.clone(), 0));
+    Kind::const_like(null, 1);
+}
+
+#[test]
+fn i128_from_a_decimal_string() {
+    let ctx = Context::create_context();
+    let (i64, i128) = (int(&ctx, 64), int(&ctx, 128));
+    let n = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210_u128;
+    let parsed = Kind::const_int_of_string(i128.clone(), c"1512366075204170947332355369683137040", 10);
+    assert_eq!(parsed, Kind::const_u128(i128.clone(), n));
+    assert_eq!(parsed, Kind::const_int_of_words(i128.clone(), &[n as u64, (n >> 64) as u64]));
+    let low = Kind::const_trunc(parsed, i64);
+    assert_eq!(low.const_int_value(), Some(0xfedc_ba98_7654_3210));
+
+    let hex = Kind::const_int_of_string(i128.clone(), c"000123456789abcdeffedcba9876543210", 16);
+    assert_eq!(hex, Kind::const_u128(i128.clone(), n));
+    let minus_one = Kind::const_int_of_string(i128.clone(), c"-1", 10);
+    assert_eq!(minus_one, Kind::const_u128(i128, u128::MAX));
+    let max = Kind::const_int_of_string(int(&ctx, 8), c"11111111", 2);
+    assert_eq!(max.const_int_value(), Some(255));
+}
+
+#[test]
+fn malformed_integer_strings_panic() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let ctx = Context::create_context();
+    let (i8, i128) = (int(&ctx, 8), int(&ctx, 128));
+    let rejected = |s: &CStr, ty: &Type, radix: u8, msg: &str| {
+        let err = catch_unwind(AssertUnwindSafe(|| Kind::const_int_of_string(ty.clone(), s, radix)))
+            .expect_err(msg);
+        let err = err.downcast_ref::<String>().unwrap();
+        assert_eq!(err, msg);
+    };
+    rejected(c"", &i128, 10, r#"empty integer literal """#);
+    rejected(c"-", &i128, 10, r#"empty integer literal "-""#);
+    rejected(c"12a", &i128, 10, r#"invalid digit 'a' in base-10 literal "12a""#);
+    rejected(c"8", &i128, 8, r#"invalid digit '8' in base-8 literal "8""#);
+    rejected(c"256", &i8, 10, r#"integer literal "256" does not fit in i8"#);
+    rejected(
+        c"340282366920938463463374607431768211456",
+        &i128,
+        10,
+        r#"integer literal "340282366920938463463374607431768211456" does not fit in i128"#,
+    );
+    rejected(c"1", &Type::ptr_ty(ctx.clone(), 0), 10, "const_int_of_string requires an integer type");
+    rejected(c"1", &i8, 3, "unsupported radix 3");
+}
+
+/// Creates the named struct type `%Point = type { i32, i32 }`.
+fn point<'a>(ctx: &Context<'a>) -> Type<'a> {
+    use llvm_sys::core::*;
+    let i32 = int(ctx, 32);
+    let mut fields = [i32.ptr(), i32.ptr()];
+    unsafe {
+        let ty = LLVMStructCreateNamed(ctx.ptr(), c"Point".as_ptr());
+        LLVMStructSetBody(ty, fields.as_mut_ptr(), 2, 0);
+        Type::from_raw_leaked(ty)
+    }
+}
+
+#[test]
+fn struct_constants_initialize_globals() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let fields = || [1, 2].map(|n| Kind::const_i32(ctx.clone(), n)).into_iter();
+    let pair = Kind::const_struct(ctx.clone(), fields(), false);
+    assert_eq!(format!("{:?}", pair.type_of()), "{ i32, i32 }");
+    let packed = Kind::const_struct(ctx.clone(), fields(), true);
+    assert_eq!(format!("{:?}", packed.type_of()), "<{ i32, i32 }>");
+    let point = point(&ctx);
+    let origin = Kind::const_named_struct(point.clone(), fields());
+    assert_eq!(origin.type_of(), point);
+
+    Kind::add_constant_global(module.clone(), c"pair", pair);
+    Kind::add_constant_global(module.clone(), c"origin", origin);
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("@pair = private unnamed_addr constant { i32, i32 } { i32 1, i32 2 }"), "{ir}");
+    assert!(ir.contains("@origin = private unnamed_addr constant %Point { i32 1, i32 2 }"), "{ir}");
+}
+
+#[test]
+#[should_panic = "struct constant has the wrong number of fields"]
+fn named_struct_constants_need_every_field() {
+    let ctx = Context::create_context();
+    Kind::const_named_struct(point(&ctx), [Kind::const_i32(ctx.clone(), 1)].into_iter());
+}
+
+#[test]
+fn pointer_to_the_second_field_of_a_global() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let fields = [1, 2].map(|n| Kind::const_i32(ctx.clone(), n));
+    let pair = Kind::const_struct(ctx.clone(), fields.into_iter(), false);
+    let pair_ty = pair.type_of();
+    let global = Kind::add_constant_global(module.clone(), c"pair", pair);
+    let indices = [Kind::const_i32(ctx.clone(), 0), Kind::const_i32(ctx.clone(), 1)];
+    let second = Kind::const_gep(pair_ty, global, indices.into_iter());
+    assert_eq!(second.value_kind(), ValueClass::ConstantExpr);
+    Kind::add_constant_global(module.clone(), c"second", second);
+
+    let float = unsafe { Type::from_raw_leaked(llvm_sys::core::LLVMFloatTypeInContext(ctx.ptr())) };
+    let one = Kind::const_bitcast(Kind::const_i32(ctx.clone(), 0x3f80_0000), float.clone());
+    assert_eq!(one.type_of(), float);
+    Kind::add_constant_global(module.clone(), c"one", one);
+
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    let gep = "getelementptr ({ i32, i32 }, ptr @pair, i32 0, i32 1)";
+    assert!(ir.contains(&format!("@second = private unnamed_addr constant ptr {gep}")), "{ir}");
+    assert!(ir.contains("@one = private unnamed_addr constant float 1.000000e+00"), "{ir}");
+}
+
+#[test]
+fn bool_and_fixed_width_shortcuts() {
+    let ctx = Context::create_context();
+    let yes = Kind::const_bool(ctx.clone(), true);
+    assert_eq!(yes.type_of(), Type::i1_ty(ctx.clone()));
+    assert_eq!(yes.type_of().int_width(), Some(1));
+    assert_eq!(unsafe { llvm_sys::core::LLVMConstIntGetZExtValue(yes.ptr()) }, 1);
+    let no = Kind::const_bool(ctx.clone(), false);
+    assert_eq!(unsafe { llvm_sys::core::LLVMConstIntGetZExtValue(no.ptr()) }, 0);
+
+    let n = Kind::const_i32(ctx.clone(), -7);
+    assert_eq!(n.type_of().int_width(), Some(32));
+    assert_eq!(unsafe { llvm_sys::core::LLVMConstIntGetSExtValue(n.ptr()) }, -7);
+    let n = Kind::const_i64(ctx.clone(), i64::MIN);
+    assert_eq!(n.type_of().int_width(), Some(64));
+    assert_eq!(unsafe { llvm_sys::core::LLVMConstIntGetSExtValue(n.ptr()) }, i64::MIN);
+}
+
+#[test]
+fn constant_arithmetic_folds() {
+    use llvm_sys::core::LLVMConstIntGetZExtValue;
+    let ctx = Context::create_context();
+    let (two, three) = (Kind::const_i32(ctx.clone(), 2), Kind::const_i32(ctx.clone(), 3));
+    let sum = Kind::const_add(two.clone(), three.clone());
+    assert_eq!(unsafe { LLVMConstIntGetZExtValue(sum.ptr()) }, 5);
+    let diff = Kind::const_sub(two.clone(), three.clone());
+    assert_eq!(format!("{diff:?}"), "i32 -1");
+    assert_eq!(Kind::const_neg(diff), Kind::const_i32(ctx.clone(), 1));
+    let bits = Kind::const_xor(two, three);
+    assert_eq!(unsafe { LLVMConstIntGetZExtValue(bits.ptr()) }, 1);
+}
+
+#[test]
+#[cfg(not(feature = "llvm-sys-210"))]
+fn constant_products_fold() {
+    let ctx = Context::create_context();
+    let product = Kind::const_mul(Kind::const_i32(ctx.clone(), 6), Kind::const_i32(ctx.clone(), 7));
+    assert_eq!(unsafe { llvm_sys::core::LLVMConstIntGetZExtValue(product.ptr()) }, 42);
+}
+
+#[test]
+#[cfg(feature = "llvm-sys-210")]
+#[should_panic = "const_mul requires LLVM 20 or older"]
+fn constant_products_need_llvm_20() {
+    let ctx = Context::create_context();
+    Kind::const_mul(Kind::const_i32(ctx.clone(), 6), Kind::const_i32(ctx.clone(), 7));
+}
+
+#[test]
+fn constant_ints_read_back() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i64 = int(&ctx, 64);
+    let answer = Kind::const_int(i64.clone(), 42, false);
+    assert_eq!(answer.const_int_value(), Some(42));
+    assert_eq!(answer.const_int_value_signed(), Some(42));
+    let minus_one = Kind::const_i64(ctx.clone(), -1);
+    assert_eq!(minus_one.const_int_value(), Some(u64::MAX));
+    assert_eq!(minus_one.const_int_value_signed(), Some(-1));
+
+    let f = define(&ctx, &module, c"f", i64.clone(), [i64]);
+    assert_eq!(f.param(0).const_int_value(), None);
+    assert_eq!(Kind::const_zero(Type::ptr_ty(ctx.clone(), 0)).const_int_value_signed(), None);
+}
+
+#[test]
+fn aggregates_zero_to_zeroinitializer() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let zero = Kind::const_int(i32.clone(), 0, false);
+    let array = Kind::const_array(i32.clone(), std::iter::repeat_n(zero, 100)).type_of();
+    let zeroed = Kind::const_zero(array);
+    assert_eq!(unsafe { llvm_sys::core::LLVMIsNull(zeroed.ptr()) }, 1);
+    Kind::add_constant_global(module.clone(), c"table", zeroed);
+
+    let pair = Type::struct_ty(ctx.clone(), [i32.clone(), Type::ptr_ty(ctx.clone(), 0)].into_iter(), false);
+    assert_eq!(format!("{:?}", Kind::const_zero(pair)), "{ i32, ptr } zeroinitializer");
+    let v4i32 = unsafe { Type::from_raw_leaked(llvm_sys::core::LLVMVectorType(i32.ptr(), 4)) };
+    assert_eq!(format!("{:?}", Kind::const_zero(v4i32)), "<4 x i32> zeroinitializer");
+    assert_eq!(format!("{:?}", Kind::const_zero(i32)), "i32 0");
+
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("@table = private unnamed_addr constant [100 x i32] zeroinitializer"), "{ir}");
+}
+
+#[test]
+fn byte_table_indexed_at_runtime() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let (i8, i64) = (int(&ctx, 8), int(&ctx, 64));
+    let data = (0..=255u8).map(|b| b.reverse_bits()).collect::<Vec<_>>();
+    let bytes = Kind::const_bytes(ctx.clone(), &data);
+    let table_ty = bytes.type_of();
+    assert_eq!(format!("{table_ty:?}"), "[256 x i8]");
+    let table = Kind::add_constant_global(module.clone(), c"", bytes);
+
+    let f = define(&ctx, &module, c"reverse", i8.clone(), [i64.clone()]);
+    let zero = Kind::const_int(i64, 0, false);
+    let slot = f.gep2_in_bounds(table_ty, table, [zero, f.param(0)].into_iter(), c"slot");
+    f.Ret(f.Load2(i8, slot, c"byte"));
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("@0 = private unnamed_addr constant [256 x i8] c\"\\00\\80@"), "{ir}");
+
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    let reverse = unsafe { jit.lookup_fn::<extern "C" fn(u64) -> u8>(c"reverse") }.unwrap();
+    for i in [0, 1, 2, 0x0f, 0x80, 0xaa, 0xff] {
+        assert_eq!(reverse(i), (i as u8).reverse_bits());
+    }
+}
+
+#[test]
+fn data_arrays_truncate_to_the_element_type() {
+    let ctx = Context::create_context();
+    let table = Kind::const_data_array(int(&ctx, 16), &[1, 0x1_0002, u64::MAX]);
+    assert_eq!(format!("{table:?}"), "[3 x i16] [i16 1, i16 2, i16 -1]");
+}
+
+#[test]
+fn integer_constants_change_width() {
+    let ctx = Context::create_context();
+    let (i8, i32, i64) = (int(&ctx, 8), int(&ctx, 32), int(&ctx, 64));
+    let wide = Kind::const_int(i64.clone(), 0x1_0000_0001, false);
+    let narrow = Kind::const_trunc(wide, i32);
+    assert_eq!(narrow.type_of().int_width(), Some(32));
+    assert_eq!(narrow.const_int_value(), Some(1));
+
+    let minus_one = Kind::const_int(i8, u8::MAX.into(), false);
+    assert_eq!(Kind::const_zext(minus_one.clone(), i64.clone()).const_int_value(), Some(0xff));
+    assert_eq!(Kind::const_sext(minus_one, i64).const_int_value_signed(), Some(-1));
+}
+
+#[test]
+#[cfg(any(
+    feature = "llvm-sys-180",
+    feature = "llvm-sys-190",
+    feature = "llvm-sys-200",
+    feature = "llvm-sys-210",
+))]
+#[should_panic = "LLVMZExt of this constant does not fold to a constant"]
+fn zext_of_an_address_must_fold_on_llvm_18() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let g = Kind::add_constant_global(module.clone(), c"g", Kind::const_i32(ctx.clone(), 0));
+    let addr = unsafe { Val::from_raw_leaked(llvm_sys::core::LLVMConstPtrToInt(g.ptr(), int(&ctx, 32).ptr())) };
+    Kind::const_zext(addr, int(&ctx, 64));
+}
+
+#[test]
+#[cfg(not(any(
+    feature = "llvm-sys-180",
+    feature = "llvm-sys-190",
+    feature = "llvm-sys-200",
+    feature = "llvm-sys-210",
+)))]
+fn zext_of_an_address_is_a_constant_expression() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let g = Kind::add_constant_global(module.clone(), c"g", Kind::const_i32(ctx.clone(), 0));
+    let addr = unsafe { Val::from_raw_leaked(llvm_sys::core::LLVMConstPtrToInt(g.ptr(), int(&ctx, 32).ptr())) };
+    let wide = Kind::const_zext(addr, int(&ctx, 64));
+    assert_eq!(format!("{wide:?}"), "i64 zext (i32 ptrtoint (ptr @g to i32) to i64)");
+}
+
+#[test]
+fn u128_constants_keep_both_words() {
+    let ctx = Context::create_context();
+    let (i64, i128) = (int(&ctx, 64), int(&ctx, 128));
+    let n = 0xfedc_ba98_7654_3210_0123_4567_89ab_cdef_u128;
+    let wide = Kind::const_u128(i128.clone(), n);
+    let low = Kind::const_trunc(wide.clone(), i64.clone());
+    assert_eq!(low.const_int_value(), Some(n as u64));
+    // The C API has no accessor for words past the first, so read the value
+    // back from its printed form.
+    let printed = format!("{wide:?}");
+    let value = printed.strip_prefix("i128 ").unwrap().parse::<i128>().unwrap();
+    assert_eq!(value as u128, n);
+    assert_eq!((value as u128 >> 64) as u64, 0xfedc_ba98_7654_3210);
+
+    let narrow = Kind::const_u128(i64, n);
+    assert_eq!(narrow.const_int_value(), Some(0x0123_4567_89ab_cdef));
+
+    let module = Module::create_mod(c"m", &ctx);
+    let f = define(&ctx, &module, c"wide", i128, []);
+    f.Ret(wide);
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    let wide = unsafe { jit.lookup_fn::<extern "C" fn() -> u128>(c"wide") }.unwrap();
+    assert_eq!(wide(), n);
+}
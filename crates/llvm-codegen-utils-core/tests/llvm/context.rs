@@ -0,0 +1,90 @@
+use super::*;
+
+#[test]
+fn modules_and_builders_keep_the_context_alive() {
+    let ctx = Context::create_context();
+    let disposed = count_disposal(&ctx);
+    let module = Module::create_mod(c"m", &ctx);
+    let builder = IrBuilder::new_in_ctx(ctx.clone());
+    let i32 = int(&ctx, 32);
+    drop(ctx);
+    assert_eq!(disposed.get(), 0);
+
+    let f = FunctionBuilder::new(builder, module.clone(), c"seven", i32.clone().fun_ty([].into_iter()));
+    f.Ret(Kind::const_int(i32, 7, false));
+    drop(f);
+    verify(&module).unwrap();
+    assert!(format!("{module:?}").contains("ret i32 7"));
+    assert_eq!(disposed.get(), 0);
+    drop(module);
+    assert_eq!(disposed.get(), 1);
+}
+
+#[test]
+fn owned_raw_contexts_are_disposed_like_created_ones() {
+    let ctx = unsafe { Context::from_raw_owned(llvm_sys::core::LLVMContextCreate()) };
+    let disposed = count_disposal(&ctx);
+    let module = Module::create_mod(c"m", &ctx);
+    assert_eq!(ctx.modules(), std::slice::from_ref(&module));
+    drop(ctx);
+    assert_eq!(disposed.get(), 0);
+    drop(module);
+    assert_eq!(disposed.get(), 1);
+}
+
+#[test]
+fn with_context_disposes_the_context_on_return() {
+    let (ir, disposed) = Context::with_context(|ctx| {
+        let disposed = count_disposal(&ctx);
+        let module = Module::create_mod(c"scoped", &ctx);
+        let i64 = int(&ctx, 64);
+        let add = FunctionBuilder::new(
+            IrBuilder::new_in_ctx(ctx.clone()),
+            module.clone(),
+            c"add",
+            i64.clone().fun_ty([i64.clone(), i64].into_iter()),
+        );
+        let sum = add.Add(add.param(0), add.param(1), c"sum");
+        add.Ret(sum);
+        verify(&module).unwrap();
+        (format!("{module:?}"), disposed)
+    });
+    assert!(ir.contains("add i64"));
+    assert_eq!(disposed.get(), 1);
+}
+
+#[test]
+fn with_context_disposes_the_context_on_unwind() {
+    let disposed = std::cell::RefCell::new(None);
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        Context::with_context(|ctx| {
+            *disposed.borrow_mut() = Some(count_disposal(&ctx));
+            let _module = Module::create_mod(c"scoped", &ctx);
+            panic!("codegen failed");
+        })
+    }));
+    assert!(res.is_err());
+    assert_eq!(disposed.borrow().as_ref().unwrap().get(), 1);
+}
+
+#[test]
+fn diagnostic_handler_sees_linker_warnings() {
+    let ctx = Context::create_context();
+    let seen = Rc::new(std::cell::RefCell::new(vec![]));
+    let sink = seen.clone();
+    ctx.set_diagnostic_handler(move |info| sink.borrow_mut().push(info));
+
+    let dst = Module::create_mod(c"dst", &ctx);
+    let src = Module::create_mod(c"src", &ctx);
+    unsafe {
+        llvm_sys::core::LLVMSetDataLayout(dst.ptr(), c"e-p:64:64".as_ptr());
+        llvm_sys::core::LLVMSetDataLayout(src.ptr(), c"e-p:32:32".as_ptr());
+    }
+    let dst = dst.link(src).unwrap();
+
+    let seen = seen.borrow();
+    assert_eq!(seen.len(), 1, "{seen:?}");
+    assert_eq!(seen[0].severity, DiagnosticSeverity::Warning);
+    assert!(seen[0].message.contains("different data layouts"), "{}", seen[0].message);
+    drop(dst);
+}
@@ -0,0 +1,63 @@
+use super::*;
+
+type DebugInfo<'a> = LLHandle<'a, Normal, llvm_sys::LLVMOpaqueDIBuilder>;
+
+#[test]
+fn line_locations_are_attached_as_dbg() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"twice", i32.clone(), [i32]);
+
+    let di = DebugInfo::new_in_mod(module.clone());
+    let file = di.create_file(c"twice.src", c"/tmp");
+    let unit = di.create_compile_unit(SourceLanguage::C, file.clone(), c"tests", false);
+    let int_ty = di.create_basic_type(c"int", 32, 0x05);
+    let fn_ty = di.create_subroutine_type(file.clone(), [Some(int_ty.clone()), Some(int_ty)].into_iter());
+    let sub = di.create_function(f.func(), unit, c"twice", file, 3, fn_ty);
+    di.set_location(&f, 4, 7, sub);
+    let sum = f.Add(f.param(0), f.param(0), c"sum");
+    f.clear_debug_location();
+    f.Ret(sum);
+    di.finalize();
+
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("%sum = add i32 %0, %0, !dbg !"), "{ir}");
+    assert!(ir.contains("ret i32 %sum\n"), "{ir}");
+    assert!(ir.contains("define i32 @twice(i32 %0) !dbg !"), "{ir}");
+    assert!(ir.contains("!DILocation(line: 4, column: 7, scope: !"), "{ir}");
+    assert!(ir.contains(r#"!DIFile(filename: "twice.src", directory: "/tmp")"#), "{ir}");
+}
+
+#[test]
+fn each_instruction_gets_the_current_location() {
+    use llvm_sys::core::{LLVMGetDebugLocColumn, LLVMGetDebugLocLine};
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"poly", i32.clone(), [i32]);
+
+    let di = DebugInfo::new_in_mod(module.clone());
+    let file = di.create_file(c"poly.src", c"/tmp");
+    let unit = di.create_compile_unit(SourceLanguage::C, file.clone(), c"tests", false);
+    let int_ty = di.create_basic_type(c"int", 32, 0x05);
+    let fn_ty = di.create_subroutine_type(file.clone(), [Some(int_ty.clone()), Some(int_ty)].into_iter());
+    let sub = di.create_function(f.func(), unit, c"poly", file, 1, fn_ty);
+    f.set_debug_location(2, 5, sub.clone());
+    let square = f.Mul(f.param(0), f.param(0), c"square");
+    f.set_debug_location(3, 9, sub);
+    let sum = f.Add(square.clone(), f.param(0), c"sum");
+    f.clear_debug_location();
+    let ret = f.Ret(sum.clone());
+    di.finalize();
+    verify(&module).unwrap();
+
+    let loc = |v: &Val| unsafe { (LLVMGetDebugLocLine(v.ptr()), LLVMGetDebugLocColumn(v.ptr())) };
+    assert_eq!(loc(&square), (2, 5));
+    assert_eq!(loc(&sum), (3, 9));
+    assert_eq!(loc(&ret), (0, 0));
+    let ir = format!("{module:?}");
+    assert!(ir.contains("!DILocation(line: 2, column: 5, scope: !"), "{ir}");
+    assert!(ir.contains("!DILocation(line: 3, column: 9, scope: !"), "{ir}");
+}
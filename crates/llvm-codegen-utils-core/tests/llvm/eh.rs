@@ -0,0 +1,122 @@
+use super::*;
+use px_llvm_codegen_utils_core::eh::*;
+
+/// Declares the personality `name` and a `void ()` function that may throw,
+/// returning the personality and the thrower with its type.
+fn personality<'a>(
+    ctx: &Context<'a>,
+    module: &Module<'a>,
+    name: &CStr,
+) -> (Function<'a>, Type<'a>, Val<'a>) {
+    let personality = Kind::declare(module.clone(), name, int(ctx, 32).fun_ty([].into_iter()));
+    let may_throw_ty = void(ctx).fun_ty([].into_iter());
+    let may_throw = Kind::declare(module.clone(), c"may_throw", may_throw_ty.clone());
+    (personality, may_throw_ty, unsafe { Val::from_raw_leaked(may_throw.ptr()) })
+}
+
+#[test]
+fn try_catch_verifies() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let (personality, may_throw_ty, may_throw) = personality(&ctx, &module, c"__gxx_personality_v0");
+    let i32 = int(&ctx, 32);
+    let ptr = Type::ptr_ty(ctx.clone(), 0);
+    let f = define(&ctx, &module, c"f", i32.clone(), []);
+    f.func().set_personality(personality);
+    let ok = f.append_block(c"ok");
+    let catch = f.append_block(c"catch");
+    f.invoke(may_throw_ty, may_throw, [].into_iter(), ok.clone(), catch.clone(), c"");
+
+    f.position_at_end(ok);
+    f.Ret(Kind::const_int(i32.clone(), 0, false));
+
+    f.position_at_end(catch);
+    let exn_ty = Type::struct_ty(ctx.clone(), [ptr.clone(), i32.clone()].into_iter(), false);
+    let lp = f.landing_pad(exn_ty, c"lp");
+    lp.add_catch(Kind::const_zero(ptr.clone()));
+    lp.add_filter([]);
+    lp.set_cleanup(true);
+    f.Ret(Kind::const_int(i32, 1, false));
+
+    f.func().verify().unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("landingpad { ptr, i32 }\n          cleanup\n          catch ptr null\n          filter [0 x ptr] zeroinitializer"), "{ir}");
+    assert_eq!(LandingPad::from_value(lp.as_value()), Some(lp));
+}
+
+#[test]
+fn funclets_verify_on_windows() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    unsafe { llvm_sys::core::LLVMSetTarget(module.ptr(), c"x86_64-pc-windows-msvc".as_ptr()) };
+    let (personality, may_throw_ty, may_throw) = personality(&ctx, &module, c"__CxxFrameHandler3");
+    let i32 = int(&ctx, 32);
+    let ptr = Type::ptr_ty(ctx.clone(), 0);
+    let f = define(&ctx, &module, c"f", void(&ctx), []);
+    f.func().set_personality(personality);
+    let ok = f.append_block(c"ok");
+    let dispatch = f.append_block(c"dispatch");
+    let handler = f.append_block(c"handler");
+    let cleanup = f.append_block(c"cleanup");
+    f.invoke(may_throw_ty, may_throw, [].into_iter(), ok.clone(), dispatch.clone(), c"");
+
+    f.position_at_end(ok.clone());
+    f.ret_void();
+
+    // catch (...) {}, running a cleanup on any other exception.
+    f.position_at_end(dispatch);
+    let switch = f.catch_switch(None, Some(cleanup.clone()), c"cs");
+    switch.add_handler(handler.clone());
+    f.position_at_end(handler);
+    let null = Kind::const_zero(ptr);
+    let pad = f.catch_pad(&switch, [null.clone(), Kind::const_int(i32, 64, false), null].into_iter(), c"cp");
+    f.CatchRet(pad.as_value(), ok);
+    f.position_at_end(cleanup);
+    let cleanup_pad = f.cleanup_pad(None, [].into_iter(), c"cl");
+    f.CleanupRet(cleanup_pad.as_value(), None);
+
+    verify(&module).unwrap();
+    f.func().verify().unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("%cs = catchswitch within none [label %handler] unwind label %cleanup"), "{ir}");
+    assert!(ir.contains("%cp = catchpad within %cs [ptr null, i32 64, ptr null]"), "{ir}");
+    assert!(ir.contains("%cl = cleanuppad within none []"), "{ir}");
+    assert!(CatchSwitch::from_value(switch.as_value()).is_some());
+    assert!(CatchPad::from_value(cleanup_pad.as_value()).is_none());
+    assert!(CleanupPad::from_value(cleanup_pad.as_value()).is_some());
+}
+
+#[test]
+fn only_pads_convert() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"f", i32.clone(), [i32]);
+    let sum = f.Add(f.param(0), f.param(0), c"sum");
+    assert!(LandingPad::from_value(sum.clone()).is_none());
+    assert!(CatchSwitch::from_value(sum).is_none());
+}
+
+#[test]
+fn personality_is_attached_to_the_function() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let (personality, may_throw_ty, may_throw) = personality(&ctx, &module, c"__gxx_personality_v0");
+    let f = define(&ctx, &module, c"f", void(&ctx), []);
+    f.func().set_personality(personality.clone());
+    assert_eq!(unsafe { llvm_sys::core::LLVMGetPersonalityFn(f.func().ptr()) }, personality.ptr());
+    let ok = f.append_block(c"ok");
+    let cleanup = f.append_block(c"cleanup");
+    f.invoke(may_throw_ty, may_throw, [].into_iter(), ok.clone(), cleanup.clone(), c"");
+    f.position_at_end(ok);
+    f.ret_void();
+    f.position_at_end(cleanup);
+    let ptr = Type::ptr_ty(ctx.clone(), 0);
+    let exn_ty = Type::struct_ty(ctx.clone(), [ptr, int(&ctx, 32)].into_iter(), false);
+    let lp = f.landing_pad(exn_ty, c"lp");
+    lp.set_cleanup(true);
+    f.Resume(lp.as_value());
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("define void @f() personality ptr @__gxx_personality_v0"), "{ir}");
+}
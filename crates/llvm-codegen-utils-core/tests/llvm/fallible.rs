@@ -0,0 +1,112 @@
+use super::*;
+
+#[test]
+fn binop_type_mismatches_are_errors() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let (i32, i64, ptr) = (int(&ctx, 32), int(&ctx, 64), Type::ptr_ty(ctx.clone(), 0));
+    let f = define(&ctx, &module, c"f", i32.clone(), [i32.clone(), i64, ptr.clone(), ptr]);
+    let (a, wide, p) = (f.param(0), f.param(1), f.param(2));
+
+    assert_eq!(f.try_binop(BinOp::Add, a.clone(), wide, c"").unwrap_err(), BuildError::TypeMismatch);
+    assert_eq!(f.try_binop(BinOp::Xor, p, f.param(3), c"").unwrap_err(), BuildError::TypeMismatch);
+    assert_eq!(f.entry_block().instructions().count(), 0);
+
+    let sum = f.try_binop(BinOp::Add, a.clone(), a, c"sum").unwrap();
+    f.Ret(sum);
+    verify(&module).unwrap();
+}
+
+#[test]
+fn gep_type_mismatches_are_errors() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let (i8, i16, i32, i64) = (int(&ctx, 8), int(&ctx, 16), int(&ctx, 32), int(&ctx, 64));
+    let ptr = Type::ptr_ty(ctx.clone(), 0);
+    let s = Type::struct_ty(ctx.clone(), [i8, i16, i64.clone()].into_iter(), false);
+    let f = define(&ctx, &module, c"f", ptr.clone(), [ptr, i32.clone()]);
+    let (base, n) = (f.param(0), f.param(1));
+    let zero = Kind::const_i32(ctx.clone(), 0);
+    let gep = |indices: Vec<Val>| f.try_gep2(s.clone(), base.clone(), indices.into_iter(), c"field");
+
+    // A struct field must be picked by a constant `i32` in range.
+    assert_eq!(gep(vec![zero.clone(), n.clone()]).unwrap_err(), BuildError::TypeMismatch);
+    assert_eq!(gep(vec![zero.clone(), Kind::const_i64(ctx.clone(), 2)]).unwrap_err(), BuildError::TypeMismatch);
+    assert_eq!(gep(vec![zero.clone(), Kind::const_i32(ctx.clone(), 3)]).unwrap_err(), BuildError::TypeMismatch);
+    // Indices must be integers and the base a pointer.
+    assert_eq!(gep(vec![base.clone()]).unwrap_err(), BuildError::TypeMismatch);
+    let not_ptr = f.try_gep2(s.clone(), n.clone(), [zero.clone()].into_iter(), c"");
+    assert_eq!(not_ptr.unwrap_err(), BuildError::TypeMismatch);
+    // Nothing can step into the `i64` field.
+    let past_leaf = vec![zero.clone(), Kind::const_i32(ctx.clone(), 2), zero.clone()];
+    assert_eq!(gep(past_leaf).unwrap_err(), BuildError::TypeMismatch);
+    assert_eq!(f.entry_block().instructions().count(), 0);
+
+    let field = gep(vec![n, Kind::const_i32(ctx.clone(), 2)]).unwrap();
+    f.Ret(field);
+    verify(&module).unwrap();
+}
+
+#[test]
+fn calls_are_checked_against_the_signature() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let (i32, i64) = (int(&ctx, 32), int(&ctx, 64));
+    let sig = Signature::new(i32.clone(), [i32.clone(), i32.clone()]);
+    let add = define(&ctx, &module, c"add", i32.clone(), [i32.clone(), i32.clone()]);
+    assert_eq!(Signature::of(&sig.fn_ty()), Some(sig.clone()));
+    add.Ret(add.Add(add.param(0), add.param(1), c"sum"));
+    let add = unsafe { Val::from_raw_leaked(add.func().ptr()) };
+
+    let f = define(&ctx, &module, c"f", i32.clone(), [i32.clone(), i64]);
+    let (x, wide) = (f.param(0), f.param(1));
+    let err = f.call_with_signature(&sig, add.clone(), [x.clone()].into_iter(), c"").unwrap_err();
+    assert_eq!(err, BuildError::ArityMismatch { expected: 2, found: 1 });
+    assert_eq!(err.to_string(), "expected 2 arguments, found 1");
+    let err = f.call_with_signature(&sig, add.clone(), [x.clone(), x.clone(), x.clone()].into_iter(), c"");
+    assert_eq!(err.unwrap_err(), BuildError::ArityMismatch { expected: 2, found: 3 });
+    if cfg!(debug_assertions) {
+        let err = f.call_with_signature(&sig, add.clone(), [x.clone(), wide].into_iter(), c"");
+        assert_eq!(err.unwrap_err(), BuildError::TypeMismatch);
+    }
+    assert_eq!(f.entry_block().instructions().count(), 0);
+
+    let sum = f.call_with_signature(&sig, add, [x.clone(), x].into_iter(), c"sum").unwrap();
+    f.Ret(sum);
+    verify(&module).unwrap();
+}
+
+#[test]
+fn variadic_signatures_accept_extra_arguments() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let (i32, ptr) = (int(&ctx, 32), Type::ptr_ty(ctx.clone(), 0));
+    let sig = Signature::var_arg(i32.clone(), [ptr.clone()]);
+    assert!(sig.is_var_arg());
+    assert_eq!(sig.fn_ty().is_var_arg(), Some(true));
+    let printf = Kind::declare(module.clone(), c"printf", sig.fn_ty());
+    let declared = unsafe { Type::from_raw_leaked(llvm_sys::core::LLVMGlobalGetValueType(printf.ptr())) };
+    assert_eq!(Signature::of(&declared), Some(sig.clone()));
+    let printf = unsafe { Val::from_raw_leaked(printf.ptr()) };
+
+    let f = define(&ctx, &module, c"f", void(&ctx), [i32.clone()]);
+    let format = f.global_string_ptr(c"%d %d\n", c"format");
+    let err = f.call_with_signature(&sig, printf.clone(), [].into_iter(), c"").unwrap_err();
+    assert_eq!(err, BuildError::ArityMismatch { expected: 1, found: 0 });
+    if cfg!(debug_assertions) {
+        let err = f.call_with_signature(&sig, printf.clone(), [f.param(0)].into_iter(), c"");
+        assert_eq!(err.unwrap_err(), BuildError::TypeMismatch);
+    }
+    f.call_with_signature(&sig, printf.clone(), [format.clone()].into_iter(), c"").unwrap();
+    f.call_with_signature(&sig, printf, [format, f.param(0), f.param(0)].into_iter(), c"").unwrap();
+    f.ret_void();
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("declare i32 @printf(ptr, ...)"), "{ir}");
+    assert!(ir.contains("call i32 (ptr, ...) @printf(ptr @format, i32 %0, i32 %0)"), "{ir}");
+
+    let fixed = Signature::new(i32, [ptr]);
+    assert!(!fixed.is_var_arg());
+    assert_ne!(fixed, sig);
+    assert_eq!(Signature::of(&fixed.fn_ty()), Some(fixed));
+}
@@ -0,0 +1,176 @@
+use super::*;
+
+#[test]
+fn classic_add() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i64 = int(&ctx, 64);
+    let ty = i64.clone().fun_ty([i64.clone(), i64].into_iter());
+
+    let add = FunctionBuilder::new(IrBuilder::new_in_ctx(ctx.clone()), module.clone(), c"add", ty);
+    let sum = add.Add(add.param(0), add.param(1), c"sum");
+    add.Ret(sum);
+
+    assert!(add.entry_block().as_value().name().as_deref() == Some("entry"));
+    verify(&module).unwrap();
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    let add = unsafe { jit.lookup_fn::<extern "C" fn(i64, i64) -> i64>(c"add") }.unwrap();
+    assert_eq!(add(40, 2), 42);
+}
+
+#[test]
+fn appending_a_block_leaves_the_builder_in_place() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = define(&ctx, &module, c"f", void(&ctx), []);
+    let exit = f.append_block(c"exit");
+    assert!(f.insert_block() == Some(f.entry_block()));
+    f.Br(exit.clone());
+    f.position_at_end(exit.clone());
+    assert!(f.insert_block() == Some(exit));
+    f.ret_void();
+    assert_eq!(f.func().basic_blocks().count(), 2);
+    verify(&module).unwrap();
+}
+
+#[test]
+#[should_panic = "parameter index out of range"]
+fn missing_parameters_panic() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    define(&ctx, &module, c"f", void(&ctx), []).param(0);
+}
+
+#[test]
+fn interned_names_are_reused() {
+    let names = NameCache::default();
+    let tmp = names.intern("tmp");
+    for i in 0..1000 {
+        names.intern(&format!("tmp{i}"));
+    }
+    assert!(std::ptr::eq(tmp, names.intern("tmp")));
+    assert_eq!(tmp, c"tmp");
+
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"twice", i32.clone(), [i32]);
+    let mut acc = f.param(0);
+    for _ in 0..2 {
+        acc = f.Add(acc.clone(), acc, f.name("acc"));
+    }
+    assert!(std::ptr::eq(f.name("acc"), f.name("acc")));
+    f.Ret(acc);
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("%acc = add i32 %0, %0"), "{ir}");
+    assert!(ir.contains("%acc1 = add i32 %acc, %acc"), "{ir}");
+}
+
+#[test]
+fn if_else_returning_from_both_arms() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"small", i32.clone(), [i32]);
+    let ten = Kind::const_i32(ctx.clone(), 10);
+    let cond = f.ICmp(ICmp::Lts, f.param(0), ten, c"small");
+    f.build_if(
+        cond,
+        |f| {
+            f.Ret(Kind::const_i32(ctx.clone(), 1));
+        },
+        |f| {
+            f.Ret(Kind::const_i32(ctx.clone(), 2));
+        },
+    );
+    // Both arms returned, so there is no merge block to continue in.
+    assert!(f.insert_block().is_none());
+    assert_eq!(f.func().basic_blocks().count(), 3);
+    verify(&module).unwrap();
+
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    let small = unsafe { jit.lookup_fn::<extern "C" fn(i32) -> i32>(c"small") }.unwrap();
+    assert_eq!((small(3), small(10), small(-5), small(99)), (1, 2, 1, 2));
+}
+
+#[test]
+fn if_else_falling_through_to_the_merge_block() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"abs", i32.clone(), [i32.clone()]);
+    let slot = f.Alloca(i32.clone(), c"slot");
+    let zero = Kind::const_i32(ctx.clone(), 0);
+    let negative = f.ICmp(ICmp::Lts, f.param(0), zero.clone(), c"negative");
+    f.build_if(
+        negative,
+        |f| {
+            f.Store(f.Sub(zero, f.param(0), c"neg"), slot.clone());
+        },
+        |f| {
+            f.Store(f.param(0), slot.clone());
+        },
+    );
+    f.Ret(f.Load2(i32, slot, c"abs"));
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("if.end:"), "{ir}");
+
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    let abs = unsafe { jit.lookup_fn::<extern "C" fn(i32) -> i32>(c"abs") }.unwrap();
+    assert_eq!((abs(-7), abs(7), abs(0)), (7, 7, 0));
+}
+
+#[test]
+fn while_loop_sums_below_n() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i64 = int(&ctx, 64);
+    let f = define(&ctx, &module, c"sum_below", i64.clone(), [i64.clone()]);
+    let (i, sum) = (f.Alloca(i64.clone(), c"i"), f.Alloca(i64.clone(), c"sum"));
+    let (zero, one) = (Kind::const_i64(ctx.clone(), 0), Kind::const_i64(ctx.clone(), 1));
+    f.Store(zero.clone(), i.clone());
+    f.Store(zero, sum.clone());
+    f.build_while(
+        |f| {
+            let cur = f.Load2(i64.clone(), i.clone(), c"cur");
+            f.ICmp(ICmp::Lts, cur, f.param(0), c"more")
+        },
+        |f| {
+            let cur = f.Load2(i64.clone(), i.clone(), c"cur");
+            let acc = f.Load2(i64.clone(), sum.clone(), c"acc");
+            f.Store(f.Add(acc, cur.clone(), c"acc.next"), sum.clone());
+            f.Store(f.Add(cur, one, c"cur.next"), i.clone());
+        },
+    );
+    assert!(f.insert_block().unwrap().as_value().name().as_deref() == Some("while.end"));
+    f.Ret(f.Load2(i64, sum, c"total"));
+    verify(&module).unwrap();
+
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    let sum_below = unsafe { jit.lookup_fn::<extern "C" fn(i64) -> i64>(c"sum_below") }.unwrap();
+    assert_eq!((sum_below(0), sum_below(1), sum_below(10), sum_below(-3)), (0, 0, 45, 0));
+}
+
+#[test]
+fn functions_verify_on_their_own() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let good = define(&ctx, &module, c"good", i32.clone(), [i32.clone()]);
+    good.Ret(good.param(0));
+    assert_eq!(good.func().verify(), Ok(()));
+    assert_eq!(good.func().verify_with_message(), Ok(()));
+
+    // The entry block has no terminator.
+    let bad = define(&ctx, &module, c"bad", i32.clone(), [i32]);
+    bad.Add(bad.param(0), bad.param(0), c"sum");
+    assert_eq!(bad.func().verify(), Err(()));
+    assert_eq!(bad.func().verify_with_message(), Err(()));
+    assert_eq!(good.func().verify(), Ok(()));
+}
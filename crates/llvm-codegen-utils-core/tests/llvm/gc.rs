@@ -0,0 +1,67 @@
+use super::*;
+
+#[test]
+fn gc_strategy_round_trips() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = define(&ctx, &module, c"f", void(&ctx), []);
+    assert_eq!(f.func().gc(), None);
+    f.func().set_gc(c"statepoint-example");
+    assert_eq!(f.func().gc().as_deref(), Some("statepoint-example"));
+    f.func().set_gc(c"shadow-stack");
+    assert_eq!(f.func().gc().as_deref(), Some("shadow-stack"));
+    f.ret_void();
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains(r#"define void @f() gc "shadow-stack" {"#), "{ir}");
+}
+
+#[test]
+#[cfg(any(
+    feature = "llvm-sys-180",
+    feature = "llvm-sys-190",
+    feature = "llvm-sys-200",
+    feature = "llvm-sys-210",
+))]
+fn statepoint_around_a_call_verifies() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let gc_ptr = Type::ptr_ty(ctx.clone(), 1);
+    let callee_ty = i32.clone().fun_ty([i32.clone()].into_iter());
+    let callee = Kind::declare(module.clone(), c"callee", callee_ty.clone());
+    let callee = unsafe { Val::from_raw_leaked(callee.ptr()) };
+
+    let f = define(&ctx, &module, c"f", gc_ptr.clone(), [gc_ptr.clone(), i32.clone()]);
+    f.func().set_gc(c"statepoint-example");
+    let (obj, x) = (f.param(0), f.param(1));
+    let token = f.gc_statepoint(0, 0, callee_ty, callee, [x].into_iter(), [obj].into_iter(), c"sp");
+    let res = f.gc_result(token.clone(), i32.clone(), c"res");
+    let moved = f.gc_relocate(token, 0, 0, gc_ptr.clone(), c"moved");
+    f.Store(res, moved.clone());
+    f.Ret(moved);
+
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("elementtype(i32 (i32)) @callee, i32 1, i32 0, i32 %1, i32 0, i32 0)"), "{ir}");
+    assert!(ir.contains("[ \"gc-live\"(ptr addrspace(1) %0) ]"), "{ir}");
+    assert!(ir.contains("@llvm.experimental.gc.relocate.p1(token %sp, i32 0, i32 0)"), "{ir}");
+}
+
+#[test]
+#[cfg(not(any(
+    feature = "llvm-sys-180",
+    feature = "llvm-sys-190",
+    feature = "llvm-sys-200",
+    feature = "llvm-sys-210",
+)))]
+#[should_panic = "`gc.statepoint` requires LLVM 18 or newer"]
+fn statepoints_need_llvm_18() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let callee_ty = void(&ctx).fun_ty([].into_iter());
+    let callee = Kind::declare(module.clone(), c"callee", callee_ty.clone());
+    let callee = unsafe { Val::from_raw_leaked(callee.ptr()) };
+    let f = define(&ctx, &module, c"f", void(&ctx), []);
+    f.gc_statepoint(0, 0, callee_ty, callee, [].into_iter(), [].into_iter(), c"sp");
+}
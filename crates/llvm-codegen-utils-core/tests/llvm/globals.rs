@@ -0,0 +1,171 @@
+use super::*;
+
+/// Adds a constant `i32` global called `name` to `module`.
+fn global<'a>(ctx: &Context<'a>, module: &Module<'a>, name: &CStr) -> Val<'a> {
+    Kind::add_constant_global(module.clone(), name, Kind::const_int(int(ctx, 32), 7, false))
+}
+
+#[test]
+fn thread_local_mode_round_trips() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let tls = global(&ctx, &module, c"tls");
+    assert_eq!(tls.thread_local_mode(), ThreadLocalMode::NotThreadLocal);
+    tls.set_thread_local_mode(ThreadLocalMode::InitialExec);
+    assert_eq!(tls.thread_local_mode(), ThreadLocalMode::InitialExec);
+    let ir = format!("{module:?}");
+    assert!(ir.contains("thread_local(initialexec)"), "{ir}");
+    verify(&module).unwrap();
+}
+
+#[test]
+#[should_panic = "not a global variable"]
+fn thread_local_mode_needs_a_global_variable() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = define(&ctx, &module, c"f", void(&ctx), []);
+    f.func().set_thread_local_mode(ThreadLocalMode::LocalExec);
+}
+
+#[test]
+fn section_and_alignment_round_trip() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let g = global(&ctx, &module, c"g");
+    assert_eq!(g.section(), None);
+    g.set_section(c".mysection");
+    assert_eq!(g.section().as_deref(), Some(".mysection"));
+    g.set_alignment(16);
+
+    let f = define(&ctx, &module, c"f", void(&ctx), []);
+    let slot = f.Alloca(int(&ctx, 32), c"slot");
+    slot.set_alignment(8);
+    f.ret_void();
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains(r#"section ".mysection", align 16"#), "{ir}");
+    assert!(ir.contains("%slot = alloca i32, align 8"), "{ir}");
+}
+
+#[test]
+#[should_panic = "only global objects, allocas, loads and stores have an alignment"]
+fn alignment_needs_an_aligned_value() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"f", i32.clone(), [i32]);
+    f.Add(f.param(0), f.param(0), c"sum").set_alignment(4);
+}
+
+#[test]
+#[should_panic = "not a global value"]
+fn section_needs_a_global_value() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"f", i32.clone(), [i32]);
+    f.param(0).set_section(c".mysection");
+}
+
+#[test]
+fn argv_table_ends_in_null() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let prog = Kind::add_constant_global(module.clone(), c"prog", Kind::const_bytes(ctx.clone(), b"prog\0"));
+    let flag = Kind::add_constant_global(module.clone(), c"flag", Kind::const_bytes(ctx.clone(), b"-v\0"));
+    let argv = Kind::build_ptr_array_global(module.clone(), c"argv", [prog.clone(), flag.clone()].into_iter());
+
+    let init = unsafe { Val::from_raw_leaked(llvm_sys::core::LLVMGetInitializer(argv.ptr())) };
+    assert_eq!(format!("{:?}", init.type_of()), "[3 x ptr]");
+    let elems = init.operands().collect::<Vec<_>>();
+    assert_eq!(elems[..2], [prog, flag]);
+    assert_eq!(elems[2].value_kind(), ValueClass::ConstantPointerNull);
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("@argv = constant [3 x ptr] [ptr @prog, ptr @flag, ptr null]"), "{ir}");
+}
+
+#[test]
+fn identical_string_literals_get_their_own_globals() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = define(&ctx, &module, c"greet", Type::ptr_ty(ctx.clone(), 0), []);
+    let first = f.global_string_ptr(c"hello", c"greeting");
+    let second = f.global_string_ptr(c"hello", c"greeting");
+    assert_eq!(first.type_of(), Type::ptr_ty(ctx.clone(), 0));
+    assert_eq!(second.type_of(), first.type_of());
+    assert_ne!(first, second);
+    f.Ret(second);
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains(r#"@greeting = private unnamed_addr constant [6 x i8] c"hello\00""#), "{ir}");
+    assert!(ir.contains(r#"@greeting.1 = private unnamed_addr constant [6 x i8] c"hello\00""#), "{ir}");
+    assert!(ir.contains("ret ptr @greeting.1"), "{ir}");
+}
+
+#[test]
+fn alias_resolves_to_its_function() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"answer", i32.clone(), []);
+    f.Ret(Kind::const_i32(ctx.clone(), 42));
+    let answer = unsafe { Val::from_raw_leaked(f.func().ptr()) };
+    let alias = module.add_alias(i32.clone().fun_ty([].into_iter()), 0, answer.clone(), c"reply");
+    assert_eq!(unsafe { llvm_sys::core::LLVMAliasGetAliasee(alias.ptr()) }, answer.ptr());
+    assert_eq!(alias.name().unwrap(), "reply");
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("@reply = alias i32 (), ptr @answer"), "{ir}");
+
+    let resolver = define(&ctx, &module, c"pick", Type::ptr_ty(ctx.clone(), 0), []);
+    resolver.Ret(answer);
+    let ifunc = module.add_ifunc(i32.fun_ty([].into_iter()), 0, resolver.func().clone(), c"dispatch");
+    assert_eq!(unsafe { llvm_sys::core::LLVMGetGlobalIFuncResolver(ifunc.ptr()) }, resolver.func().ptr());
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("@dispatch = ifunc i32 (), ptr @pick"), "{ir}");
+}
+
+#[test]
+fn functions_join_a_comdat() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = define(&ctx, &module, c"inline_fn", void(&ctx), []);
+    f.ret_void();
+    assert!(f.func().comdat().is_none());
+    let comdat = module.comdat(c"inline_fn");
+    assert_eq!(comdat.selection_kind(), ComdatSelectionKind::Any);
+    comdat.set_selection_kind(ComdatSelectionKind::Largest);
+    f.func().set_comdat(comdat.clone());
+    let read = f.func().comdat().unwrap();
+    assert_eq!(read.ptr(), comdat.ptr());
+    assert_eq!(read.selection_kind(), ComdatSelectionKind::Largest);
+    assert_eq!(module.comdat(c"inline_fn").ptr(), comdat.ptr());
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("$inline_fn = comdat largest"), "{ir}");
+    assert!(ir.contains("define void @inline_fn() comdat {"), "{ir}");
+}
+
+#[test]
+fn unnamed_addr_round_trips() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = define(&ctx, &module, c"f", void(&ctx), []);
+    let s = f.global_string_ptr(c"hi", c"s");
+    assert_eq!(s.unnamed_addr(), UnnamedAddr::Global);
+    s.set_unnamed_addr(UnnamedAddr::Local);
+    assert_eq!(s.unnamed_addr(), UnnamedAddr::Local);
+    assert_eq!(f.func().unnamed_addr(), UnnamedAddr::None);
+    f.func().set_unnamed_addr(UnnamedAddr::Global);
+    assert_eq!(f.func().unnamed_addr(), UnnamedAddr::Global);
+    f.ret_void();
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains(r#"@s = private local_unnamed_addr constant [3 x i8] c"hi\00""#), "{ir}");
+    assert!(ir.contains("define void @f() unnamed_addr {"), "{ir}");
+
+    s.set_unnamed_addr(UnnamedAddr::None);
+    assert_eq!(s.unnamed_addr(), UnnamedAddr::None);
+}
@@ -0,0 +1,47 @@
+use super::*;
+use inkwell::types::{AnyTypeEnum, BasicTypeEnum};
+use inkwell::values::{AnyValueEnum, AsValueRef, BasicValueEnum, FunctionValue};
+
+#[test]
+fn handles_round_trip_through_inkwell() {
+    let icx = inkwell::context::Context::create();
+    let ctx = Context::from(&icx);
+    assert_eq!(ctx.ptr(), icx.raw());
+
+    let imodule = icx.create_module("m");
+    let module = Module::from(&imodule);
+    assert_eq!(module.ptr(), imodule.as_mut_ptr());
+
+    let i32 = Type::from(BasicTypeEnum::from(icx.i32_type()));
+    assert_eq!(i32, int(&ctx, 32));
+    assert_eq!(AnyTypeEnum::from(i32.clone()), AnyTypeEnum::from(icx.i32_type()));
+
+    let ifun = imodule.add_function("f", icx.i32_type().fn_type(&[], false), None);
+    let fun = Function::from(ifun);
+    assert!(fun.is_declaration());
+    assert_eq!(FunctionValue::from(fun), ifun);
+
+    let ibb = icx.append_basic_block(ifun, "entry");
+    let bb = Block::from(ibb);
+    assert_eq!(bb.ptr(), ibb.as_mut_ptr());
+    assert_eq!(inkwell::basic_block::BasicBlock::from(bb), ibb);
+
+    let ione = icx.i32_type().const_int(1, false);
+    let one = Val::from(BasicValueEnum::from(ione));
+    assert_eq!(one, Kind::const_int(i32, 1, false));
+    assert_eq!(AnyValueEnum::from(one).as_value_ref(), ione.as_value_ref());
+}
+
+#[test]
+fn only_owned_modules_move_into_inkwell() {
+    let icx = inkwell::context::Context::create();
+    let ctx = Context::from(&icx);
+    let module = Module::create_mod(c"owned", &ctx);
+    let shared = module.clone();
+    let module = inkwell::module::Module::try_from(module).unwrap_err();
+    drop(shared);
+
+    let imodule = inkwell::module::Module::try_from(module).unwrap();
+    assert_eq!(imodule.get_name().to_str(), Ok("owned"));
+    assert!(imodule.verify().is_ok());
+}
@@ -0,0 +1,63 @@
+use super::*;
+
+#[test]
+fn add_two_and_three() {
+    let ctx = Context::create_context();
+    let disposed = count_disposal(&ctx);
+    let module = Module::create_mod(c"m", &ctx);
+    let i64 = int(&ctx, 64);
+    let add = define(&ctx, &module, c"add", i64.clone(), [i64.clone(), i64]);
+    let sum = add.Add(add.param(0), add.param(1), c"sum");
+    add.Ret(sum);
+    drop(add);
+
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    drop(ctx);
+    let add = unsafe { jit.lookup_fn::<extern "C" fn(i64, i64) -> i64>(c"add") }.unwrap();
+    assert_eq!(add(2, 3), 5);
+    assert_eq!(disposed.get(), 0);
+    drop(jit);
+    assert_eq!(disposed.get(), 1);
+}
+
+#[test]
+fn shared_modules_are_rejected() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let jit = jit();
+    assert!(jit.add_module(module.clone()).is_err());
+    let leaked = unsafe { Module::from_raw_leaked(module.ptr()) };
+    assert!(jit.add_module(leaked).is_err());
+    assert!(jit.lookup_address(c"missing").is_err());
+}
+
+#[test]
+fn bounds_check_takes_both_paths() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i64 = int(&ctx, 64);
+    let get = define(&ctx, &module, c"get", i64.clone(), [i64.clone()]);
+    let len = Kind::const_int(i64.clone(), 4, false);
+    get.build_bounds_check(get.param(0), len.clone(), |b| {
+        b.Ret(Kind::const_int(i64.clone(), -1i64 as u64, true));
+    });
+    get.Ret(get.Mul(get.param(0), Kind::const_int(i64.clone(), 10, false), c"scaled"));
+    drop(get);
+
+    // Without a terminator from `on_fail`, the failure block is unreachable.
+    let assume = define(&ctx, &module, c"assume", void(&ctx), [i64.clone()]);
+    assume.build_bounds_check(assume.param(0), len, |_| {});
+    assume.ret_void();
+    assert!(format!("{module:?}").contains("unreachable"));
+    verify(&module).unwrap();
+
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    let get = unsafe { jit.lookup_fn::<extern "C" fn(i64) -> i64>(c"get") }.unwrap();
+    assert_eq!(get(0), 0);
+    assert_eq!(get(3), 30);
+    assert_eq!(get(4), -1);
+    // The comparison is unsigned, so negative indices are out of bounds too.
+    assert_eq!(get(-1), -1);
+}
@@ -0,0 +1,36 @@
+use super::*;
+
+pub type Layout<'a> = LLHandle<'a, Normal, llvm_sys::target::LLVMOpaqueTargetData>;
+
+#[test]
+fn struct_layout_under_a_known_layout() {
+    let ctx = Context::create_context();
+    let layout = Layout::from_layout(c"e-i8:8-i32:32-i64:64").unwrap();
+    let s = Type::struct_ty(ctx.clone(), [int(&ctx, 8), int(&ctx, 32)].into_iter(), false);
+    assert_eq!(layout.size_of(s.clone()), 8);
+    assert_eq!(layout.abi_align_of(s.clone()), 4);
+    assert_eq!(layout.offset_of_element(s.clone(), 1), 4);
+    assert_eq!(layout.element_at_offset(s.clone(), 0), 0);
+    assert_eq!(layout.element_at_offset(s, 5), 1);
+
+    let packed = Type::struct_ty(ctx.clone(), [int(&ctx, 8), int(&ctx, 32)].into_iter(), true);
+    assert_eq!(layout.size_of(packed), 5);
+}
+
+#[test]
+fn malformed_layouts_are_errors() {
+    let Err(err) = Layout::from_layout(c"e-i32:abc") else {
+        panic!("malformed layout accepted");
+    };
+    assert!(!err.is_empty());
+    assert!(Layout::from_layout(c"e-\"").is_err());
+}
+
+#[test]
+#[should_panic = "struct has no field 2"]
+fn offset_of_a_missing_field_panics() {
+    let ctx = Context::create_context();
+    let layout = Layout::from_layout(c"e").unwrap();
+    let s = Type::struct_ty(ctx.clone(), [int(&ctx, 8), int(&ctx, 32)].into_iter(), false);
+    layout.offset_of_element(s, 2);
+}
@@ -0,0 +1,65 @@
+use super::*;
+
+const KEYS: [u64; 8] = [3, 17, 256, 1_000, 4_099, 65_537, 1 << 40, u64::MAX];
+
+/// Defines `find(key) -> i32`, returning the position of `key` in `keys` or -1.
+fn define_find<'a>(ctx: &Context<'a>, module: &Module<'a>, key_bits: u32, keys: &[u64]) {
+    let i32 = int(ctx, 32);
+    let f = define(ctx, module, c"find", i32.clone(), [int(ctx, key_bits)]);
+    let (hit, index) = f.build_lookup(f.param(0), keys, c"index");
+    f.build_if(
+        hit,
+        |f| {
+            f.Ret(index);
+        },
+        |f| {
+            f.Ret(Kind::const_i32(ctx.clone(), -1));
+        },
+    );
+}
+
+#[test]
+fn sparse_keys_dispatch_through_the_jit() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    define_find(&ctx, &module, 64, &KEYS);
+    verify(&module).unwrap();
+
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    let find = unsafe { jit.lookup_fn::<extern "C" fn(u64) -> i32>(c"find") }.unwrap();
+    for (i, key) in KEYS.iter().enumerate() {
+        assert_eq!(find(*key), i as i32, "key {key}");
+    }
+    for miss in [0, 1, 2, 4, 18, 255, 999, 65_536, (1 << 40) + 1, u64::MAX - 1] {
+        assert_eq!(find(miss), -1, "key {miss}");
+    }
+}
+
+#[test]
+fn narrow_keys_dispatch_through_the_jit() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    define_find(&ctx, &module, 8, &[1, 9, 200, 255]);
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    let find = unsafe { jit.lookup_fn::<extern "C" fn(u8) -> i32>(c"find") }.unwrap();
+    let found = (0..=255u8).filter_map(|k| (find(k) >= 0).then_some((k, find(k)))).collect::<Vec<_>>();
+    assert_eq!(found, [(1, 0), (9, 1), (200, 2), (255, 3)]);
+}
+
+#[test]
+#[should_panic = "lookup needs at least one key"]
+fn empty_key_sets_are_rejected() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    define_find(&ctx, &module, 64, &[]);
+}
+
+#[test]
+#[should_panic = "lookup key does not fit the key type"]
+fn keys_must_fit_the_key_type() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    define_find(&ctx, &module, 8, &[1, 256]);
+}
@@ -0,0 +1,147 @@
+//! Tests run against the LLVM selected by the enabled `llvm-sys-*` feature.
+//!
+//! Run with e.g. `cargo test -p px-llvm-codegen-utils-core --features llvm-sys-210`.
+//! Tests for APIs that differ between versions are gated on the matching
+//! features.
+#![cfg(any(
+    feature = "llvm-sys-160",
+    feature = "llvm-sys-170",
+    feature = "llvm-sys-180",
+    feature = "llvm-sys-190",
+    feature = "llvm-sys-200",
+    feature = "llvm-sys-210",
+))]
+#![allow(non_snake_case)]
+
+#[cfg(feature = "llvm-sys-210")]
+use llvm_sys_210 as llvm_sys;
+#[cfg(all(feature = "llvm-sys-200", not(feature = "llvm-sys-210")))]
+use llvm_sys_200 as llvm_sys;
+#[cfg(all(feature = "llvm-sys-190", not(any(feature = "llvm-sys-200", feature = "llvm-sys-210"))))]
+use llvm_sys_190 as llvm_sys;
+#[cfg(all(
+    feature = "llvm-sys-180",
+    not(any(feature = "llvm-sys-190", feature = "llvm-sys-200", feature = "llvm-sys-210"))
+))]
+use llvm_sys_180 as llvm_sys;
+#[cfg(all(
+    feature = "llvm-sys-170",
+    not(any(
+        feature = "llvm-sys-180",
+        feature = "llvm-sys-190",
+        feature = "llvm-sys-200",
+        feature = "llvm-sys-210"
+    ))
+))]
+use llvm_sys_170 as llvm_sys;
+#[cfg(all(
+    feature = "llvm-sys-160",
+    not(any(
+        feature = "llvm-sys-170",
+        feature = "llvm-sys-180",
+        feature = "llvm-sys-190",
+        feature = "llvm-sys-200",
+        feature = "llvm-sys-210"
+    ))
+))]
+use llvm_sys_160 as llvm_sys;
+
+use px_llvm_codegen_utils_core::*;
+use std::cell::Cell;
+use std::ffi::CStr;
+use std::rc::Rc;
+
+mod blocks;
+mod calls;
+mod constants;
+mod context;
+mod debug;
+mod eh;
+mod fallible;
+mod function_builder;
+mod gc;
+mod globals;
+#[cfg(feature = "inkwell-interop")]
+mod interop;
+mod jit;
+mod layout;
+mod lookup;
+mod memory;
+mod metadata;
+mod modules;
+mod passes;
+mod target;
+mod types;
+mod values;
+mod version;
+
+pub type Context<'a> = LLHandle<'a, Normal, llvm_sys::LLVMContext>;
+pub type Module<'a> = LLHandle<'a, Normal, llvm_sys::LLVMModule>;
+pub type IrBuilder<'a> = LLHandle<'a, Normal, llvm_sys::LLVMBuilder>;
+pub type Type<'a> = LLHandle<'a, Normal, llvm_sys::LLVMType>;
+pub type Val<'a> = LLHandle<'a, Normal, llvm_sys::LLVMValue>;
+pub type Function<'a> = LLHandle<'a, FuncTag, llvm_sys::LLVMValue>;
+pub type Block<'a> = LLHandle<'a, Normal, llvm_sys::LLVMBasicBlock>;
+pub type Kind = llvm_sys::LLVMValue;
+
+/// Counts how often the context `ctx` has been disposed of, by dropping a
+/// guard along with its diagnostic handler.
+pub fn count_disposal(ctx: &Context) -> Rc<Cell<usize>> {
+    struct Guard(Rc<Cell<usize>>);
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+    let count = Rc::new(Cell::new(0));
+    let guard = Guard(count.clone());
+    ctx.set_diagnostic_handler(move |_| {
+        let _ = &guard;
+    });
+    count
+}
+
+/// Runs the module verifier, returning its message on failure.
+pub fn verify(module: &Module) -> Result<(), String> {
+    use llvm_sys::analysis::*;
+    let mut msg = std::ptr::null_mut();
+    let failed = unsafe {
+        LLVMVerifyModule(module.ptr(), LLVMVerifierFailureAction::LLVMReturnStatusAction, &mut msg) != 0
+    };
+    let text = unsafe { CStr::from_ptr(msg) }.to_string_lossy().into_owned();
+    unsafe { llvm_sys::core::LLVMDisposeMessage(msg) };
+    if failed {
+        return Err(text);
+    }
+    Ok(())
+}
+
+/// Returns the integer type of `bits` bits in `ctx`.
+pub fn int<'a>(ctx: &Context<'a>, bits: u32) -> Type<'a> {
+    Type::int_ty(ctx.clone(), bits)
+}
+
+/// Returns `void` in `ctx`, which the crate has no constructor for.
+pub fn void<'a>(ctx: &Context<'a>) -> Type<'a> {
+    unsafe { Type::from_raw_leaked(llvm_sys::core::LLVMVoidTypeInContext(ctx.ptr())) }
+}
+
+/// Adds the function `name` returning `ret` and taking `params` to `module`,
+/// with a fresh builder positioned in its entry block.
+pub fn define<'a>(
+    ctx: &Context<'a>,
+    module: &Module<'a>,
+    name: &CStr,
+    ret: Type<'a>,
+    params: impl IntoIterator<Item = Type<'a>>,
+) -> FunctionBuilder<'a, IrBuilder<'a>> {
+    let ty = ret.fun_ty(params.into_iter());
+    FunctionBuilder::new(IrBuilder::new_in_ctx(ctx.clone()), module.clone(), name, ty)
+}
+pub type Lljit<'a> = LLHandle<'a, Normal, llvm_sys::orc2::lljit::LLVMOrcOpaqueLLJIT>;
+
+/// Creates a JIT for the host.
+pub fn jit<'a>() -> Lljit<'a> {
+    init_native_target().unwrap();
+    Lljit::create().unwrap()
+}
@@ -0,0 +1,220 @@
+use super::*;
+
+#[test]
+fn mem_set_zeroes_a_stack_buffer() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let (i8, i64) = (int(&ctx, 8), int(&ctx, 64));
+    let f = define(&ctx, &module, c"zeroed", i64.clone(), []);
+    let buf = f.ArrayAlloca(i8.clone(), Kind::const_i64(ctx.clone(), 16), c"buf");
+    buf.set_alignment(16);
+    f.Store(Kind::const_i64(ctx.clone(), -1), buf.clone());
+    f.mem_set(buf.clone(), Kind::const_int(i8, 0, false), Kind::const_i64(ctx.clone(), 16), 16);
+    let word = f.Load2(i64, buf, c"word");
+    f.Ret(word);
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("call void @llvm.memset.p0.i64(ptr align 16 %buf, i8 0, i64 16, i1 false)"), "{ir}");
+
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    let zeroed = unsafe { jit.lookup_fn::<extern "C" fn() -> i64>(c"zeroed") }.unwrap();
+    assert_eq!(zeroed(), 0);
+}
+
+#[test]
+fn mem_cpy_between_allocas() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i64 = int(&ctx, 64);
+    let f = define(&ctx, &module, c"copy", i64.clone(), [i64.clone()]);
+    let src = f.Alloca(i64.clone(), c"src");
+    let dst = f.Alloca(i64.clone(), c"dst");
+    f.Store(f.param(0), src.clone());
+    f.mem_cpy(dst.clone(), 8, src, 8, Kind::const_i64(ctx.clone(), 8));
+    let copied = f.Load2(i64, dst, c"copied");
+    f.Ret(copied);
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("call void @llvm.memcpy.p0.p0.i64(ptr align 8 %dst, ptr align 8 %src, i64 8, i1 false)"), "{ir}");
+
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    let copy = unsafe { jit.lookup_fn::<extern "C" fn(i64) -> i64>(c"copy") }.unwrap();
+    assert_eq!(copy(0x1234_5678_9abc), 0x1234_5678_9abc);
+}
+
+#[test]
+fn intrinsics_are_declared_at_their_overloads() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let ctpop = module.intrinsic(c"llvm.ctpop", [i32.clone()].into_iter()).unwrap();
+    assert_eq!(ctpop.name().unwrap(), "llvm.ctpop.i32");
+    assert!(module.intrinsic(c"llvm.no.such.intrinsic", [].into_iter()).is_none());
+
+    let f = define(&ctx, &module, c"popcount", i32.clone(), [i32.clone()]);
+    let ctpop = unsafe { Val::from_raw_leaked(ctpop.ptr()) };
+    let ones = f.call1(i32.clone().fun_ty([i32].into_iter()), ctpop, f.param(0), c"ones");
+    f.Ret(ones);
+    verify(&module).unwrap();
+}
+
+#[test]
+fn nontemporal_store_is_annotated() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"stream", void(&ctx), [i32, Type::ptr_ty(ctx.clone(), 0)]);
+    f.Store(f.param(0), f.param(1)).set_nontemporal();
+    f.ret_void();
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("store i32 %0, ptr %1, align 4, !nontemporal !0"), "{ir}");
+    assert!(ir.contains("!0 = !{i32 1}"), "{ir}");
+}
+
+#[test]
+fn in_bounds_gep_into_an_array() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let (i32, i64) = (int(&ctx, 32), int(&ctx, 64));
+    let array = Kind::const_data_array(i32.clone(), &[0; 4]).type_of();
+    let f = define(&ctx, &module, c"nth", i32.clone(), [i64.clone()]);
+    let arr = f.Alloca(array.clone(), c"arr");
+    let zero = Kind::const_int(i64, 0, false);
+    let slot = f.gep2_in_bounds(array.clone(), arr.clone(), [zero.clone(), f.param(0)].into_iter(), c"slot");
+    let first = f.gep2(array, arr, [zero.clone(), zero].into_iter(), c"first");
+    f.Store(f.Load2(i32.clone(), first, c"head"), slot.clone());
+    f.Ret(f.Load2(i32, slot, c"elem"));
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("%slot = getelementptr inbounds [4 x i32], ptr %arr, i64 0, i64 %0"), "{ir}");
+    assert!(ir.contains("%first = getelementptr [4 x i32], ptr %arr, i64 0, i64 0"), "{ir}");
+}
+
+#[test]
+fn struct_gep_typed_loads_the_field_type() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let (i8, i64) = (int(&ctx, 8), int(&ctx, 64));
+    let s = Type::struct_ty(ctx.clone(), [i8, i64.clone()].into_iter(), false);
+    let f = define(&ctx, &module, c"second", i64.clone(), [Type::ptr_ty(ctx.clone(), 0)]);
+    let (field, ty) = f.struct_gep_typed(s, f.param(0), 1, c"field");
+    assert_eq!(ty, i64);
+    let value = f.Load2(ty, field, c"value");
+    f.Ret(value);
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("{ i8, i64 }, ptr %0, i32 0, i32 1"), "{ir}");
+    assert!(ir.contains("%value = load i64, ptr %field"), "{ir}");
+}
+
+#[test]
+fn over_aligned_vector_slot() {
+    use llvm_sys::core::*;
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let v4f32 = unsafe { Type::from_raw_leaked(LLVMVectorType(LLVMFloatTypeInContext(ctx.ptr()), 4)) };
+    let f = define(&ctx, &module, c"slot", void(&ctx), []);
+    let slot = f.alloca_aligned(v4f32, 16, c"slot");
+    assert_eq!(unsafe { LLVMGetAlignment(slot.ptr()) }, 16);
+    f.ret_void();
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("%slot = alloca <4 x float>, align 16"), "{ir}");
+}
+
+#[test]
+#[should_panic = "power of two"]
+fn alloca_alignment_must_be_a_power_of_two() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = define(&ctx, &module, c"slot", void(&ctx), []);
+    f.alloca_aligned(int(&ctx, 32), 12, c"slot");
+}
+
+#[test]
+fn runtime_sized_array_alloca() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"last", i32.clone(), [i32.clone()]);
+    let count = f.param(0);
+    let buf = f.ArrayAlloca(i32.clone(), count.clone(), c"buf");
+    let last = f.Sub(count, Kind::const_i32(ctx.clone(), 1), c"last");
+    let slot = f.gep2(i32.clone(), buf, [last].into_iter(), c"slot");
+    f.Store(Kind::const_i32(ctx.clone(), 42), slot.clone());
+    f.Ret(f.Load2(i32, slot, c"value"));
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("%buf = alloca i32, i32 %0"), "{ir}");
+
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    let last = unsafe { jit.lookup_fn::<extern "C" fn(i32) -> i32>(c"last") }.unwrap();
+    assert_eq!(last(8), 42);
+}
+
+#[test]
+fn gep_indices_into_an_array_of_structs() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let pair = Type::struct_ty(ctx.clone(), [i32.clone(), i32.clone()].into_iter(), false);
+    let pairs = Kind::const_array(pair.clone(), std::iter::repeat_n(Kind::const_zero(pair), 10)).type_of();
+    let f = define(&ctx, &module, c"fourth_second", i32.clone(), [Type::ptr_ty(ctx.clone(), 0)]);
+    let field = f.gep_indices(pairs, f.param(0), &[0, 3, 1], c"field");
+    f.Ret(f.Load2(i32, field, c"value"));
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("%field = getelementptr [10 x { i32, i32 }], ptr %0, i64 0, i64 3, i32 1"), "{ir}");
+
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    let get = unsafe { jit.lookup_fn::<extern "C" fn(*const [[i32; 2]; 10]) -> i32>(c"fourth_second") }.unwrap();
+    let mut table = [[0; 2]; 10];
+    table[3][1] = 31;
+    assert_eq!(get(&table), 31);
+}
+
+#[test]
+#[should_panic = "struct has no field 2"]
+fn gep_indices_needs_an_existing_field() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let pair = Type::struct_ty(ctx.clone(), [i32.clone(), i32].into_iter(), false);
+    let f = define(&ctx, &module, c"f", void(&ctx), [Type::ptr_ty(ctx.clone(), 0)]);
+    f.gep_indices(pair, f.param(0), &[0, 2], c"field");
+}
+
+#[test]
+fn field_ptr_returns_the_field_type() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let (i8, i16, i64) = (int(&ctx, 8), int(&ctx, 16), int(&ctx, 64));
+    let s = Type::struct_ty(ctx.clone(), [i8, i16, i64.clone()].into_iter(), false);
+    let f = define(&ctx, &module, c"third", i64, [Type::ptr_ty(ctx.clone(), 0)]);
+    let (field, ty) = f.field_ptr(s, f.param(0), 2, c"field");
+    assert_eq!(ty.int_width(), Some(64));
+    f.Ret(f.Load2(ty, field, c"value"));
+    verify(&module).unwrap();
+
+    #[repr(C)]
+    struct Record(u8, u16, u64);
+    let jit = jit();
+    jit.add_module(module).unwrap();
+    let third = unsafe { jit.lookup_fn::<extern "C" fn(*const Record) -> u64>(c"third") }.unwrap();
+    assert_eq!(third(&Record(1, 2, 0xdead_beef_cafe)), 0xdead_beef_cafe);
+}
+
+#[test]
+#[should_panic = "struct has no field 1"]
+fn field_ptr_needs_an_existing_field() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let s = Type::struct_ty(ctx.clone(), [int(&ctx, 8)].into_iter(), false);
+    let f = define(&ctx, &module, c"f", void(&ctx), [Type::ptr_ty(ctx.clone(), 0)]);
+    f.field_ptr(s, f.param(0), 1, c"field");
+}
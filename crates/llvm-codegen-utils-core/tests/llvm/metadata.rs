@@ -0,0 +1,56 @@
+use super::*;
+
+type Node<'a> = LLHandle<'a, Normal, llvm_sys::LLVMOpaqueMetadata>;
+
+/// Builds the node `!{i32 lo, i32 hi}`.
+fn range<'a>(ctx: &Context<'a>, lo: i32, hi: i32) -> Node<'a> {
+    let bounds = [lo, hi].map(|n| Kind::value_as_metadata(Kind::const_i32(ctx.clone(), n)));
+    Node::md_node(ctx.clone(), bounds.into_iter())
+}
+
+#[test]
+fn range_on_a_load_round_trips() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"digit", i32.clone(), [Type::ptr_ty(ctx.clone(), 0)]);
+    let load = f.Load2(i32, f.param(0), c"digit");
+    f.Ret(load.clone());
+
+    let kind = ctx.metadata_kind_id(c"range");
+    // `range` is one of LLVM's fixed kinds, `MD_range`.
+    assert_eq!(kind, 4);
+    assert!(load.metadata(kind).is_none());
+    let node = range(&ctx, 0, 10);
+    load.set_metadata(kind, node.clone());
+    assert!(load.metadata(kind) == Some(node));
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("%digit = load i32, ptr %0, align 4, !range !0"), "{ir}");
+    assert!(ir.contains("!0 = !{i32 0, i32 10}"), "{ir}");
+}
+
+#[test]
+fn globals_carry_metadata() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let g = Kind::add_constant_global(module.clone(), c"g", Kind::const_i32(ctx.clone(), 7));
+    let (kind, other) = (ctx.metadata_kind_id(c"origin"), ctx.metadata_kind_id(c"unused"));
+    let node = Node::md_node(ctx.clone(), [Node::md_string(ctx.clone(), c"generated")].into_iter());
+    g.set_metadata(kind, node.clone());
+    assert!(g.metadata(kind) == Some(node));
+    assert!(g.metadata(other).is_none());
+    let ir = format!("{module:?}");
+    assert!(ir.contains("@g = private unnamed_addr constant i32 7, !origin !0"), "{ir}");
+    assert!(ir.contains(r#"!0 = !{!"generated"}"#), "{ir}");
+}
+
+#[test]
+#[should_panic = "only instructions and global objects carry metadata"]
+fn arguments_carry_no_metadata() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"f", i32.clone(), [i32]);
+    f.param(0).metadata(ctx.metadata_kind_id(c"range"));
+}
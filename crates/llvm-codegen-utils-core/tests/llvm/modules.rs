@@ -0,0 +1,172 @@
+use super::*;
+
+/// Defines `name` in `module` as returning `callee()` + 1, declaring `callee`.
+fn define_calling<'a>(ctx: &Context<'a>, module: &Module<'a>, name: &CStr, callee: &CStr) {
+    let i32 = int(ctx, 32);
+    let callee_ty = i32.clone().fun_ty([].into_iter());
+    let callee = Kind::declare(module.clone(), callee, callee_ty.clone());
+    let f = define(ctx, module, name, i32.clone(), []);
+    let callee = unsafe { Val::from_raw_leaked(callee.ptr()) };
+    let res = f.call0(callee_ty, callee, c"res");
+    let res = f.Add(res, Kind::const_int(i32, 1, false), c"inc");
+    f.Ret(res);
+}
+
+/// Looks up the function called `name` in `module`.
+fn named<'a>(module: &Module<'a>, name: &CStr) -> Function<'a> {
+    unsafe { Function::from_raw_leaked(llvm_sys::core::LLVMGetNamedFunction(module.ptr(), name.as_ptr())) }
+}
+
+#[test]
+fn link_modules_referencing_each_other() {
+    let ctx = Context::create_context();
+    let a = Module::create_mod(c"a", &ctx);
+    let b = Module::create_mod(c"b", &ctx);
+    define_calling(&ctx, &a, c"ping", c"pong");
+    define_calling(&ctx, &b, c"pong", c"ping");
+    assert!(named(&a, c"pong").is_declaration());
+
+    let linked = a.link(b).unwrap();
+    verify(&linked).unwrap();
+    assert!(!named(&linked, c"ping").is_declaration());
+    assert!(!named(&linked, c"pong").is_declaration());
+    assert_eq!(ctx.modules(), std::slice::from_ref(&linked));
+}
+
+#[test]
+fn link_rejects_shared_and_foreign_modules() {
+    let ctx = Context::create_context();
+    let a = Module::create_mod(c"a", &ctx);
+    let b = Module::create_mod(c"b", &ctx);
+    let err = a.clone().link(b.clone()).unwrap_err();
+    assert!(err.contains("only owning handle"), "{err}");
+
+    let other_ctx = Context::create_context();
+    let foreign = Module::create_mod(c"foreign", &other_ctx);
+    let err = a.link(foreign).unwrap_err();
+    assert!(err.contains("different contexts"), "{err}");
+}
+
+/// Returns the identifier of `module`.
+fn identifier(module: &Module) -> String {
+    let mut len = 0;
+    let id = unsafe { llvm_sys::core::LLVMGetModuleIdentifier(module.ptr(), &mut len) };
+    String::from_utf8_lossy(unsafe { std::slice::from_raw_parts(id as *const u8, len) }).into_owned()
+}
+
+#[test]
+fn modules_are_listed_in_creation_order() {
+    let ctx = Context::create_context();
+    let a = Module::create_mod(c"a", &ctx);
+    let b = Module::create_mod(c"b", &ctx);
+    let c = Module::create_mod(c"c", &ctx);
+    let names: Vec<_> = ctx.modules().iter().map(identifier).collect();
+    assert_eq!(names, ["a", "b", "c"]);
+    assert_eq!(ctx.module(c"b"), Some(b.clone()));
+    assert_eq!(ctx.module(c"d"), None);
+
+    drop(b);
+    assert_eq!(ctx.modules(), [a.clone(), c.clone()]);
+
+    let raw = unsafe { llvm_sys::core::LLVMModuleCreateWithNameInContext(c"raw".as_ptr(), ctx.ptr()) };
+    let leaked = unsafe { Module::from_raw_leaked(raw) };
+    assert_eq!(ctx.modules(), [a, c]);
+    drop(leaked);
+    unsafe { llvm_sys::core::LLVMDisposeModule(raw) };
+}
+
+#[test]
+fn wrap_a_module_built_through_llvm_sys() {
+    use llvm_sys::core::*;
+
+    let ctx = Context::create_context();
+    let i32 = int(&ctx, 32);
+    let (raw, func, entry) = unsafe {
+        let raw = LLVMModuleCreateWithNameInContext(c"raw".as_ptr(), ctx.ptr());
+        let func = LLVMAddFunction(raw, c"one".as_ptr(), i32.clone().fun_ty([].into_iter()).ptr());
+        (raw, func, LLVMAppendBasicBlockInContext(ctx.ptr(), func, c"entry".as_ptr()))
+    };
+    let module = unsafe { Module::from_raw_owned(raw) };
+    assert_eq!(module.ptr(), raw);
+    assert!(ctx.module(c"raw") == Some(module.clone()));
+
+    let func = unsafe { Function::from_raw_leaked(func) };
+    let entry = unsafe { Block::from_raw_leaked(entry) };
+    assert!(func.basic_blocks().next() == Some(entry.clone()));
+    let builder = unsafe { IrBuilder::from_raw_owned(LLVMCreateBuilderInContext(ctx.ptr())) };
+    builder.r#continue(entry);
+    builder.Ret(Kind::const_int(i32, 1, false));
+    drop(builder);
+    verify(&module).unwrap();
+    assert!(format!("{module:?}").contains("ret i32 1"));
+
+    // The only owning handle hands the module back.
+    assert_eq!(module.try_into_raw().ok(), Some(raw));
+    unsafe { LLVMDisposeModule(raw) };
+}
+
+#[test]
+fn cloned_modules_are_independent() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    define_calling(&ctx, &module, c"f", c"callee");
+    let before = format!("{module:?}");
+
+    let copy = module.clone_module();
+    assert!(copy != module);
+    assert!(copy.ctx() == ctx);
+    assert_eq!(format!("{copy:?}"), before);
+    define_calling(&ctx, &copy, c"g", c"callee");
+    named(&copy, c"f").set_name(c"renamed");
+
+    assert_eq!(format!("{module:?}"), before);
+    assert!(format!("{copy:?}").contains("@renamed("));
+    assert!(format!("{copy:?}").contains("@g("));
+    verify(&module).unwrap();
+    verify(&copy).unwrap();
+    drop(module);
+    verify(&copy).unwrap();
+}
+
+#[test]
+fn functions_are_declarations_until_they_get_a_block() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let ty = void(&ctx).fun_ty([].into_iter());
+    let f = Kind::function(module.clone(), c"f", ty.clone());
+    assert!(f.is_declaration());
+    let entry = Block::new(f.clone(), c"entry");
+    assert!(!f.is_declaration());
+
+    let ext = Kind::declare(module.clone(), c"ext", ty.clone());
+    assert!(ext.is_declaration());
+    assert!(Kind::declare(module.clone(), c"ext", ty) == ext);
+    let builder = IrBuilder::new_in_ctx(ctx.clone());
+    builder.r#continue(entry);
+    builder.ret_void();
+    verify(&module).unwrap();
+    assert!(format!("{module:?}").contains("declare void @ext()"));
+}
+
+#[test]
+#[should_panic = "\"f\" is already defined in this module"]
+fn declare_refuses_a_defined_function() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = define(&ctx, &module, c"f", void(&ctx), []);
+    f.ret_void();
+    Kind::declare(module.clone(), c"f", void(&ctx).fun_ty([].into_iter()));
+}
+
+#[test]
+fn module_inline_asm_is_printed() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    assert_eq!(module.inline_asm(), "");
+    module.append_inline_asm(c".globl trampoline");
+    module.append_inline_asm(c"trampoline:\n\tret");
+    assert_eq!(module.inline_asm(), ".globl trampoline\ntrampoline:\n\tret\n");
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("module asm \".globl trampoline\"\nmodule asm \"trampoline:\"\nmodule asm \"\\09ret\""), "{ir}");
+}
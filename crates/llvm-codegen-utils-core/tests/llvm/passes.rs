@@ -0,0 +1,79 @@
+use super::*;
+
+/// Defines `f` as returning its argument through a chain of unconditional
+/// branches, which `simplifycfg` folds into the entry block.
+fn redundant_branches<'a>(ctx: &Context<'a>, module: &Module<'a>) -> Function<'a> {
+    let i32 = int(ctx, 32);
+    let f = define(ctx, module, c"f", i32.clone(), [i32]);
+    let (a, b) = (f.append_block(c"a"), f.append_block(c"b"));
+    f.Br(a.clone());
+    f.position_at_end(a);
+    f.Br(b.clone());
+    f.position_at_end(b);
+    f.Ret(f.param(0));
+    assert_eq!(f.func().basic_blocks().count(), 3);
+    f.func()
+}
+
+#[test]
+fn run_passes_simplifies_the_cfg() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = redundant_branches(&ctx, &module);
+    module.run_passes(c"simplifycfg", None).unwrap();
+    assert_eq!(f.basic_blocks().count(), 1);
+    verify(&module).unwrap();
+}
+
+#[test]
+fn mem2reg_promotes_allocas() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"sum", i32.clone(), [i32.clone(), i32.clone()]);
+    let (a, b) = (f.Alloca(i32.clone(), c"a"), f.Alloca(i32.clone(), c"b"));
+    f.Store(f.param(0), a.clone());
+    f.Store(f.param(1), b.clone());
+    let a = f.Load2(i32.clone(), a, c"a.val");
+    let b = f.Load2(i32, b, c"b.val");
+    f.Ret(f.Add(a, b, c"sum"));
+    assert!(format!("{module:?}").contains("alloca"));
+
+    module.run_passes(c"mem2reg", None).unwrap();
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(!ir.contains("alloca") && !ir.contains("load") && !ir.contains("store"), "{ir}");
+    assert!(ir.contains("%sum = add i32 %0, %1"), "{ir}");
+}
+
+#[test]
+fn unknown_passes_are_errors() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let err = module.run_passes(c"no-such-pass", None).unwrap_err();
+    assert!(err.contains("no-such-pass"), "{err}");
+}
+
+#[test]
+#[cfg(all(
+    feature = "llvm-sys-160",
+    not(any(
+        feature = "llvm-sys-170",
+        feature = "llvm-sys-180",
+        feature = "llvm-sys-190",
+        feature = "llvm-sys-200",
+        feature = "llvm-sys-210"
+    ))
+))]
+fn legacy_pass_manager_simplifies_the_cfg() {
+    type PassManager<'a> = LLHandle<'a, Normal, llvm_sys::LLVMPassManager>;
+
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = redundant_branches(&ctx, &module);
+    let fpm = PassManager::for_module(&module);
+    fpm.add_cfg_simplification_pass();
+    assert!(fpm.run(f.clone()));
+    assert_eq!(f.basic_blocks().count(), 1);
+    verify(&module).unwrap();
+}
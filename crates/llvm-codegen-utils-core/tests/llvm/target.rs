@@ -0,0 +1,42 @@
+use super::*;
+
+type Backend<'a> = LLHandle<'a, Normal, llvm_sys::target_machine::LLVMTarget>;
+
+#[test]
+fn emit_an_object_file_for_the_host() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"answer", i32.clone(), []);
+    f.Ret(Kind::const_int(i32, 42, false));
+
+    init_native_target().unwrap();
+    let triple = default_target_triple();
+    let target = Backend::from_triple(&triple).unwrap();
+    let machine = target
+        .create_target_machine(&triple, c"", c"", CodeGenOptLevel::Default, RelocMode::Pic, CodeModel::Default)
+        .unwrap();
+
+    let path = std::env::temp_dir().join(format!("llvm-codegen-utils-{}.o", std::process::id()));
+    let c_path = std::ffi::CString::new(path.to_str().unwrap()).unwrap();
+    machine.emit_to_file(&module, FileType::Object, &c_path).unwrap();
+    let object = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(!object.is_empty());
+    #[cfg(target_os = "linux")]
+    assert_eq!(object[..4], *b"\x7fELF");
+    assert_eq!(machine.emit_to_memory(&module, FileType::Object).unwrap(), object);
+
+    let asm = machine.emit_to_memory(&module, FileType::Assembly).unwrap();
+    let asm = String::from_utf8(asm).unwrap();
+    assert!(asm.contains("answer"), "{asm}");
+}
+
+#[test]
+fn unknown_triples_are_errors() {
+    init_native_target().unwrap();
+    let Err(err) = Backend::from_triple(c"nonexistent-unknown-none") else {
+        panic!("found a target for a bogus triple");
+    };
+    assert!(!err.is_empty());
+}
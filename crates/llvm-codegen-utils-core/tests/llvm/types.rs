@@ -0,0 +1,81 @@
+use super::*;
+
+#[test]
+fn integer_kinds_and_widths() {
+    let ctx = Context::create_context();
+    for bits in [1, 8, 32, 128, 1000] {
+        let ty = int(&ctx, bits);
+        assert_eq!(ty.kind(), TypeKind::Integer);
+        assert_eq!(ty.int_width(), Some(bits));
+        assert!(ty.is_sized());
+    }
+    let ptr = Type::ptr_ty(ctx.clone(), 0);
+    assert_eq!(ptr.kind(), TypeKind::Pointer);
+    assert_eq!(ptr.int_width(), None);
+    assert_eq!(ptr.element_type(), None);
+    assert_eq!(void(&ctx).kind(), TypeKind::Void);
+    assert!(!void(&ctx).is_sized());
+}
+
+#[test]
+fn array_and_vector_element_types() {
+    let ctx = Context::create_context();
+    let i16 = int(&ctx, 16);
+    let array = Kind::const_data_array(i16.clone(), &[1, 2, 3]).type_of();
+    assert_eq!(array.kind(), TypeKind::Array);
+    assert_eq!(array.element_type(), Some(i16.clone()));
+    assert!(array.is_sized());
+    let vector = unsafe { Type::from_raw_leaked(llvm_sys::core::LLVMVectorType(i16.ptr(), 4)) };
+    assert_eq!(vector.kind(), TypeKind::Vector);
+    assert_eq!(vector.element_type(), Some(i16.clone()));
+    assert_eq!(i16.element_type(), None);
+}
+
+#[test]
+fn struct_fields_are_enumerated_in_order() {
+    let ctx = Context::create_context();
+    let fields = [int(&ctx, 8), Type::ptr_ty(ctx.clone(), 0), int(&ctx, 64)];
+    let record = Type::struct_ty(ctx.clone(), fields.clone().into_iter(), false);
+    assert_eq!(record.kind(), TypeKind::Struct);
+    assert_eq!(record.struct_field_types().unwrap(), fields);
+    assert!(record.is_sized());
+    let empty = Type::struct_ty(ctx.clone(), std::iter::empty(), false);
+    assert_eq!(empty.struct_field_types(), Some(vec![]));
+    assert_eq!(int(&ctx, 8).struct_field_types(), None);
+
+    let opaque = unsafe { llvm_sys::core::LLVMStructCreateNamed(ctx.ptr(), c"Opaque".as_ptr()) };
+    let opaque = unsafe { Type::from_raw_leaked(opaque) };
+    assert_eq!(opaque.kind(), TypeKind::Struct);
+    assert!(!opaque.is_sized());
+}
+
+#[test]
+fn debug_prints_the_ir() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"printed", &ctx);
+    let i32 = int(&ctx, 32);
+    assert_eq!(format!("{i32:?}"), "i32");
+    assert_eq!(format!("{:?}", Kind::const_i32(ctx.clone(), 7)), "i32 7");
+    let ir = format!("{module:?}");
+    assert!(ir.contains("; ModuleID = 'printed'"), "{ir}");
+}
+
+#[test]
+fn function_signatures_are_recovered() {
+    let ctx = Context::create_context();
+    let (i8, i32, ptr) = (int(&ctx, 8), int(&ctx, 32), Type::ptr_ty(ctx.clone(), 0));
+    let ty = i32.clone().fun_ty([i8.clone(), ptr.clone()].into_iter());
+    assert_eq!(ty.kind(), TypeKind::Function);
+    assert_eq!(ty.return_type(), Some(i32.clone()));
+    assert_eq!(ty.count_param_types(), Some(2));
+    let params = ty.param_types().unwrap();
+    assert_eq!(params.iter().map(|p| p.kind()).collect::<Vec<_>>(), [TypeKind::Integer, TypeKind::Pointer]);
+    assert_eq!(params, [i8, ptr.clone()]);
+    assert_eq!(ty.is_var_arg(), Some(false));
+    assert_eq!(i32.clone().var_arg_fun_ty([ptr].into_iter()).is_var_arg(), Some(true));
+
+    assert_eq!(i32.return_type(), None);
+    assert_eq!(i32.count_param_types(), None);
+    assert_eq!(i32.param_types(), None);
+    assert_eq!(i32.is_var_arg(), None);
+}
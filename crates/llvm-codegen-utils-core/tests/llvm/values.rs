@@ -0,0 +1,273 @@
+use super::*;
+
+#[test]
+fn allocated_type_of_a_struct_alloca() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let pair = Type::struct_ty(ctx.clone(), [int(&ctx, 32), int(&ctx, 64)].into_iter(), false);
+    let f = define(&ctx, &module, c"f", void(&ctx), []);
+    let slot = f.Alloca(pair.clone(), c"slot");
+    f.ret_void();
+
+    assert_eq!(slot.type_of().kind(), TypeKind::Pointer);
+    let allocated = slot.allocated_type().unwrap();
+    assert_eq!(allocated, pair);
+    assert_eq!(format!("{allocated:?}"), "{ i32, i64 }");
+    assert!(Kind::const_i32(ctx.clone(), 0).allocated_type().is_none());
+    verify(&module).unwrap();
+}
+
+#[test]
+fn opaque_pointers_have_no_pointee() {
+    let ctx = Context::create_context();
+    assert_eq!(Type::ptr_ty(ctx.clone(), 0).pointee_type(), None);
+    assert_eq!(int(&ctx, 32).pointee_type(), None);
+}
+
+#[test]
+#[cfg(all(
+    feature = "llvm-sys-160",
+    not(any(
+        feature = "llvm-sys-170",
+        feature = "llvm-sys-180",
+        feature = "llvm-sys-190",
+        feature = "llvm-sys-200",
+        feature = "llvm-sys-210"
+    ))
+))]
+fn typed_pointers_report_their_pointee() {
+    let ctx = Context::create_context();
+    unsafe { llvm_sys::core::LLVMContextSetOpaquePointers(ctx.ptr(), 0) };
+    let i32 = int(&ctx, 32);
+    let ptr = unsafe { Type::from_raw_leaked(llvm_sys::core::LLVMPointerType(i32.ptr(), 0)) };
+    assert_eq!(ptr.kind(), TypeKind::Pointer);
+    assert_eq!(ptr.pointee_type(), Some(i32));
+}
+
+#[test]
+#[cfg(any(
+    feature = "llvm-sys-180",
+    feature = "llvm-sys-190",
+    feature = "llvm-sys-200",
+    feature = "llvm-sys-210",
+))]
+fn nneg_and_disjoint_flags_are_printed() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let (i32, i64) = (int(&ctx, 32), int(&ctx, 64));
+    let f = define(&ctx, &module, c"f", i64.clone(), [i32.clone(), i32.clone()]);
+    let hi = f.And(f.param(0), Kind::const_int(i32.clone(), 0x7fff_0000, false), c"hi");
+    let lo = f.And(f.param(1), Kind::const_int(i32.clone(), 0xffff, false), c"lo");
+    let both = f.or_disjoint(hi, lo, c"both");
+    f.Ret(f.zext_nneg(both, i64, c"wide"));
+
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("%both = or disjoint i32 %hi, %lo"), "{ir}");
+    assert!(ir.contains("%wide = zext nneg i32 %both to i64"), "{ir}");
+}
+
+#[test]
+#[cfg(not(any(
+    feature = "llvm-sys-180",
+    feature = "llvm-sys-190",
+    feature = "llvm-sys-200",
+    feature = "llvm-sys-210",
+)))]
+#[should_panic = "`zext nneg` requires LLVM 18 or newer"]
+fn nneg_needs_llvm_18() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = define(&ctx, &module, c"f", int(&ctx, 64), [int(&ctx, 32)]);
+    f.zext_nneg(f.param(0), int(&ctx, 64), c"wide");
+}
+
+#[test]
+fn functions_round_trip_through_a_plain_value() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"id", i32.clone(), [i32]);
+    f.Ret(f.param(0));
+
+    let found = unsafe { llvm_sys::core::LLVMGetNamedFunction(module.ptr(), c"id".as_ptr()) };
+    let found = unsafe { Val::from_raw_leaked(found) };
+    assert_eq!(found.value_kind(), ValueClass::Function);
+    let func = found.as_func().unwrap();
+    assert_eq!(func, f.func());
+    assert_eq!(func.params().count(), 1);
+    assert_eq!(func.ptr(), found.ptr());
+
+    assert_eq!(f.param(0).value_kind(), ValueClass::Argument);
+    assert!(f.param(0).as_func().is_none());
+}
+
+#[test]
+fn freeze_a_poison_value() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"f", i32.clone(), []);
+    let poison = unsafe { Val::from_raw_leaked(llvm_sys::core::LLVMGetPoison(i32.ptr())) };
+    assert_eq!(poison.value_kind(), ValueClass::PoisonValue);
+    let frozen = f.Freeze(poison, c"frozen");
+    assert_eq!(frozen.opcode(), Some(Opcode::Freeze));
+    assert_eq!(frozen.type_of(), i32);
+    f.Ret(frozen);
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("%frozen = freeze i32 poison"), "{ir}");
+}
+
+#[test]
+fn named_parameters_show_up_in_the_ir() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"square", i32.clone(), [i32]);
+    let x = f.param(0);
+    assert_eq!(x.name(), None);
+    x.set_name(c"x");
+    assert_eq!(x.name().as_deref(), Some("x"));
+    f.Ret(f.Mul(x.clone(), x.clone(), c"sq"));
+    f.param(0).set_name(c"");
+    assert_eq!(x.name(), None);
+    x.set_name(c"x");
+
+    let ir = format!("{module:?}");
+    assert!(ir.contains("define i32 @square(i32 %x)"), "{ir}");
+    assert!(ir.contains("%sq = mul i32 %x, %x"), "{ir}");
+    verify(&module).unwrap();
+}
+
+#[test]
+fn constants_report_their_type() {
+    let ctx = Context::create_context();
+    let five = Kind::const_int(int(&ctx, 32), 5, false);
+    let ty = five.type_of();
+    assert_eq!(ty, int(&ctx, 32));
+    assert_eq!(unsafe { llvm_sys::core::LLVMGetIntTypeWidth(ty.ptr()) }, 32);
+    assert_eq!(Kind::const_bool(ctx.clone(), true).type_of().int_width(), Some(1));
+    assert_eq!(Kind::const_i64(ctx.clone(), -1).type_of(), int(&ctx, 64));
+}
+
+#[test]
+#[allow(clippy::mutable_key_type)]
+fn handles_hash_by_identity() {
+    use std::collections::HashSet;
+
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"f", i32.clone(), [i32.clone(), i32.clone()]);
+    let seen = HashSet::from([f.param(0)]);
+    // Each call wraps the argument in a fresh handle.
+    assert!(seen.contains(&f.param(0)));
+    assert!(!seen.contains(&f.param(1)));
+    let raw = unsafe { Val::from_raw_leaked(f.param(0).ptr()) };
+    assert!(seen.contains(&raw));
+
+    // Structurally equal types from different contexts are distinct objects.
+    let other = Context::create_context();
+    let types = HashSet::from([i32.clone(), int(&ctx, 32), int(&other, 32)]);
+    assert_eq!(types.len(), 2);
+}
+
+#[test]
+fn replace_an_add_with_a_constant() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"f", i32.clone(), [i32.clone()]);
+    let a = f.Add(f.param(0), f.param(0), c"a");
+    let b = f.Mul(a.clone(), a.clone(), c"b");
+    f.Ret(b.clone());
+    let four = Kind::const_i32(ctx.clone(), 4);
+    a.replace_all_uses_with(four.clone());
+    assert_eq!(a.uses().count(), 0);
+    assert_eq!(b.operands().collect::<Vec<_>>(), [four.clone(), four]);
+    verify(&module).unwrap();
+    let ir = format!("{module:?}");
+    assert!(ir.contains("%b = mul i32 4, 4"), "{ir}");
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic = "replace_all_uses_with requires a value of the same type"]
+fn replacements_keep_the_type() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let f = define(&ctx, &module, c"f", void(&ctx), [int(&ctx, 32)]);
+    let a = f.Add(f.param(0), f.param(0), c"a");
+    a.replace_all_uses_with(Kind::const_i64(ctx.clone(), 4));
+}
+
+#[test]
+fn operands_and_uses_of_an_add() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"f", i32.clone(), [i32.clone(), i32]);
+    let (x, y) = (f.param(0), f.param(1));
+    let sum = f.Add(x.clone(), y.clone(), c"sum");
+    let square = f.Mul(sum.clone(), sum.clone(), c"square");
+    let ret = f.Ret(square.clone());
+    assert_eq!(sum.operands().collect::<Vec<_>>(), [x.clone(), y]);
+    assert_eq!(sum.uses().collect::<Vec<_>>(), [square.clone(), square.clone()]);
+    assert_eq!(x.uses().collect::<Vec<_>>(), [sum]);
+    assert_eq!(ret.uses().count(), 0);
+    assert_eq!(square.uses().collect::<Vec<_>>(), [ret]);
+
+    let exit = f.append_block(c"exit");
+    f.position_at_end(exit.clone());
+    let br = f.Br(exit.clone());
+    let target = br.operands().collect::<Vec<_>>();
+    assert_eq!(target.len(), 1);
+    assert!(target[0].as_basic_block() == Some(exit));
+}
+
+#[test]
+fn clone_an_add_into_the_same_block() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"f", i32.clone(), [i32]);
+    let sum = f.Add(f.param(0), f.param(0), c"sum");
+    let copy = sum.clone_instruction();
+    assert_ne!(copy, sum);
+    assert_eq!(copy.operands().collect::<Vec<_>>(), [f.param(0), f.param(0)]);
+    f.insert(copy.clone(), c"copy");
+    f.Ret(copy);
+    verify(&module).unwrap();
+    let adds = f.entry_block().instructions().filter(|i| i.opcode() == Some(Opcode::Add)).count();
+    assert_eq!(adds, 2);
+    let ir = format!("{module:?}");
+    assert!(ir.contains("%sum = add i32 %0, %0\n  %copy = add i32 %0, %0\n  ret i32 %copy"), "{ir}");
+}
+
+#[test]
+#[should_panic = "not an instruction"]
+fn only_instructions_can_be_cloned() {
+    let ctx = Context::create_context();
+    Kind::const_i32(ctx.clone(), 1).clone_instruction();
+}
+
+#[test]
+fn instructions_report_their_opcode() {
+    let ctx = Context::create_context();
+    let module = Module::create_mod(c"m", &ctx);
+    let i32 = int(&ctx, 32);
+    let f = define(&ctx, &module, c"f", i32.clone(), [i32.clone()]);
+    let sum = f.Add(f.param(0), f.param(0), c"sum");
+    let slot = f.Alloca(i32.clone(), c"slot");
+    let store = f.Store(sum.clone(), slot.clone());
+    let load = f.Load2(i32, slot.clone(), c"load");
+    let ret = f.Ret(load.clone());
+    assert_eq!(sum.opcode(), Some(Opcode::Add));
+    assert_eq!(slot.opcode(), Some(Opcode::Alloca));
+    assert_eq!(store.opcode(), Some(Opcode::Store));
+    assert_eq!(load.opcode(), Some(Opcode::Load));
+    assert_eq!(ret.opcode(), Some(Opcode::Ret));
+    assert_eq!(f.param(0).opcode(), None);
+    assert_eq!(Kind::const_i32(ctx.clone(), 1).opcode(), None);
+}
@@ -0,0 +1,43 @@
+use super::*;
+
+#[test]
+fn llvm_version_matches_the_feature() {
+    // The newest enabled feature wins, as for the `llvm_sys` alias.
+    let expected = if cfg!(feature = "llvm-sys-210") {
+        (21, 0, 0)
+    } else if cfg!(feature = "llvm-sys-200") {
+        (20, 0, 0)
+    } else if cfg!(feature = "llvm-sys-190") {
+        (19, 0, 0)
+    } else if cfg!(feature = "llvm-sys-180") {
+        (18, 0, 0)
+    } else if cfg!(feature = "llvm-sys-170") {
+        (17, 0, 0)
+    } else {
+        (16, 0, 0)
+    };
+    assert_eq!(LLVM_VERSION, expected);
+}
+
+#[test]
+fn runtime_version_is_at_least_the_selected_one() {
+    let (major, minor, _) = runtime_version();
+    assert!((major, minor) >= (LLVM_VERSION.0, LLVM_VERSION.1), "{major}.{minor}");
+    #[cfg(all(feature = "llvm-sys-160", not(feature = "llvm-sys-170")))]
+    assert_eq!(runtime_version(), LLVM_VERSION);
+}
+
+#[test]
+fn vers_forwards_extra_tokens_to_every_expansion() {
+    macro_rules! record {
+        ($l:ident {} $into:ident) => {
+            $into.extend([stringify!($l)]);
+        };
+    }
+    let mut enabled = vec![];
+    llvm_codegen_utils_version_macros::vers!({} record enabled);
+    assert!(!enabled.is_empty());
+    assert!(enabled.windows(2).all(|w| w[0] < w[1]), "{enabled:?}");
+    let newest = format!("llvm_sys_{}0", LLVM_VERSION.0);
+    assert_eq!(enabled.last(), Some(&&*newest));
+}
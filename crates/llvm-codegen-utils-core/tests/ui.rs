@@ -0,0 +1,6 @@
+//! Compile-fail checks for the thread-safety guarantees of handles.
+
+#[test]
+fn ui() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
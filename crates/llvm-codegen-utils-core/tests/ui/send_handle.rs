@@ -0,0 +1,6 @@
+use px_llvm_codegen_utils_core::{LLHandle, Normal};
+
+fn main() {
+    let handle = unsafe { LLHandle::<'static, Normal, u8>::leaked(std::ptr::null_mut(), Normal) };
+    std::thread::spawn(move || drop(handle));
+}
@@ -25,9 +25,47 @@
 /// Each tuple contains:
 /// - The LLVM major version identifier (e.g., "190" for LLVM 19.0)
 /// - The corresponding `llvm-sys` crate version (e.g., "191")
-pub static LLVMS: &'static [(&'static str, &'static str)] = &[
-    ("190", "191"),
+///
+/// Entries are kept in ascending order of LLVM version:
+///
+/// ```
+/// use px_llvm_codegen_utils_info::LLVMS;
+///
+/// for w in LLVMS.windows(2) {
+///     let a: u32 = w[0].0.parse().unwrap();
+///     let b: u32 = w[1].0.parse().unwrap();
+///     assert!(a < b, "LLVMS is out of order: {} before {}", a, b);
+/// }
+/// ```
+pub static LLVMS: &[(&str, &str)] = &[
+    ("160", "160"),
+    ("170", "170"),
     ("180", "181"),
+    ("190", "191"),
     ("200", "201"),
     ("210", "211"),
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::LLVMS;
+
+    fn version(s: &str) -> u32 {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn versions_ascend_in_both_columns() {
+        for w in LLVMS.windows(2) {
+            assert!(version(w[0].0) < version(w[1].0), "{} listed before {}", w[0].0, w[1].0);
+            assert!(version(w[0].1) < version(w[1].1), "{} listed before {}", w[0].1, w[1].1);
+        }
+    }
+
+    #[test]
+    fn llvm_sys_versions_match_their_llvm_major() {
+        for (llvm, sys) in LLVMS {
+            assert_eq!(version(llvm) / 10, version(sys) / 10, "llvm-sys {sys} does not bind LLVM {llvm}");
+        }
+    }
+}
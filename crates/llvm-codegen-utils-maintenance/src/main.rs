@@ -29,7 +29,7 @@
 //! - `# GEN LL_DEPS` / `# RESUME` - Generate cross-crate LLVM dependencies
 //! - `# GEN VERSION` / `# RESUME` - Synchronize version from version.txt
 
-use std::{fs::FileType, iter::once, path::PathBuf, sync::LazyLock};
+use std::{iter::once, path::PathBuf};
 
 use itertools::Itertools;
 use llvm_codegen_utils_info::LLVMS;
@@ -208,7 +208,7 @@ fn main() -> std::io::Result<()> {
     }
     std::fs::write(format!("{root}/Cargo.toml"), t)?;
     let ver = std::fs::read_to_string(format!("{root}/version.txt"))?;
-    for f in std::fs::read_dir(&format!("{root}/crates"))? {
+    for f in std::fs::read_dir(format!("{root}/crates"))? {
         let Ok(f) = f else {
             continue;
         };
@@ -222,7 +222,7 @@ fn main() -> std::io::Result<()> {
     let xs = LLVMS.iter().map(|(a, _)| {
         quasiquote! {
             #[cfg(feature = #{format!("llvm-sys-{a}")})]
-            $($m)*!(#{format_ident!("llvm_sys_{a}")} {$($contents)*} )
+            $($m)*!(#{format_ident!("llvm_sys_{a}")} {$($contents)*} $($extra)*)
         }
     });
     let contents = quasiquote! {
@@ -231,26 +231,52 @@ fn main() -> std::io::Result<()> {
         /// This macro expands code conditionally based on enabled LLVM version features.
         /// It takes a block of content and a macro name, then invokes the macro for each
         /// enabled LLVM version with the appropriate `llvm_sys_*` module identifier.
+        /// Any tokens after the macro name are forwarded verbatim to every invocation.
         ///
         /// # Usage
         ///
         /// ```ignore
         /// vers!({/* contents */} my_macro);
+        /// vers!({/* contents */} my_macro extra tokens);
         /// ```
         ///
         /// # Expansion
         ///
         /// For each enabled LLVM version feature, this expands to:
         /// ```ignore
-        /// #[cfg(feature = "llvm-sys-190")] my_macro!(llvm_sys_190 { /* contents */ });
-        /// #[cfg(feature = "llvm-sys-180")] my_macro!(llvm_sys_180 { /* contents */ });
+        /// #[cfg(feature = "llvm-sys-190")] my_macro!(llvm_sys_190 { /* contents */ } extra tokens);
+        /// #[cfg(feature = "llvm-sys-180")] my_macro!(llvm_sys_180 { /* contents */ } extra tokens);
         /// // ... and so on for other enabled versions
         /// ```
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use px_llvm_codegen_utils_version_macros::vers;
+        ///
+        /// macro_rules! version_name {
+        ///     ($l:ident {} $name:ident) => {
+        ///         pub const $name: &str = stringify!($l);
+        ///     };
+        /// }
+        ///
+        /// // Defines `LLVM_NAME` once per enabled LLVM version feature.
+        /// vers!({} version_name LLVM_NAME);
+        /// ```
         #[macro_export]
         macro_rules! vers{
-            ({$($contents:tt)*} $($m:tt)*) => {
+            (@split {$($contents:tt)*} [$($m:tt)*] :: $i:ident $($rest:tt)*) => {
+                $crate::vers!(@split {$($contents)*} [$($m)* :: $i] $($rest)*);
+            };
+            (@split {$($contents:tt)*} [] $i:ident $($rest:tt)*) => {
+                $crate::vers!(@split {$($contents)*} [$i] $($rest)*);
+            };
+            (@split {$($contents:tt)*} [$($m:tt)+] $($extra:tt)*) => {
                 #(#xs);*;
-            }
+            };
+            ({$($contents:tt)*} $($rest:tt)*) => {
+                $crate::vers!(@split {$($contents)*} [] $($rest)*);
+            };
         }
     };
     std::fs::write(
@@ -275,7 +301,7 @@ fn main() -> std::io::Result<()> {
             .current_dir(&root)
             .spawn()?
             .wait()?;
-        for f in std::fs::read_dir(&format!("{root}/crates"))? {
+        for f in std::fs::read_dir(format!("{root}/crates"))? {
             let Ok(f) = f else {
                 continue;
             };
@@ -288,10 +314,9 @@ fn main() -> std::io::Result<()> {
             if !f.path().join("Cargo.toml").exists() {
                 continue;
             }
-            match f.file_name().to_str() {
-                Some("llvm-codegen-utils-maintenance") => continue,
-                _ => {}
-            };
+            if let Some("llvm-codegen-utils-maintenance") = f.file_name().to_str() {
+                continue;
+            }
             if !std::process::Command::new("cargo")
                 .arg("publish")
                 .current_dir(f.path())
@@ -313,7 +338,9 @@ fn cargo(root: PathBuf, ver: &str) -> std::io::Result<()> {
     }
     let s = std::fs::read_to_string(&p)?;
     let deps =
-        std::fs::read_to_string(&root.join("llvm-deps.list")).unwrap_or_else(|_| format!(""));
+        std::fs::read_to_string(root.join("llvm-deps.list")).unwrap_or_default();
+    // Crates with an optional inkwell dependency build it for the same LLVM.
+    let inkwell = s.lines().any(|l| l.starts_with("inkwell ") || l.starts_with("inkwell="));
     let mut t = String::default();
     let mut generating = false;
     for l in s.lines() {
@@ -322,7 +349,7 @@ fn cargo(root: PathBuf, ver: &str) -> std::io::Result<()> {
             t += l;
             t += "\n";
             if p.starts_with("LLVM") {
-                for (a, b) in LLVMS.iter() {
+                for (a, _) in LLVMS.iter() {
                     t += &format!("llvm-sys-{a}={{workspace=true,optional=true}}\n");
                 }
             }
@@ -330,6 +357,10 @@ fn cargo(root: PathBuf, ver: &str) -> std::io::Result<()> {
                 for (a, b) in LLVMS.iter() {
                     let x = once(format!("\"dep:llvm-sys-{a}\""))
                         .chain(deps.lines().map(|l| format!("{l}/llvm-sys-{a}")))
+                        .chain(inkwell.then(|| {
+                            let (major, minor) = b.split_at(b.len() - 1);
+                            format!("\"inkwell?/llvm{major}-{minor}\"")
+                        }))
                         .join(",");
                     t += &format!("llvm-sys-{a}=[{x}]\n");
                 }
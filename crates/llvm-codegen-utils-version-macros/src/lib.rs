@@ -23,8 +23,10 @@
 //!
 //! The macro generates code conditionally based on these feature flags:
 //! <!-- GEN FEATURE_FLAGS -->
-//! - `llvm-sys-190` - LLVM 19
+//! - `llvm-sys-160` - LLVM 16
+//! - `llvm-sys-170` - LLVM 17
 //! - `llvm-sys-180` - LLVM 18
+//! - `llvm-sys-190` - LLVM 19
 //! - `llvm-sys-200` - LLVM 20
 //! - `llvm-sys-210` - LLVM 21
 //! <!-- RESUME -->
@@ -3,27 +3,57 @@
 /// This macro expands code conditionally based on enabled LLVM version features.
 /// It takes a block of content and a macro name, then invokes the macro for each
 /// enabled LLVM version with the appropriate `llvm_sys_*` module identifier.
+/// Any tokens after the macro name are forwarded verbatim to every invocation.
 ///
 /// # Usage
 ///
 /// ```ignore
 /// vers!({/* contents */} my_macro);
+/// vers!({/* contents */} my_macro extra tokens);
 /// ```
 ///
 /// # Expansion
 ///
 /// For each enabled LLVM version feature, this expands to:
 /// ```ignore
-/// #[cfg(feature = "llvm-sys-190")] my_macro!(llvm_sys_190 { /* contents */ });
-/// #[cfg(feature = "llvm-sys-180")] my_macro!(llvm_sys_180 { /* contents */ });
+/// #[cfg(feature = "llvm-sys-190")] my_macro!(llvm_sys_190 { /* contents */ } extra tokens);
+/// #[cfg(feature = "llvm-sys-180")] my_macro!(llvm_sys_180 { /* contents */ } extra tokens);
 /// // ... and so on for other enabled versions
 /// ```
+///
+/// # Example
+///
+/// ```
+/// use px_llvm_codegen_utils_version_macros::vers;
+///
+/// macro_rules! version_name {
+///     ($l:ident {} $name:ident) => {
+///         pub const $name: &str = stringify!($l);
+///     };
+/// }
+///
+/// // Defines `LLVM_NAME` once per enabled LLVM version feature.
+/// vers!({} version_name LLVM_NAME);
+/// ```
 #[macro_export]
 macro_rules! vers {
-    ({ $($contents:tt)* } $($m:tt)*) => {
-        #[cfg(feature = "llvm-sys-190")] $($m)*! (llvm_sys_190 { $($contents)* });
-        #[cfg(feature = "llvm-sys-180")] $($m)*! (llvm_sys_180 { $($contents)* });
-        #[cfg(feature = "llvm-sys-200")] $($m)*! (llvm_sys_200 { $($contents)* });
-        #[cfg(feature = "llvm-sys-210")] $($m)*! (llvm_sys_210 { $($contents)* });
+    (@ split { $($contents:tt)* } [$($m:tt)*] :: $i:ident $($rest:tt)*) => {
+        $crate::vers!(@ split { $($contents)* } [$($m)* :: $i] $($rest)*);
+    };
+    (@ split { $($contents:tt)* } [] $i:ident $($rest:tt)*) => {
+        $crate::vers!(@ split { $($contents)* } [$i] $($rest)*);
+    };
+    (@ split { $($contents:tt)* } [$($m:tt)+] $($extra:tt)*) => {
+        #[cfg(feature = "llvm-sys-160")] $($m)*! (llvm_sys_160 { $($contents)* }
+        $($extra)*); #[cfg(feature = "llvm-sys-170")] $($m)*! (llvm_sys_170 {
+        $($contents)* } $($extra)*); #[cfg(feature = "llvm-sys-180")] $($m)*!
+        (llvm_sys_180 { $($contents)* } $($extra)*); #[cfg(feature = "llvm-sys-190")]
+        $($m)*! (llvm_sys_190 { $($contents)* } $($extra)*); #[cfg(feature =
+        "llvm-sys-200")] $($m)*! (llvm_sys_200 { $($contents)* } $($extra)*);
+        #[cfg(feature = "llvm-sys-210")] $($m)*! (llvm_sys_210 { $($contents)* }
+        $($extra)*);
+    };
+    ({ $($contents:tt)* } $($rest:tt)*) => {
+        $crate::vers!(@ split { $($contents)* } [] $($rest)*);
     };
 }